@@ -0,0 +1,185 @@
+use hecs::{Bundle, DynamicBundle, Entity, World};
+use parking_lot::Mutex;
+
+use crate::{MessageContext, MessageResult, Resources, WorldMessage};
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Queue of deferred structural changes to a world
+///
+/// Commands are recorded through a shared reference,
+/// so can be pushed by systems that are holding query borrows,
+/// and are applied in recording order by [`Commands::apply`].
+///
+/// A world's own queue is held as a resource, and is flushed by [`apply_commands`]
+/// after each stage of a [`Schedule`](crate::Schedule).
+/// A standalone queue can also be shipped to another world with [`send_commands`].
+#[derive(Default)]
+pub struct Commands(Mutex<Vec<Command>>);
+
+impl Commands {
+    /// Spawn an entity with the given components
+    pub fn spawn<B: DynamicBundle + Send + 'static>(&self, bundle: B) {
+        self.push(move |world| {
+            world.spawn(bundle);
+        })
+    }
+
+    /// Spawn an entity with the given components at an entity reserved via [`World::reserve_entity`]
+    pub fn spawn_at<B: DynamicBundle + Send + 'static>(&self, entity: Entity, bundle: B) {
+        self.push(move |world| world.spawn_at(entity, bundle))
+    }
+
+    /// Insert components into an entity, replacing any of the same types
+    pub fn insert<B: DynamicBundle + Send + 'static>(&self, entity: Entity, bundle: B) {
+        self.push(move |world| {
+            if world.insert(entity, bundle).is_err() {
+                tracing::warn!(
+                    bundle = std::any::type_name::<B>(),
+                    ?entity,
+                    "Tried to insert into a missing entity"
+                );
+            }
+        })
+    }
+
+    /// Remove components from an entity, dropping them
+    pub fn remove<B: Bundle + 'static>(&self, entity: Entity) {
+        self.push(move |world| {
+            if let Err(e) = world.remove::<B>(entity) {
+                tracing::warn!(
+                    bundle = std::any::type_name::<B>(),
+                    ?entity,
+                    "Failed to remove components: {}",
+                    e
+                );
+            }
+        })
+    }
+
+    /// Despawn an entity and all of its components
+    pub fn despawn(&self, entity: Entity) {
+        self.push(move |world| {
+            if world.despawn(entity).is_err() {
+                tracing::warn!(?entity, "Tried to despawn a missing entity");
+            }
+        })
+    }
+
+    /// Record an arbitrary change to be made with exclusive world access
+    pub fn push<F: FnOnce(&mut World) + Send + 'static>(&self, f: F) {
+        self.0.lock().push(Box::new(f))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.lock().is_empty()
+    }
+
+    /// Move the recorded commands into a new queue, leaving this one empty
+    pub fn take(&self) -> Commands {
+        Commands(Mutex::new(std::mem::take(&mut *self.0.lock())))
+    }
+
+    /// Apply the recorded commands to `world` in recording order
+    pub fn apply(self, world: &mut World) {
+        for command in self.0.into_inner() {
+            command(world)
+        }
+    }
+}
+
+/// Insert an empty [`Commands`] resource if one is not already present
+pub fn init_commands(world: &mut World) {
+    if !world.contains_resource::<Commands>() {
+        world.insert_resource(Commands::default());
+    }
+}
+
+/// Apply and clear the commands recorded in `world`'s [`Commands`] resource
+///
+/// Commands recorded while flushing are left for the next call.
+pub fn apply_commands(world: &mut World) {
+    let commands = if let Ok(commands) = world.get_resource::<Commands>() {
+        commands.take()
+    } else {
+        return;
+    };
+
+    if !commands.is_empty() {
+        tracing::trace!(count = commands.len(), "Applying commands");
+        commands.apply(world);
+    }
+}
+
+/// Send a queue of commands to be applied to world U
+pub fn send_commands<U: Send + 'static>(
+    commands: Commands,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (_, channel) = &mut ctx;
+
+        tracing::debug!(
+            count = commands.len(),
+            receiver = std::any::type_name::<U>(),
+            "Sending commands"
+        );
+
+        channel.send(WorldMessage::to::<U, _>(move |mut ctx: MessageContext| {
+            let (world, _) = &mut ctx;
+            commands.apply(world);
+            Ok(ctx)
+        }))?;
+
+        Ok(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands() {
+        let mut world = World::new();
+        init_commands(&mut world);
+
+        let a = world.spawn((1u32,));
+        let b = world.spawn((2u32, "b"));
+        let reserved = world.reserve_entity();
+
+        for (entity, value) in world.query::<&u32>().iter() {
+            let commands = world.get_resource::<Commands>().unwrap();
+            commands.insert(entity, (*value as u64,));
+            commands.spawn((*value * 10,));
+        }
+
+        {
+            let commands = world.get_resource::<Commands>().unwrap();
+            commands.remove::<(&str,)>(b);
+            commands.despawn(a);
+            commands.despawn(a);
+            commands.spawn_at(reserved, (3u32,));
+            assert_eq!(commands.len(), 8);
+        }
+
+        apply_commands(&mut world);
+        assert!(world.get_resource::<Commands>().unwrap().is_empty());
+
+        assert!(!world.contains(a));
+        assert_eq!(*world.get::<u64>(b).unwrap(), 2);
+        assert!(world.get::<&str>(b).is_err());
+        assert_eq!(*world.get::<u32>(reserved).unwrap(), 3);
+
+        let mut values = world
+            .query::<&u32>()
+            .iter()
+            .map(|(_, value)| *value)
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, [2, 3, 10, 20]);
+    }
+}
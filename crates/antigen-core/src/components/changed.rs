@@ -1,35 +1,61 @@
 use std::{
+    any::TypeId,
     borrow::{Borrow, BorrowMut},
+    collections::{BTreeMap, BTreeSet},
     ops::{Deref, DerefMut},
-    sync::atomic::{AtomicBool, Ordering},
+    sync::atomic::{AtomicU64, Ordering},
 };
 
+use hecs::{Entity, World};
+use parking_lot::Mutex;
+
+use crate::{MissingResource, Resources};
+
+static CHANGE_TICK: AtomicU64 = AtomicU64::new(0);
+
+/// Point in the global sequence of component changes
+///
+/// The default tick precedes every change, and so represents a consumer that has never run.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChangeTick(u64);
+
+impl ChangeTick {
+    /// Advance the global change counter, returning a tick later than any issued before it
+    pub fn advance() -> Self {
+        ChangeTick(CHANGE_TICK.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
 pub struct ChangedFlag(pub bool);
 
-// Changed flag
+/// Change-tracked component
+///
+/// Records the tick at which it was created and the tick at which it was last marked changed,
+/// allowing any number of consumers to detect changes since they last ran.
 #[derive(Debug)]
 pub struct Changed<T> {
     pub data: T,
-    flag: AtomicBool,
+    added: ChangeTick,
+    changed: AtomicU64,
 }
 
+/// Clones are treated as new components, and are marked changed if their source ever was
 impl<T> Clone for Changed<T>
 where
     T: Clone,
 {
     fn clone(&self) -> Self {
-        Changed {
-            data: self.data.clone(),
-            flag: AtomicBool::new(self.flag.load(Ordering::Relaxed)),
-        }
+        Changed::new(self.data.clone(), self.changed.load(Ordering::Relaxed) > 0)
     }
 }
 
 impl<T> Changed<T> {
     pub fn new(data: T, changed: bool) -> Self {
+        let added = ChangeTick::advance();
         Changed {
             data,
-            flag: AtomicBool::new(changed),
+            added,
+            changed: AtomicU64::new(if changed { added.0 } else { 0 }),
         }
     }
 
@@ -64,29 +90,42 @@ impl<T> DerefMut for Changed<T> {
     }
 }
 
-/// A type that can get and set a changed flag
+/// A type that tracks when it was added and last changed
 pub trait ChangedTrait {
-    fn get_changed(&self) -> bool;
-    fn set_changed(&self, dirty: bool);
+    fn added_tick(&self) -> ChangeTick;
+    fn changed_tick(&self) -> ChangeTick;
+    fn mark_changed(&self);
+
+    /// Returns true if this was created after `tick`
+    fn is_added_since(&self, tick: ChangeTick) -> bool {
+        self.added_tick() > tick
+    }
+
+    /// Returns true if this was marked changed after `tick`
+    fn is_changed_since(&self, tick: ChangeTick) -> bool {
+        self.changed_tick() > tick
+    }
 }
 
 impl<T> ChangedTrait for Changed<T> {
-    fn get_changed(&self) -> bool {
-        self.flag.load(Ordering::Relaxed)
+    fn added_tick(&self) -> ChangeTick {
+        self.added
     }
 
-    fn set_changed(&self, dirty: bool) {
-        self.flag.store(dirty, Ordering::Relaxed);
+    fn changed_tick(&self) -> ChangeTick {
+        ChangeTick(self.changed.load(Ordering::Relaxed))
+    }
+
+    fn mark_changed(&self) {
+        self.changed
+            .store(ChangeTick::advance().0, Ordering::Relaxed);
     }
 }
 
 /// Construct implementation
 impl<T> crate::Construct<T, crate::peano::Z> for Changed<T> {
     fn construct(t: T) -> Self {
-        Changed {
-            data: t,
-            flag: false.into(),
-        }
+        Changed::new(t, false)
     }
 }
 
@@ -95,18 +134,48 @@ where
     N: crate::Construct<T, I>,
 {
     fn construct(t: T) -> Self {
-        Changed {
-            data: N::construct(t),
-            flag: false.into(),
+        Changed::new(N::construct(t), false)
+    }
+}
+
+/// Serialized as the wrapped data and whether it has ever been marked changed,
+/// since change ticks are only meaningful within a single process
+impl<T> serde::Serialize for Changed<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Changed", 2)?;
+        state.serialize_field("data", &self.data)?;
+        state.serialize_field("changed", &(self.changed.load(Ordering::Relaxed) > 0))?;
+        state.end()
+    }
+}
+
+impl<'de, T> serde::Deserialize<'de> for Changed<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Changed")]
+        struct Repr<T> {
+            data: T,
+            changed: bool,
         }
+
+        let Repr { data, changed } = Repr::deserialize(deserializer)?;
+        Ok(Changed::new(data, changed))
     }
 }
 
 /// With implementation
 impl<T> crate::With<ChangedFlag, crate::peano::Z> for Changed<T> {
     fn with(self, t: ChangedFlag) -> Self {
+        let changed = if t.0 { self.added.0 } else { 0 };
         Changed {
-            flag: t.0.into(),
+            changed: changed.into(),
             ..self
         }
     }
@@ -123,3 +192,281 @@ where
         }
     }
 }
+
+/// Ticks at which each change consumer in a world last observed changes,
+/// and the entities whose changes each consumer has deferred to its next run
+#[derive(Debug, Default)]
+pub struct ObservedTicks {
+    ticks: Mutex<BTreeMap<(TypeId, Option<Entity>), ChangeTick>>,
+    deferred: Mutex<BTreeMap<TypeId, BTreeSet<Entity>>>,
+}
+
+/// Consumers that have already warned about a missing ObservedTicks resource
+static WARNED_UNOBSERVED: Mutex<BTreeSet<TypeId>> = parking_lot::const_mutex(BTreeSet::new());
+
+/// Insert the ObservedTicks resource into `world` if it does not already have one
+///
+/// Change observation only needs shared world access, so cannot create this on demand;
+/// call this once when setting up a world whose systems observe changes.
+pub fn init_observed_ticks(world: &mut World) {
+    if !world.contains_resource::<ObservedTicks>() {
+        world.insert_resource(ObservedTicks::default());
+    }
+}
+
+fn observe(world: &World, key: (TypeId, Option<Entity>)) -> Result<ChangeTick, MissingResource> {
+    Ok(world
+        .get_resource::<ObservedTicks>()?
+        .ticks
+        .lock()
+        .insert(key, ChangeTick::advance())
+        .unwrap_or_default())
+}
+
+/// Returns the tick at which consumer S last observed changes in `world`,
+/// recording the current tick as its latest observation
+///
+/// S is typically a marker type private to the consuming system.
+/// Fails if `world` has not been initialized with [`init_observed_ticks`].
+pub fn observe_changes<S: 'static>(world: &World) -> Result<ChangeTick, MissingResource> {
+    observe(world, (TypeId::of::<S>(), None))
+}
+
+/// Returns the tick at which consumer S last observed changes to `entity`,
+/// recording the current tick as its latest observation
+pub fn observe_entity_changes<S: 'static>(
+    world: &World,
+    entity: Entity,
+) -> Result<ChangeTick, MissingResource> {
+    observe(world, (TypeId::of::<S>(), Some(entity)))
+}
+
+fn observe_or_warn<S: 'static>(
+    result: Result<ChangeTick, MissingResource>,
+    consumer: &str,
+) -> Option<ChangeTick> {
+    match result {
+        Ok(since) => Some(since),
+        Err(e) => {
+            if WARNED_UNOBSERVED.lock().insert(TypeId::of::<S>()) {
+                tracing::warn!("Skipping {}: {}", consumer, e);
+            }
+            None
+        }
+    }
+}
+
+/// As [`observe_changes`], returning `None` if `world` has not been initialized
+///
+/// Logs a warning naming `consumer` the first time each consumer fails to observe,
+/// so systems can skip their run without flooding the log.
+pub fn observe_changes_or_warn<S: 'static>(world: &World, consumer: &str) -> Option<ChangeTick> {
+    observe_or_warn::<S>(observe_changes::<S>(world), consumer)
+}
+
+/// As [`observe_entity_changes`], returning `None` if `world` has not been initialized
+pub fn observe_entity_changes_or_warn<S: 'static>(
+    world: &World,
+    entity: Entity,
+    consumer: &str,
+) -> Option<ChangeTick> {
+    observe_or_warn::<S>(observe_entity_changes::<S>(world, entity), consumer)
+}
+
+/// Have consumer S treat `entity` as changed on its next run,
+/// such as when a change could not be acted on until another component is ready
+///
+/// Unlike marking the component changed, this is invisible to other consumers.
+pub fn defer_changes<S: 'static>(world: &World, entity: Entity) -> Result<(), MissingResource> {
+    world
+        .get_resource::<ObservedTicks>()?
+        .deferred
+        .lock()
+        .entry(TypeId::of::<S>())
+        .or_default()
+        .insert(entity);
+    Ok(())
+}
+
+/// Take the entities whose changes consumer S deferred on its previous run
+pub fn take_deferred_changes<S: 'static>(
+    world: &World,
+) -> Result<BTreeSet<Entity>, MissingResource> {
+    Ok(world
+        .get_resource::<ObservedTicks>()?
+        .deferred
+        .lock()
+        .remove(&TypeId::of::<S>())
+        .unwrap_or_default())
+}
+
+/// Per-entity query filters over Changed<T> components
+pub mod filter {
+    use super::{ChangeTick, ChangedTrait};
+    use hecs::{Entity, Query, QueryBorrow, QueryItem, World};
+    use std::marker::PhantomData;
+
+    /// A filter over Changed<T> components
+    pub trait ChangeFilter {
+        type Target: hecs::Component;
+
+        fn matches(component: &super::Changed<Self::Target>, since: ChangeTick) -> bool;
+    }
+
+    /// Matches Changed<T> components created since a given tick
+    pub struct Added<T>(PhantomData<T>);
+
+    impl<T: hecs::Component> ChangeFilter for Added<T> {
+        type Target = T;
+
+        fn matches(component: &super::Changed<T>, since: ChangeTick) -> bool {
+            component.is_added_since(since)
+        }
+    }
+
+    /// Matches Changed<T> components created or marked changed since a given tick
+    pub struct Changed<T>(PhantomData<T>);
+
+    impl<T: hecs::Component> ChangeFilter for Changed<T> {
+        type Target = T;
+
+        fn matches(component: &super::Changed<T>, since: ChangeTick) -> bool {
+            component.is_added_since(since) || component.is_changed_since(since)
+        }
+    }
+
+    /// Borrow of query Q, restricted to entities matching filter F
+    pub struct FilteredQuery<'w, Q: Query, F: ChangeFilter> {
+        borrow: QueryBorrow<'w, (Q, &'static super::Changed<F::Target>)>,
+        since: ChangeTick,
+    }
+
+    impl<'w, Q: Query, F: ChangeFilter> FilteredQuery<'w, Q, F> {
+        pub fn iter(&mut self) -> impl Iterator<Item = (Entity, QueryItem<'_, Q>)> + '_ {
+            let since = self.since;
+            self.borrow
+                .iter()
+                .filter(move |(_, (_, component))| F::matches(component, since))
+                .map(|(entity, (item, _))| (entity, item))
+        }
+    }
+
+    /// Extension trait for running filtered queries against a world
+    pub trait QueryFiltered {
+        /// Query Q over entities whose Changed<T> component matches filter F since `since`
+        fn query_filtered<Q: Query, F: ChangeFilter>(
+            &self,
+            since: ChangeTick,
+        ) -> FilteredQuery<'_, Q, F>;
+    }
+
+    impl QueryFiltered for World {
+        fn query_filtered<Q: Query, F: ChangeFilter>(
+            &self,
+            since: ChangeTick,
+        ) -> FilteredQuery<'_, Q, F> {
+            FilteredQuery {
+                borrow: self.query(),
+                since,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::filter::QueryFiltered;
+    use super::*;
+
+    enum First {}
+    enum Second {}
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Value(i32);
+
+    #[test]
+    fn test_change_ticks() {
+        let mut world = World::new();
+        assert_eq!(
+            observe_changes::<First>(&world),
+            Err(MissingResource(std::any::type_name::<ObservedTicks>()))
+        );
+        assert_eq!(observe_changes_or_warn::<First>(&world, "first"), None);
+
+        init_observed_ticks(&mut world);
+        let a = world.spawn((Changed::new(Value(1), false),));
+
+        // Consumers that have never run see components as added, but only flagged ones as changed
+        let first = observe_changes::<First>(&world).unwrap();
+        assert_eq!(first, ChangeTick::default());
+        {
+            let value = world.get::<Changed<Value>>(a).unwrap();
+            assert!(value.is_added_since(first));
+            assert!(!value.is_changed_since(first));
+        }
+
+        world.get::<Changed<Value>>(a).unwrap().mark_changed();
+
+        // Each consumer observes the change independently
+        let second = observe_changes::<Second>(&world).unwrap();
+        let first = observe_changes::<First>(&world).unwrap();
+        {
+            let value = world.get::<Changed<Value>>(a).unwrap();
+            assert!(value.is_changed_since(first));
+            assert!(value.is_changed_since(second));
+        }
+
+        let first = observe_changes::<First>(&world).unwrap();
+        assert!(!world
+            .get::<Changed<Value>>(a)
+            .unwrap()
+            .is_changed_since(first));
+
+        let b = world.spawn((Changed::new(Value(2), false),));
+        assert!(world
+            .get::<Changed<Value>>(b)
+            .unwrap()
+            .is_added_since(first));
+        assert!(observe_entity_changes::<First>(&world, b) == Ok(ChangeTick::default()));
+
+        // Deferred changes are only seen again by the deferring consumer
+        defer_changes::<First>(&world, a).unwrap();
+        assert_eq!(take_deferred_changes::<Second>(&world), Ok(BTreeSet::new()));
+        assert_eq!(
+            take_deferred_changes::<First>(&world),
+            Ok(std::iter::once(a).collect())
+        );
+        assert_eq!(take_deferred_changes::<First>(&world), Ok(BTreeSet::new()));
+        assert!(!world
+            .get::<Changed<Value>>(a)
+            .unwrap()
+            .is_changed_since(observe_changes::<Second>(&world).unwrap()));
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let mut world = World::new();
+        let a = world.spawn((Value(1), Changed::new(Value(1), false)));
+        let since = ChangeTick::advance();
+        let b = world.spawn((Value(2), Changed::new(Value(2), false)));
+
+        let added = world
+            .query_filtered::<&Value, filter::Added<Value>>(since)
+            .iter()
+            .map(|(entity, value)| (entity, *value))
+            .collect::<Vec<_>>();
+        assert_eq!(added, vec![(b, Value(2))]);
+
+        world.get::<Changed<Value>>(a).unwrap().mark_changed();
+
+        let mut changed = world
+            .query_filtered::<(), filter::Changed<Value>>(since)
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        changed.sort();
+        let mut expected = vec![a, b];
+        expected.sort();
+        assert_eq!(changed, expected);
+    }
+}
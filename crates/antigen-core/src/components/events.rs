@@ -0,0 +1,392 @@
+use std::{borrow::Cow, marker::PhantomData};
+
+use hecs::{Component, Entity, World};
+
+use crate::{find_named_entities, Resources, Usage};
+
+/// Double-buffered event queue
+///
+/// Events persist for two calls to [`Events::update`],
+/// so that systems running once per frame will observe every event
+/// regardless of whether they run before or after the sender.
+///
+/// Any number of consumers can read the same events via their own [`EventReader`].
+pub struct Events<T> {
+    previous: Vec<T>,
+    current: Vec<T>,
+    previous_start: usize,
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Events {
+            previous: Default::default(),
+            current: Default::default(),
+            previous_start: 0,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Events<T>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Events")
+            .field("previous", &self.previous)
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+impl<T> Extend<T> for Events<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.current.extend(iter)
+    }
+}
+
+impl<T> Events<T> {
+    pub fn send(&mut self, event: T) {
+        self.current.push(event)
+    }
+
+    /// Discard events from before the last update, and begin a new buffer
+    pub fn update(&mut self) {
+        self.previous_start += self.previous.len();
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Iterate over all buffered events, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.previous.iter().chain(self.current.iter())
+    }
+
+    /// Remove all buffered events, oldest first
+    ///
+    /// Readers will not observe drained events.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.previous_start = self.event_count();
+        self.previous.drain(..).chain(self.current.drain(..))
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.previous.is_empty() && self.current.is_empty()
+    }
+
+    /// Create a reader that will only observe events sent after this call
+    pub fn reader(&self) -> EventReader<T> {
+        EventReader {
+            cursor: self.event_count(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn event_count(&self) -> usize {
+        self.previous_start + self.len()
+    }
+}
+
+/// Cursor into an [`Events`] queue, tracking which events a consumer has already read
+///
+/// A default reader will observe every event still buffered on its first read.
+pub struct EventReader<T> {
+    cursor: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EventReader<T> {
+    fn default() -> Self {
+        EventReader {
+            cursor: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for EventReader<T> {
+    fn clone(&self) -> Self {
+        EventReader {
+            cursor: self.cursor,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for EventReader<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EventReader").field(&self.cursor).finish()
+    }
+}
+
+impl<T> EventReader<T> {
+    /// Iterate over events sent since the last read, oldest first
+    pub fn read<'a>(&mut self, events: &'a Events<T>) -> impl Iterator<Item = &'a T> {
+        let skip = self.cursor.saturating_sub(events.previous_start);
+        self.cursor = events.event_count();
+        events.iter().skip(skip)
+    }
+}
+
+// Event Input
+pub enum EventInput {}
+/// Per-entity queue of received events
+pub type EventInputComponent<T> = Usage<EventInput, Events<T>>;
+
+// Event Output
+pub enum EventOutput {}
+/// Per-entity list of events to be dispatched to its [`EventTargetComponent`]
+pub type EventOutputComponent<T> = Usage<EventOutput, Vec<T>>;
+
+/// Recipient of an entity's output events
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventTargetId {
+    /// Every entity whose name matches a pattern in the [`NamedEntitiesComponent`](crate::NamedEntitiesComponent)
+    ///
+    /// See [`find_named_entities`] for the pattern syntax.
+    Name(Cow<'static, str>),
+    Entity(Entity),
+}
+
+impl From<&'static str> for EventTargetId {
+    fn from(name: &'static str) -> Self {
+        EventTargetId::Name(name.into())
+    }
+}
+
+impl From<String> for EventTargetId {
+    fn from(name: String) -> Self {
+        EventTargetId::Name(name.into())
+    }
+}
+
+impl From<Cow<'static, str>> for EventTargetId {
+    fn from(name: Cow<'static, str>) -> Self {
+        EventTargetId::Name(name)
+    }
+}
+
+impl From<Entity> for EventTargetId {
+    fn from(entity: Entity) -> Self {
+        EventTargetId::Entity(entity)
+    }
+}
+
+// Event Target
+pub struct EventTarget<T>(PhantomData<T>);
+pub type EventTargetComponent<T> = Usage<EventTarget<T>, EventTargetId>;
+
+/// Marks an entity whose input events of type I are to be transformed into output events of type O
+pub struct EventTransformComponent<I, O> {
+    reader: EventReader<I>,
+    _phantom: PhantomData<fn() -> O>,
+}
+
+impl<I, O> Default for EventTransformComponent<I, O> {
+    fn default() -> Self {
+        EventTransformComponent {
+            reader: Default::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl EventTransformComponent<(), ()> {
+    pub fn unit() -> Self {
+        Default::default()
+    }
+}
+
+impl<I, O> EventTransformComponent<I, O> {
+    pub fn with_input_type<T>(self) -> EventTransformComponent<T, O> {
+        Default::default()
+    }
+
+    pub fn with_output_type<T>(self) -> EventTransformComponent<I, T> {
+        Default::default()
+    }
+}
+
+/// Resolve an event target to the entities it refers to
+///
+/// Unknown names resolve to no entities.
+fn event_target_entities(world: &World, target: &EventTargetId) -> Vec<Entity> {
+    match target {
+        EventTargetId::Entity(entity) => vec![*entity],
+        EventTargetId::Name(name) => find_named_entities(world, name).unwrap_or_default(),
+    }
+}
+
+/// Move output events of type T into the input queues of their targets
+///
+/// Events addressed to a missing target, or to one with no input queue, are dropped with a warning.
+pub fn event_dispatch_system<T>(world: &World)
+where
+    T: Component + Clone,
+{
+    let mut query = world.query::<(&EventTargetComponent<T>, &mut EventOutputComponent<T>)>();
+    for (entity, (target, output)) in query.iter() {
+        if output.is_empty() {
+            continue;
+        }
+
+        let targets = event_target_entities(world, target);
+        if targets.is_empty() {
+            tracing::warn!(
+                event = std::any::type_name::<T>(),
+                ?entity,
+                target = ?**target,
+                "Dropping events for unknown target"
+            );
+        }
+
+        for target in targets {
+            match world.get_mut::<EventInputComponent<T>>(target) {
+                Ok(mut input) => input.extend(output.iter().cloned()),
+                Err(_) => tracing::warn!(
+                    event = std::any::type_name::<T>(),
+                    ?entity,
+                    ?target,
+                    "Dropping events for target without an input queue"
+                ),
+            }
+        }
+
+        output.clear();
+    }
+}
+
+/// Map unread input events of type I into output events of type O
+/// for entities with an [`EventTransformComponent<I, O>`]
+pub fn event_transform_system<I, O, F>(world: &World, mut f: F)
+where
+    I: Component,
+    O: Component,
+    F: FnMut(&I) -> O,
+{
+    for (_, (transform, input, output)) in world
+        .query::<(
+            &mut EventTransformComponent<I, O>,
+            &EventInputComponent<I>,
+            &mut EventOutputComponent<O>,
+        )>()
+        .iter()
+    {
+        output.extend(transform.reader.read(input).map(&mut f));
+    }
+}
+
+/// Advance the event queues of type T, including any [`Events<T>`] resource,
+/// and clear undispatched output events
+///
+/// Should be run once per frame.
+pub fn update_events_system<T: Component>(world: &World) {
+    if let Ok(mut events) = world.get_resource_mut::<Events<T>>() {
+        events.update();
+    }
+
+    for (_, input) in world.query::<&mut EventInputComponent<T>>().iter() {
+        input.update();
+    }
+
+    for (_, output) in world.query::<&mut EventOutputComponent<T>>().iter() {
+        output.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Construct, NamedEntitiesComponent, Resources};
+
+    #[test]
+    fn test_event_readers() {
+        let mut events = Events::default();
+        let mut early = EventReader::default();
+
+        events.send(1);
+        events.send(2);
+        let mut late = events.reader();
+        assert_eq!(early.read(&events).copied().collect::<Vec<_>>(), [1, 2]);
+
+        events.update();
+        events.send(3);
+        assert_eq!(early.read(&events).copied().collect::<Vec<_>>(), [3]);
+        assert_eq!(late.read(&events).copied().collect::<Vec<_>>(), [3]);
+
+        // Events are discarded after two updates
+        let mut fresh = EventReader::default();
+        events.update();
+        events.update();
+        events.send(4);
+        assert_eq!(fresh.read(&events).copied().collect::<Vec<_>>(), [4]);
+        assert_eq!(early.read(&events).copied().collect::<Vec<_>>(), [4]);
+        assert_eq!(early.read(&events).count(), 0);
+
+        assert_eq!(events.drain().collect::<Vec<_>>(), [4]);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_event_routing() {
+        let mut world = World::new();
+
+        let named = world.spawn((EventInputComponent::<u32>::construct(Default::default()),));
+        let direct = world.spawn((EventInputComponent::<u32>::construct(Default::default()),));
+
+        let mut named_entities = NamedEntitiesComponent::construct(Default::default());
+        named_entities
+            .entry("named".into())
+            .or_default()
+            .insert(named);
+        world.insert_resource(named_entities);
+
+        for target in [
+            EventTargetId::from("named"),
+            EventTargetId::from(direct),
+            EventTargetId::from("missing"),
+        ] {
+            world.spawn((
+                EventTargetComponent::<u32>::construct(target),
+                EventOutputComponent::<u32>::construct(vec![1, 2]),
+            ));
+        }
+
+        // Transform the named entity's input back into output for the direct one
+        world
+            .insert(
+                named,
+                (
+                    EventTransformComponent::unit()
+                        .with_input_type::<u32>()
+                        .with_output_type::<u32>(),
+                    EventOutputComponent::<u32>::construct(vec![]),
+                    EventTargetComponent::<u32>::construct(direct.into()),
+                ),
+            )
+            .unwrap();
+
+        event_dispatch_system::<u32>(&world);
+        event_transform_system::<u32, u32, _>(&world, |event| event * 10);
+        event_dispatch_system::<u32>(&world);
+        event_transform_system::<u32, u32, _>(&world, |event| event * 10);
+
+        let inputs = |entity| {
+            world
+                .get::<EventInputComponent<u32>>(entity)
+                .unwrap()
+                .iter()
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(inputs(named), [1, 2]);
+        assert_eq!(inputs(direct), [1, 2, 10, 20]);
+
+        update_events_system::<u32>(&world);
+        update_events_system::<u32>(&world);
+        assert!(inputs(direct).is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+use hecs::{Entity, World};
+
+use crate::{Commands, Construct, Usage};
+
+// Parent
+pub enum Parent {}
+pub type ParentComponent = Usage<Parent, Entity>;
+
+// Children
+pub enum Children {}
+pub type ChildrenComponent = Usage<Children, Vec<Entity>>;
+
+/// Error produced when a hierarchy change cannot be made
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HierarchyError {
+    NoSuchEntity(Entity),
+    /// The new parent is the child itself, or one of its descendants
+    Cycle {
+        child: Entity,
+        parent: Entity,
+    },
+}
+
+impl std::fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HierarchyError::NoSuchEntity(entity) => write!(f, "No such entity {:?}", entity),
+            HierarchyError::Cycle { child, parent } => write!(
+                f,
+                "Parenting {:?} to {:?} would create a cycle",
+                child, parent
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+/// Returns the parent of `entity`, if it has one
+pub fn get_parent(world: &World, entity: Entity) -> Option<Entity> {
+    world
+        .get::<ParentComponent>(entity)
+        .ok()
+        .map(|parent| **parent)
+}
+
+/// Returns the children of `entity` in the order they were added
+pub fn get_children(world: &World, entity: Entity) -> Vec<Entity> {
+    world
+        .get::<ChildrenComponent>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default()
+}
+
+/// Attach `child` to `parent`, detaching it from any existing parent
+pub fn set_parent(world: &mut World, child: Entity, parent: Entity) -> Result<(), HierarchyError> {
+    for entity in [child, parent] {
+        if !world.contains(entity) {
+            return Err(HierarchyError::NoSuchEntity(entity));
+        }
+    }
+
+    let mut ancestor = Some(parent);
+    while let Some(entity) = ancestor {
+        if entity == child {
+            return Err(HierarchyError::Cycle { child, parent });
+        }
+        ancestor = get_parent(world, entity);
+    }
+
+    remove_parent(world, child);
+
+    world
+        .insert_one(child, ParentComponent::construct(parent))
+        .unwrap();
+
+    push_child(world, parent, child);
+
+    Ok(())
+}
+
+fn push_child(world: &mut World, parent: Entity, child: Entity) {
+    let pushed = world
+        .get_mut::<ChildrenComponent>(parent)
+        .map(|mut children| children.push(child))
+        .is_ok();
+
+    if !pushed {
+        world
+            .insert_one(parent, ChildrenComponent::construct(vec![child]))
+            .unwrap();
+    }
+}
+
+/// Detach `child` from its parent, returning the parent if it had one
+pub fn remove_parent(world: &mut World, child: Entity) -> Option<Entity> {
+    let parent = *world.remove_one::<ParentComponent>(child).ok()?;
+
+    if let Ok(mut children) = world.get_mut::<ChildrenComponent>(parent) {
+        children.retain(|entity| *entity != child);
+    }
+
+    Some(parent)
+}
+
+/// Despawn `entity` along with all of its descendants, detaching it from its parent
+pub fn despawn_recursive(world: &mut World, entity: Entity) -> Result<(), HierarchyError> {
+    if !world.contains(entity) {
+        return Err(HierarchyError::NoSuchEntity(entity));
+    }
+
+    remove_parent(world, entity);
+
+    let mut pending = vec![entity];
+    while let Some(entity) = pending.pop() {
+        pending.extend(get_children(world, entity));
+        world.despawn(entity).ok();
+    }
+
+    Ok(())
+}
+
+/// Repair hierarchy links left dangling by despawning entities directly
+///
+/// Children of a despawned parent become roots,
+/// and children lists are made to match the parent components pointing at them.
+pub fn hierarchy_maintenance_system(world: &mut World) {
+    let commands = Commands::default();
+
+    for (entity, parent) in world.query::<&ParentComponent>().iter() {
+        let parent = **parent;
+        if !world.contains(parent) {
+            commands.remove::<(ParentComponent,)>(entity);
+            continue;
+        }
+
+        let listed = world
+            .get::<ChildrenComponent>(parent)
+            .map(|children| children.contains(&entity))
+            .unwrap_or_default();
+
+        if !listed {
+            commands.push(move |world| push_child(world, parent, entity));
+        }
+    }
+
+    for (entity, children) in world.query::<&mut ChildrenComponent>().iter() {
+        children.retain(|child| get_parent(world, *child) == Some(entity));
+        if children.is_empty() {
+            commands.remove::<(ChildrenComponent,)>(entity);
+        }
+    }
+
+    commands.apply(world);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hierarchy() {
+        let mut world = World::new();
+        let root = world.spawn(());
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        set_parent(&mut world, a, root).unwrap();
+        set_parent(&mut world, b, root).unwrap();
+        set_parent(&mut world, c, a).unwrap();
+        assert_eq!(get_children(&world, root), [a, b]);
+        assert_eq!(get_parent(&world, c), Some(a));
+
+        assert_eq!(
+            set_parent(&mut world, root, c),
+            Err(HierarchyError::Cycle {
+                child: root,
+                parent: c
+            })
+        );
+
+        // Reparenting detaches from the previous parent
+        set_parent(&mut world, c, b).unwrap();
+        assert!(get_children(&world, a).is_empty());
+        assert_eq!(get_children(&world, b), [c]);
+
+        // Plain despawns are repaired by the maintenance system
+        world.despawn(b).unwrap();
+        hierarchy_maintenance_system(&mut world);
+        assert_eq!(get_children(&world, root), [a]);
+        assert_eq!(get_parent(&world, c), None);
+        assert!(world.get::<ChildrenComponent>(a).is_err());
+
+        set_parent(&mut world, c, a).unwrap();
+        despawn_recursive(&mut world, a).unwrap();
+        assert!(!world.contains(a));
+        assert!(!world.contains(c));
+        assert!(get_children(&world, root).is_empty());
+    }
+}
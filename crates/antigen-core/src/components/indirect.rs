@@ -1,11 +1,47 @@
 use std::marker::PhantomData;
 
-use hecs::{Component, Entity, Fetch, Query, QueryOne, World};
+use hecs::{Component, Entity, Fetch, Query, QueryOne, QueryOneError, World};
 
-use crate::{peano::Z, Construct};
+use crate::{peano::Z, Construct, Usage};
 
+/// Error produced when an indirect reference does not resolve
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IndirectError {
+    /// The target entity has been despawned
+    NoSuchEntity(Entity),
+    /// The target entity exists, but does not satisfy the indirect query
+    Unsatisfied { entity: Entity, query: &'static str },
+}
+
+impl IndirectError {
+    pub fn entity(&self) -> Entity {
+        match self {
+            IndirectError::NoSuchEntity(entity) => *entity,
+            IndirectError::Unsatisfied { entity, .. } => *entity,
+        }
+    }
+}
+
+impl std::fmt::Display for IndirectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndirectError::NoSuchEntity(entity) => write!(f, "No such entity {:?}", entity),
+            IndirectError::Unsatisfied { entity, query } => {
+                write!(f, "Entity {:?} does not satisfy query {}", entity, query)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IndirectError {}
+
+/// Reference to the components of another entity, accessed via query T
+///
+/// Weak references are expected to outlive their target,
+/// so a despawned target is not considered dangling.
 pub struct Indirect<T> {
     entity: Entity,
+    weak: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -13,30 +49,80 @@ impl<T> Construct<Entity, Z> for Indirect<T> {
     fn construct(entity: Entity) -> Self {
         Indirect {
             entity,
+            weak: false,
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<T> Indirect<T> {
+    /// Construct a weak reference to `entity`
+    pub fn weak(entity: Entity) -> Self {
+        Indirect {
+            entity,
+            weak: true,
             _phantom: Default::default(),
         }
     }
+
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// Returns true if the target entity has not been despawned
+    pub fn is_alive(&self, world: &World) -> bool {
+        world.contains(self.entity)
+    }
 }
 
 impl<T> Indirect<T>
 where
     T: Query + Component,
 {
-    pub fn entity(&self) -> Entity {
-        self.entity
+    pub fn get<'a>(&self, world: &'a World) -> Result<QueryOne<'a, T>, IndirectError> {
+        world
+            .query_one::<T>(self.entity)
+            .map_err(|_| IndirectError::NoSuchEntity(self.entity))
+    }
+
+    pub fn get_mut<'a>(
+        &self,
+        world: &'a mut World,
+    ) -> Result<<<T as Query>::Fetch as Fetch<'a>>::Item, IndirectError> {
+        world.query_one_mut::<T>(self.entity).map_err(|e| match e {
+            QueryOneError::NoSuchEntity => IndirectError::NoSuchEntity(self.entity),
+            QueryOneError::Unsatisfied => self.unsatisfied(),
+        })
     }
 
-    pub fn get<'a>(&self, world: &'a World) -> QueryOne<'a, T> {
-        world.query_one::<T>(self.entity).unwrap()
+    /// Check that the target exists and satisfies T
+    ///
+    /// Borrows the target's components, so must not be called while they are mutably borrowed.
+    pub fn validate(&self, world: &World) -> Result<(), IndirectError> {
+        if self.get(world)?.get().is_some() {
+            Ok(())
+        } else {
+            Err(self.unsatisfied())
+        }
     }
 
-    pub fn get_mut<'a>(&self, world: &'a mut World) -> <<T as Query>::Fetch as Fetch<'a>>::Item {
-        world.query_one_mut::<T>(self.entity).unwrap()
+    /// The error for a target that exists but does not satisfy T
+    pub fn unsatisfied(&self) -> IndirectError {
+        IndirectError::Unsatisfied {
+            entity: self.entity,
+            query: std::any::type_name::<T>(),
+        }
     }
 }
 
+/// Reference to the components of several other entities, accessed via query T
 pub struct IndirectMulti<T> {
     entities: Vec<Entity>,
+    weak: bool,
     _phantom: PhantomData<T>,
 }
 
@@ -44,16 +130,300 @@ impl<T> Construct<Vec<Entity>, Z> for IndirectMulti<T> {
     fn construct(entities: Vec<Entity>) -> Self {
         IndirectMulti {
             entities,
+            weak: false,
+            _phantom: Default::default(),
+        }
+    }
+}
+
+impl<T> IndirectMulti<T> {
+    /// Construct a weak reference to `entities`
+    pub fn weak(entities: Vec<Entity>) -> Self {
+        IndirectMulti {
+            entities,
+            weak: true,
             _phantom: Default::default(),
         }
     }
+
+    pub fn entities(&self) -> &Vec<Entity> {
+        &self.entities
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Remove despawned targets, returning the number removed
+    pub fn retain_alive(&mut self, world: &World) -> usize {
+        let len = self.entities.len();
+        self.entities.retain(|entity| world.contains(*entity));
+        len - self.entities.len()
+    }
 }
 
 impl<T> IndirectMulti<T>
 where
     T: Query + Component,
 {
-    pub fn entities(&self) -> &Vec<Entity> {
-        &self.entities
+    /// Query the target at `index`, or [`None`] if out of range
+    pub fn get<'a>(
+        &self,
+        world: &'a World,
+        index: usize,
+    ) -> Option<Result<QueryOne<'a, T>, IndirectError>> {
+        self.entities
+            .get(index)
+            .map(|entity| Indirect::<T>::construct(*entity).get(world))
+    }
+
+    /// Query each target in order
+    pub fn iter<'a>(
+        &'a self,
+        world: &'a World,
+    ) -> impl Iterator<Item = Result<QueryOne<'a, T>, IndirectError>> + 'a {
+        self.entities
+            .iter()
+            .map(move |entity| Indirect::<T>::construct(*entity).get(world))
+    }
+}
+
+/// Component data containing indirect references that can be checked for dangling targets
+///
+/// Tuples validate their first element,
+/// matching the `(reference, parameters..)` layout used by pass components.
+pub trait IndirectReferences {
+    /// Push an error for each reference that does not resolve in `world`,
+    /// including weak references to despawned targets if `include_weak` is set
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>);
+
+    /// Push an error for each dangling reference,
+    /// ignoring weak references whose target has been despawned
+    fn validate_indirect(&self, world: &World, errors: &mut Vec<IndirectError>) {
+        self.check_indirect(world, false, errors)
+    }
+
+    /// Push an error for each reference that cannot currently be accessed, weak or not
+    fn validate_indirect_access(&self, world: &World, errors: &mut Vec<IndirectError>) {
+        self.check_indirect(world, true, errors)
+    }
+}
+
+impl<T> IndirectReferences for Indirect<T>
+where
+    T: Query + Component,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        match self.validate(world) {
+            Err(IndirectError::NoSuchEntity(_)) if self.weak && !include_weak => (),
+            Err(e) => errors.push(e),
+            Ok(()) => (),
+        }
+    }
+}
+
+impl<T> IndirectReferences for IndirectMulti<T>
+where
+    T: Query + Component,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        for entity in &self.entities {
+            let indirect = Indirect::<T> {
+                entity: *entity,
+                weak: self.weak,
+                _phantom: PhantomData,
+            };
+            indirect.check_indirect(world, include_weak, errors);
+        }
+    }
+}
+
+impl<T> IndirectReferences for &T
+where
+    T: IndirectReferences,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        (**self).check_indirect(world, include_weak, errors)
+    }
+}
+
+impl<U, T> IndirectReferences for Usage<U, T>
+where
+    T: IndirectReferences,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        (**self).check_indirect(world, include_weak, errors)
+    }
+}
+
+impl<T> IndirectReferences for Option<T>
+where
+    T: IndirectReferences,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        if let Some(inner) = self {
+            inner.check_indirect(world, include_weak, errors)
+        }
+    }
+}
+
+impl<T> IndirectReferences for Vec<T>
+where
+    T: IndirectReferences,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        for inner in self {
+            inner.check_indirect(world, include_weak, errors)
+        }
+    }
+}
+
+impl<A, B> IndirectReferences for (A, B)
+where
+    A: IndirectReferences,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        self.0.check_indirect(world, include_weak, errors)
+    }
+}
+
+impl<A, B, C> IndirectReferences for (A, B, C)
+where
+    A: IndirectReferences,
+{
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        self.0.check_indirect(world, include_weak, errors)
+    }
+}
+
+/// Indirect reference that failed to resolve, along with the component holding it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DanglingIndirect {
+    pub entity: Entity,
+    pub component: &'static str,
+    pub error: IndirectError,
+}
+
+impl std::fmt::Display for DanglingIndirect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} on entity {:?}: {}",
+            self.component, self.entity, self.error
+        )
+    }
+}
+
+/// Collect every dangling reference held by components of type C
+pub fn dangling_indirect<C>(world: &World) -> Vec<DanglingIndirect>
+where
+    C: Component + IndirectReferences,
+{
+    let mut dangling = vec![];
+    let mut errors = vec![];
+    for (entity, component) in world.query::<&C>().iter() {
+        component.validate_indirect(world, &mut errors);
+        dangling.extend(errors.drain(..).map(|error| DanglingIndirect {
+            entity,
+            component: std::any::type_name::<C>(),
+            error,
+        }));
+    }
+    dangling
+}
+
+/// Warn about every dangling reference held by components of type C
+pub fn validate_indirect_system<C>(world: &World)
+where
+    C: Component + IndirectReferences,
+{
+    for dangling in dangling_indirect::<C>(world) {
+        tracing::warn!(
+            entity = ?dangling.entity,
+            component = dangling.component,
+            target = ?dangling.error.entity(),
+            "{}",
+            dangling.error
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+
+    type TargetsComponent = Usage<(), Vec<(Indirect<&'static u32>, Range<usize>)>>;
+
+    #[test]
+    fn test_indirect() {
+        let mut world = World::new();
+        let target = world.spawn((1u32,));
+        let wrong = world.spawn((1.0f32,));
+        let despawned = world.spawn((3u32,));
+        world.despawn(despawned).unwrap();
+
+        let indirect = Indirect::<&mut u32>::construct(target);
+        *indirect.get_mut(&mut world).unwrap() += 1;
+        assert_eq!(*indirect.get(&world).unwrap().get().unwrap(), 2);
+
+        assert_eq!(
+            Indirect::<&mut u32>::construct(despawned)
+                .get_mut(&mut world)
+                .err(),
+            Some(IndirectError::NoSuchEntity(despawned))
+        );
+
+        let multi = IndirectMulti::<&u32>::construct(vec![target, despawned]);
+        let values = multi
+            .iter(&world)
+            .map(|query| query.map(|mut query| query.get().copied()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [Ok(Some(2)), Err(IndirectError::NoSuchEntity(despawned))]
+        );
+
+        let mut weak = IndirectMulti::<&u32>::weak(vec![target, despawned]);
+        assert_eq!(weak.retain_alive(&world), 1);
+        assert_eq!(weak.entities(), &[target]);
+
+        let owner = world.spawn((TargetsComponent::construct(vec![
+            (Indirect::construct(target), 0..1),
+            (Indirect::construct(wrong), 0..1),
+            (Indirect::construct(despawned), 0..1),
+            (Indirect::weak(despawned), 0..1),
+        ]),));
+
+        let dangling = dangling_indirect::<TargetsComponent>(&world);
+        assert_eq!(
+            dangling.iter().map(|d| d.error).collect::<Vec<_>>(),
+            [
+                IndirectError::Unsatisfied {
+                    entity: wrong,
+                    query: std::any::type_name::<&u32>()
+                },
+                IndirectError::NoSuchEntity(despawned),
+            ]
+        );
+        // Pass-level validation also reports weak references that can no longer be accessed
+        let mut errors = vec![];
+        Indirect::<&u32>::weak(despawned).validate_indirect_access(&world, &mut errors);
+        assert_eq!(errors, [IndirectError::NoSuchEntity(despawned)]);
+
+        assert!(
+            dangling
+                .iter()
+                .all(|d| d.entity == owner
+                    && d.component == std::any::type_name::<TargetsComponent>())
+        );
     }
 }
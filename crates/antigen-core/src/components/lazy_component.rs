@@ -1,14 +1,16 @@
-use crate::{Construct, peano::Z};
+use crate::{peano::Z, Construct};
 
-/// A lazily-initialized component that can be pending, ready, or dropped
+/// A lazily-initialized component that can be pending, ready, dropped,
+/// or failed with an error
 #[derive(Debug)]
-pub enum LazyComponent<R, P = (), D = ()> {
+pub enum LazyComponent<R, P = (), D = (), E = ()> {
     Pending(P),
     Ready(R),
     Dropped(D),
+    Failed(E),
 }
 
-impl<R, P, D> Default for LazyComponent<R, P, D>
+impl<R, P, D, E> Default for LazyComponent<R, P, D, E>
 where
     P: Default,
 {
@@ -17,55 +19,61 @@ where
     }
 }
 
-impl<R, P, D> Clone for LazyComponent<R, P, D>
+impl<R, P, D, E> Clone for LazyComponent<R, P, D, E>
 where
     R: Clone,
     P: Clone,
     D: Clone,
+    E: Clone,
 {
     fn clone(&self) -> Self {
         match self {
             LazyComponent::Pending(p) => LazyComponent::Pending(p.clone()),
             LazyComponent::Ready(r) => LazyComponent::Ready(r.clone()),
             LazyComponent::Dropped(d) => LazyComponent::Dropped(d.clone()),
+            LazyComponent::Failed(e) => LazyComponent::Failed(e.clone()),
         }
     }
 }
 
-impl<R, P, D> Copy for LazyComponent<R, P, D>
+impl<R, P, D, E> Copy for LazyComponent<R, P, D, E>
 where
     R: Copy,
     P: Copy,
     D: Copy,
+    E: Copy,
 {
 }
 
-impl<R, P, D> PartialEq for LazyComponent<R, P, D>
+impl<R, P, D, E> PartialEq for LazyComponent<R, P, D, E>
 where
     R: PartialEq,
     P: PartialEq,
     D: PartialEq,
+    E: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (LazyComponent::Pending(lhs), LazyComponent::Pending(rhs)) => lhs.eq(rhs),
             (LazyComponent::Ready(lhs), LazyComponent::Ready(rhs)) => lhs.eq(rhs),
             (LazyComponent::Dropped(lhs), LazyComponent::Dropped(rhs)) => lhs.eq(rhs),
+            (LazyComponent::Failed(lhs), LazyComponent::Failed(rhs)) => lhs.eq(rhs),
             _ => false,
         }
     }
 }
 
-impl<R, P, D> Eq for LazyComponent<R, P, D>
+impl<R, P, D, E> Eq for LazyComponent<R, P, D, E>
 where
     R: Eq,
     P: Eq,
     D: Eq,
+    E: Eq,
 {
 }
 
-impl<R, P, D> LazyComponent<R, P, D> {
-    pub fn new(p: P) -> LazyComponent<R, P, D> {
+impl<R, P, D, E> LazyComponent<R, P, D, E> {
+    pub fn new(p: P) -> LazyComponent<R, P, D, E> {
         LazyComponent::Pending(p)
     }
 
@@ -81,6 +89,10 @@ impl<R, P, D> LazyComponent<R, P, D> {
         matches!(self, LazyComponent::Dropped(_))
     }
 
+    pub fn is_failed(&self) -> bool {
+        matches!(self, LazyComponent::Failed(_))
+    }
+
     pub fn set_pending(&mut self)
     where
         P: Default,
@@ -114,6 +126,10 @@ impl<R, P, D> LazyComponent<R, P, D> {
         *self = LazyComponent::Dropped(d);
     }
 
+    pub fn set_failed_with(&mut self, e: E) {
+        *self = LazyComponent::Failed(e);
+    }
+
     pub fn take(&mut self) -> Self
     where
         D: Default,
@@ -134,11 +150,48 @@ impl<R, P, D> LazyComponent<R, P, D> {
             _ => None,
         }
     }
+
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            LazyComponent::Failed(e) => Some(e),
+            _ => None,
+        }
+    }
 }
 
-impl<R, P, D> Construct<P, Z> for LazyComponent<R, P, D> {
+impl<R, P, D, E> Construct<P, Z> for LazyComponent<R, P, D, E> {
     fn construct(r: P) -> Self {
         LazyComponent::Pending(r)
     }
 }
 
+/// Serialized as pending data, so that deserialized components will be recreated
+///
+/// Ready, dropped and failed components have no pending data to serialize, and produce an error.
+impl<R, P, D, E> serde::Serialize for LazyComponent<R, P, D, E>
+where
+    P: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let state = match self {
+            LazyComponent::Pending(p) => return p.serialize(serializer),
+            LazyComponent::Ready(_) => "ready",
+            LazyComponent::Dropped(_) => "dropped",
+            LazyComponent::Failed(_) => "failed",
+        };
+
+        Err(serde::ser::Error::custom(format!(
+            "Cannot serialize {} lazy component, only pending components are serializable",
+            state
+        )))
+    }
+}
+
+impl<'de, R, P, D, E> serde::Deserialize<'de> for LazyComponent<R, P, D, E>
+where
+    P: serde::Deserialize<'de>,
+{
+    fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        P::deserialize(deserializer).map(LazyComponent::Pending)
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use hecs::{Component, World};
+use parking_lot::{Mutex, RwLock};
+
+use crate::{Changed, ChangedTrait, LazyComponent, Usage};
+
+/// A component that can be resolved by the result of a [`LazyJob`]
+pub trait ResolveLazy {
+    type Ready: Send + 'static;
+    type Error: Send + 'static;
+
+    /// Transition to ready on success, or to failed on error
+    fn resolve(&mut self, result: Result<Self::Ready, Self::Error>);
+}
+
+impl<R, P, D, E> ResolveLazy for LazyComponent<R, P, D, E>
+where
+    R: Send + 'static,
+    E: Send + 'static,
+{
+    type Ready = R;
+    type Error = E;
+
+    fn resolve(&mut self, result: Result<R, E>) {
+        match result {
+            Ok(r) => self.set_ready_with(r),
+            Err(e) => self.set_failed_with(e),
+        }
+    }
+}
+
+impl<U, T> ResolveLazy for Usage<U, T>
+where
+    T: ResolveLazy,
+{
+    type Ready = T::Ready;
+    type Error = T::Error;
+
+    fn resolve(&mut self, result: Result<Self::Ready, Self::Error>) {
+        (**self).resolve(result)
+    }
+}
+
+impl<T> ResolveLazy for Changed<T>
+where
+    T: ResolveLazy,
+{
+    type Ready = T::Ready;
+    type Error = T::Error;
+
+    fn resolve(&mut self, result: Result<Self::Ready, Self::Error>) {
+        (**self).resolve(result);
+        self.mark_changed();
+    }
+}
+
+impl<T> ResolveLazy for Arc<RwLock<T>>
+where
+    T: ResolveLazy,
+{
+    type Ready = T::Ready;
+    type Error = T::Error;
+
+    fn resolve(&mut self, result: Result<Self::Ready, Self::Error>) {
+        self.write().resolve(result)
+    }
+}
+
+/// Error produced when a [`LazyJob`] is dropped without producing a result,
+/// such as when its sender is dropped or its closure panics
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct JobAbandoned;
+
+impl std::fmt::Display for JobAbandoned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Lazy job dropped without a result")
+    }
+}
+
+impl std::error::Error for JobAbandoned {}
+
+impl From<JobAbandoned> for String {
+    fn from(e: JobAbandoned) -> Self {
+        e.to_string()
+    }
+}
+
+/// Sending half of a channel-backed [`LazyJob`]
+pub type LazyJobSender<T> = Sender<Result<<T as ResolveLazy>::Ready, <T as ResolveLazy>::Error>>;
+
+type BoxedFuture<R, E> = Pin<Box<dyn Future<Output = Result<R, E>> + Send>>;
+
+enum JobSource<R, E> {
+    Channel(Receiver<Result<R, E>>),
+    Future(Mutex<BoxedFuture<R, E>>),
+}
+
+enum JobStatus<R, E> {
+    Running,
+    Complete(Result<R, E>),
+    /// The job was dropped without producing a result
+    Abandoned,
+}
+
+/// Background job resolving the component T on the same entity
+///
+/// Polled by [`resolve_lazy_jobs_system`], which removes it once complete.
+pub struct LazyJob<T: ResolveLazy> {
+    source: JobSource<T::Ready, T::Error>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> LazyJob<T>
+where
+    T: ResolveLazy,
+{
+    /// Run `f` on the rayon thread pool
+    pub fn spawn<F>(f: F) -> Self
+    where
+        F: FnOnce() -> Result<T::Ready, T::Error> + Send + 'static,
+    {
+        let (tx, job) = Self::channel();
+        rayon::spawn(move || {
+            tx.send(f()).ok();
+        });
+        job
+    }
+
+    /// Create a job resolved by sending its result over a channel,
+    /// such as from a `map_async` callback
+    pub fn channel() -> (LazyJobSender<T>, Self) {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let job = LazyJob {
+            source: JobSource::Channel(rx),
+            _phantom: PhantomData,
+        };
+        (tx, job)
+    }
+
+    /// Create a job resolved by a future
+    ///
+    /// The future is polled once per run of [`resolve_lazy_jobs_system`] rather than by an executor,
+    /// so should not rely on its waker being called.
+    pub fn from_future<F>(future: F) -> Self
+    where
+        F: Future<Output = Result<T::Ready, T::Error>> + Send + 'static,
+    {
+        LazyJob {
+            source: JobSource::Future(Mutex::new(Box::pin(future))),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn poll(&mut self) -> JobStatus<T::Ready, T::Error> {
+        match &mut self.source {
+            JobSource::Channel(rx) => match rx.try_recv() {
+                Ok(result) => JobStatus::Complete(result),
+                Err(TryRecvError::Empty) => JobStatus::Running,
+                Err(TryRecvError::Disconnected) => JobStatus::Abandoned,
+            },
+            JobSource::Future(future) => {
+                let mut cx = Context::from_waker(Waker::noop());
+                match future.get_mut().as_mut().poll(&mut cx) {
+                    Poll::Ready(result) => JobStatus::Complete(result),
+                    Poll::Pending => JobStatus::Running,
+                }
+            }
+        }
+    }
+}
+
+/// Resolve components of type T whose [`LazyJob`] has completed
+///
+/// Abandoned jobs resolve their component as failed with a [`JobAbandoned`] error.
+pub fn resolve_lazy_jobs_system<T>(world: &mut World)
+where
+    T: Component + ResolveLazy,
+    T::Error: From<JobAbandoned>,
+{
+    let mut finished = vec![];
+
+    for (entity, (component, job)) in world.query_mut::<(&mut T, &mut LazyJob<T>)>() {
+        match job.poll() {
+            JobStatus::Running => continue,
+            JobStatus::Complete(result) => component.resolve(result),
+            JobStatus::Abandoned => {
+                tracing::warn!(
+                    ?entity,
+                    component = std::any::type_name::<T>(),
+                    "{}",
+                    JobAbandoned
+                );
+                component.resolve(Err(JobAbandoned.into()))
+            }
+        }
+
+        finished.push(entity);
+    }
+
+    for entity in finished {
+        world.remove_one::<LazyJob<T>>(entity).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestComponent = LazyComponent<u32, (), (), String>;
+
+    /// Future that is pending for a given number of polls
+    struct Countdown(u32);
+
+    impl Future for Countdown {
+        type Output = Result<u32, String>;
+
+        fn poll(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0 == 0 {
+                Poll::Ready(Ok(7))
+            } else {
+                self.0 -= 1;
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn test_lazy_jobs() {
+        let mut world = World::new();
+
+        let spawned = world.spawn((
+            TestComponent::default(),
+            LazyJob::<TestComponent>::spawn(|| Ok(1)),
+        ));
+
+        let future = world.spawn((
+            TestComponent::default(),
+            LazyJob::<TestComponent>::from_future(Countdown(1)),
+        ));
+
+        let (tx, job) = LazyJob::<TestComponent>::channel();
+        let failed = world.spawn((TestComponent::default(), job));
+        tx.send(Err("Not found".into())).unwrap();
+
+        let (tx, job) = LazyJob::<TestComponent>::channel();
+        let abandoned = world.spawn((TestComponent::default(), job));
+        drop(tx);
+
+        resolve_lazy_jobs_system::<TestComponent>(&mut world);
+        assert!(world.get::<TestComponent>(future).unwrap().is_pending());
+        assert_eq!(
+            world.get::<TestComponent>(failed).unwrap().error(),
+            Some(&"Not found".to_string())
+        );
+        assert_eq!(
+            world.get::<TestComponent>(abandoned).unwrap().error(),
+            Some(&JobAbandoned.to_string())
+        );
+        assert!(world.get::<LazyJob<TestComponent>>(abandoned).is_err());
+
+        let start = std::time::Instant::now();
+        while world.get::<LazyJob<TestComponent>>(spawned).is_ok() {
+            assert!(start.elapsed() < std::time::Duration::from_secs(5));
+            resolve_lazy_jobs_system::<TestComponent>(&mut world);
+        }
+
+        assert_eq!(world.get::<TestComponent>(spawned).unwrap().get(), Some(&1));
+        assert_eq!(world.get::<TestComponent>(future).unwrap().get(), Some(&7));
+    }
+}
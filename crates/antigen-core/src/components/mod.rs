@@ -1,20 +1,30 @@
 mod args;
 mod changed;
+mod events;
+mod hierarchy;
 mod indirect;
 mod lazy_component;
+mod lazy_job;
+mod named_entities;
+mod ring;
 mod swap_with;
 mod tagged_entities;
-mod named_entities;
+mod transform;
 mod usage;
 
 pub use ::usage::*;
 pub use args::*;
 pub use changed::*;
+pub use events::*;
+pub use hierarchy::*;
 pub use indirect::*;
 pub use lazy_component::*;
+pub use lazy_job::*;
+pub use named_entities::*;
+pub use ring::*;
 pub use swap_with::*;
 pub use tagged_entities::*;
-pub use named_entities::*;
+pub use transform::*;
 
 // Position
 pub enum Position {}
@@ -35,12 +45,14 @@ pub fn copy_to_system<U: hecs::Component, T: hecs::Component + PartialEq + Copy>
     world: &mut hecs::World,
 ) {
     for (_, (value, copy_to)) in world.query::<(&T, &CopyToComponent<U, T>)>().into_iter() {
-        for target in copy_to.entities() {
-            let mut query = world.query_one::<&mut Changed<T>>(*target).unwrap();
-            let target = query.get().unwrap();
+        for mut query in copy_to.iter(world).flatten() {
+            let target = match query.get() {
+                Some(target) => target,
+                None => continue,
+            };
             if **target != *value {
                 **target = *value;
-                target.set_changed(true);
+                target.mark_changed();
             }
         }
     }
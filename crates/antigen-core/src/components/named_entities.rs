@@ -1,58 +1,264 @@
 use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet},
+    ops::Bound,
 };
 
 use hecs::{Entity, Ref, RefMut, World};
 use usage::Usage;
 
+use crate::{Construct, MissingResource, Resources};
+
 pub enum NamedEntity {}
-/// Component identifying an entity as a tagged singleton
+/// Component identifying an entity by name
 pub type NamedEntityComponent = Usage<NamedEntity, Cow<'static, str>>;
 
 pub enum NamedEntities {}
-/// TypeId -> Entity map for referring to singletons by tag
+/// Name -> Entities index for referring to entities by their [`NamedEntityComponent`],
+/// stored as a resource
+///
+/// Kept in sync with the world by [`named_entities_maintenance_system`].
 pub type NamedEntitiesComponent =
     Usage<NamedEntities, BTreeMap<Cow<'static, str>, BTreeSet<Entity>>>;
 
+/// Error produced when a named entity lookup fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamedEntityError {
+    /// The world has no [`NamedEntitiesComponent`] resource
+    MissingIndex,
+    NoSuchEntity(Entity),
+    UnknownName(Cow<'static, str>),
+    /// More than one entity has a name that was expected to be unique
+    Ambiguous(Cow<'static, str>),
+}
+
+impl std::fmt::Display for NamedEntityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NamedEntityError::MissingIndex => write!(f, "No named entities resource"),
+            NamedEntityError::NoSuchEntity(entity) => write!(f, "No such entity {:?}", entity),
+            NamedEntityError::UnknownName(name) => write!(f, "No entity named {:?}", name),
+            NamedEntityError::Ambiguous(name) => {
+                write!(f, "Multiple entities named {:?}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NamedEntityError {}
+
+impl From<MissingResource> for NamedEntityError {
+    fn from(_: MissingResource) -> Self {
+        NamedEntityError::MissingIndex
+    }
+}
+
 pub fn get_named_entities_component(
     world: &World,
-) -> Result<Ref<NamedEntitiesComponent>, hecs::ComponentError> {
-    let mut query = world.query::<&NamedEntitiesComponent>();
-    let (entity, _) = query
-        .into_iter()
-        .next()
-        .expect("No tagged entities component");
-    world.get::<NamedEntitiesComponent>(entity)
+) -> Result<Ref<'_, NamedEntitiesComponent>, NamedEntityError> {
+    Ok(world.get_resource::<NamedEntitiesComponent>()?)
 }
 
 pub fn get_named_entities_component_mut(
     world: &World,
-) -> Result<RefMut<NamedEntitiesComponent>, hecs::ComponentError> {
-    let entity = {
-        let mut query = world.query::<&NamedEntitiesComponent>();
-        query
-            .into_iter()
-            .next()
-            .expect("No tagged entities component")
-            .0
+) -> Result<RefMut<'_, NamedEntitiesComponent>, NamedEntityError> {
+    Ok(world.get_resource_mut::<NamedEntitiesComponent>()?)
+}
+
+/// Returns the single entity with the given name
+pub fn get_named_entity(world: &World, name: &str) -> Result<Entity, NamedEntityError> {
+    let named_entities = get_named_entities_component(world)?;
+    let mut entities = named_entities
+        .get(name)
+        .into_iter()
+        .flat_map(|entities| entities.iter().copied());
+
+    match (entities.next(), entities.next()) {
+        (Some(entity), None) => Ok(entity),
+        (Some(_), Some(_)) => Err(NamedEntityError::Ambiguous(name.to_string().into())),
+        (None, _) => Err(NamedEntityError::UnknownName(name.to_string().into())),
+    }
+}
+
+/// Returns every entity whose name matches `pattern`, in name order
+///
+/// `*` matches any sequence of characters, so `"door*"` is a prefix query.
+/// Patterns without a wildcard match a single name exactly.
+pub fn find_named_entities(world: &World, pattern: &str) -> Result<Vec<Entity>, NamedEntityError> {
+    let named_entities = get_named_entities_component(world)?;
+
+    let prefix = pattern.split('*').next().unwrap_or_default();
+    Ok(named_entities
+        .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+        .take_while(|(name, _)| name.starts_with(prefix))
+        .filter(|(name, _)| name_matches(pattern, name))
+        .flat_map(|(_, entities)| entities.iter().copied())
+        .collect())
+}
+
+/// Glob-style match supporting `*` wildcards
+fn name_matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
     };
-    world.get_mut::<NamedEntitiesComponent>(entity)
+
+    let parts = parts.collect::<Vec<_>>();
+    let (last, middle) = match parts.split_last() {
+        Some(split) => split,
+        // No wildcard
+        None => return rest.is_empty(),
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
 }
 
-pub fn insert_named_entity(world: &mut World, name: Cow<'static, str>, entity: Entity) {
-    let mut tagged_entities = get_named_entities_component_mut(world).unwrap();
-    tagged_entities.entry(name).or_default().insert(entity);
+/// Name `entity`, replacing any existing name and updating the index
+///
+/// The index is left untouched if `entity` does not exist.
+pub fn insert_named_entity(
+    world: &mut World,
+    name: Cow<'static, str>,
+    entity: Entity,
+) -> Result<(), NamedEntityError> {
+    if !world.contains(entity) {
+        return Err(NamedEntityError::NoSuchEntity(entity));
+    }
+
+    let mut named_entities = get_named_entities_component_mut(world)?;
+
+    let previous = world
+        .get::<NamedEntityComponent>(entity)
+        .ok()
+        .map(|name| (**name).clone());
+    if let Some(previous) = previous {
+        remove_index_entry(&mut named_entities, &previous, entity);
+    }
+
+    named_entities
+        .entry(name.clone())
+        .or_default()
+        .insert(entity);
+    drop(named_entities);
+
+    world
+        .insert_one(entity, NamedEntityComponent::construct(name))
+        .map_err(|_| NamedEntityError::NoSuchEntity(entity))
+}
+
+/// Remove the name of `entity` and its index entry, returning the name if it had one
+pub fn remove_named_entity(world: &mut World, entity: Entity) -> Option<Cow<'static, str>> {
+    let name = (*world.remove_one::<NamedEntityComponent>(entity).ok()?).clone();
+
+    if let Ok(mut named_entities) = get_named_entities_component_mut(world) {
+        remove_index_entry(&mut named_entities, &name, entity);
+    }
+
+    Some(name)
+}
+
+fn remove_index_entry(named_entities: &mut NamedEntitiesComponent, name: &str, entity: Entity) {
+    if let Some(entities) = named_entities.get_mut(name) {
+        entities.remove(&entity);
+        if entities.is_empty() {
+            named_entities.remove(name);
+        }
+    }
 }
 
-pub fn insert_named_entities_system(world: &mut World) {
-    let mut named_entities = get_named_entities_component_mut(world).unwrap();
+/// Keep the [`NamedEntitiesComponent`] index consistent with the world
+///
+/// Newly-named entities are added, while renamed, unnamed and despawned ones
+/// have their stale entries removed.
+/// Does nothing if the world has no index.
+pub fn named_entities_maintenance_system(world: &World) {
+    let mut named_entities = match get_named_entities_component_mut(world) {
+        Ok(named_entities) => named_entities,
+        Err(_) => return,
+    };
+
+    named_entities.retain(|name, entities| {
+        entities.retain(|entity| {
+            matches!(
+                world.get::<NamedEntityComponent>(*entity),
+                Ok(current) if **current == *name
+            )
+        });
+        !entities.is_empty()
+    });
 
-    let mut query = world.query::<&NamedEntityComponent>();
-    for (entity, name) in query.into_iter() {
+    for (entity, name) in world.query::<&NamedEntityComponent>().iter() {
         named_entities
             .entry((**name).clone())
             .or_default()
             .insert(entity);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_entities() {
+        let mut world = World::new();
+        assert_eq!(
+            find_named_entities(&world, "*"),
+            Err(NamedEntityError::MissingIndex)
+        );
+
+        world.insert_resource(NamedEntitiesComponent::default());
+        let door_a = world.spawn((NamedEntityComponent::construct("door_a".into()),));
+        let door_b = world.spawn((NamedEntityComponent::construct("door_b".into()),));
+        let light = world.spawn((NamedEntityComponent::construct("light".into()),));
+        named_entities_maintenance_system(&world);
+
+        assert_eq!(get_named_entity(&world, "light"), Ok(light));
+        assert_eq!(
+            find_named_entities(&world, "door*").unwrap(),
+            [door_a, door_b]
+        );
+        assert_eq!(find_named_entities(&world, "*_b").unwrap(), [door_b]);
+        assert_eq!(find_named_entities(&world, "d*r*a").unwrap(), [door_a]);
+        assert_eq!(find_named_entities(&world, "door").unwrap(), []);
+
+        // Renames and despawns are picked up by the maintenance system
+        *world.get_mut::<NamedEntityComponent>(door_b).unwrap() =
+            NamedEntityComponent::construct("light".into());
+        world.despawn(door_a).unwrap();
+        named_entities_maintenance_system(&world);
+
+        assert!(find_named_entities(&world, "door*").unwrap().is_empty());
+        assert_eq!(
+            get_named_entity(&world, "light"),
+            Err(NamedEntityError::Ambiguous("light".into()))
+        );
+
+        // Direct renames update the index immediately
+        insert_named_entity(&mut world, "switch".into(), door_b).unwrap();
+        assert_eq!(get_named_entity(&world, "light"), Ok(light));
+        assert_eq!(get_named_entity(&world, "switch"), Ok(door_b));
+
+        // Naming a despawned entity leaves the index untouched
+        assert_eq!(
+            insert_named_entity(&mut world, "ghost".into(), door_a),
+            Err(NamedEntityError::NoSuchEntity(door_a))
+        );
+        assert!(find_named_entities(&world, "ghost").unwrap().is_empty());
+
+        assert_eq!(remove_named_entity(&mut world, light), Some("light".into()));
+        assert_eq!(
+            get_named_entity(&world, "light"),
+            Err(NamedEntityError::UnknownName("light".into()))
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use std::marker::PhantomData;
+
+use hecs::{Component, Entity, EntityBuilder, World};
+
+use crate::{Changed, ChangedTrait};
+
+/// Ordered ring of entities whose components of type T are rotated by [`ring_rotate_system`]
+///
+/// Each rotation moves every value one entity along the ring,
+/// so the first entity receives the value of the last and the rest hold progressively older ones.
+/// This generalizes [`SwapWith`](crate::SwapWith) to N buffers,
+/// such as triple-buffered readbacks or history textures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ring<T> {
+    entities: Vec<Entity>,
+    index: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+/// Ring stored with a change flag, marked each time it rotates
+pub type RingComponent<T> = Changed<Ring<T>>;
+
+impl<T> Ring<T> {
+    pub fn new(entities: Vec<Entity>) -> Self {
+        assert!(
+            !entities.is_empty(),
+            "Ring must contain at least one entity"
+        );
+        for (i, entity) in entities.iter().enumerate() {
+            assert!(
+                !entities[..i].contains(entity),
+                "Ring contains {:?} more than once",
+                entity
+            );
+        }
+
+        Ring {
+            entities,
+            index: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Number of rotations modulo the ring length
+    ///
+    /// The value originally held by `entities()[i]` is now held by `entities()[(i + index) % len]`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The entity holding the newest value
+    pub fn current(&self) -> Entity {
+        self.entities[0]
+    }
+
+    /// The entity holding the value from `age` rotations ago, wrapping around the ring
+    pub fn entity(&self, age: usize) -> Entity {
+        self.entities[age % self.entities.len()]
+    }
+}
+
+pub fn ring_builder<T: Component>(entities: Vec<Entity>) -> EntityBuilder {
+    let mut builder = EntityBuilder::new();
+    builder.add(RingComponent::<T>::new(Ring::new(entities), false));
+    builder
+}
+
+/// Rotate the T components of each [`RingComponent<T>`] one step, marking the ring as changed
+///
+/// Rings with a member that has been despawned or lacks a T component are skipped with a warning.
+pub fn ring_rotate_system<T: Component>(world: &mut World) {
+    rotate_rings::<T>(world);
+}
+
+/// As [`ring_rotate_system`], additionally marking every rotated component as changed
+pub fn ring_rotate_changed_system<T: Component + ChangedTrait>(world: &mut World) {
+    for entities in rotate_rings::<T>(world) {
+        for entity in entities {
+            if let Ok(component) = world.get::<T>(entity) {
+                component.mark_changed();
+            }
+        }
+    }
+}
+
+/// Rotate every ring of T, returning the members of those rotated
+fn rotate_rings<T: Component>(world: &mut World) -> Vec<Vec<Entity>> {
+    let rings = world
+        .query_mut::<&RingComponent<T>>()
+        .into_iter()
+        .map(|(entity, ring)| (entity, ring.entities.clone()))
+        .collect::<Vec<_>>();
+
+    let mut rotated = vec![];
+    for (ring_entity, entities) in rings {
+        if let Some(entity) = entities
+            .iter()
+            .find(|entity| world.get::<T>(**entity).is_err())
+        {
+            tracing::warn!(
+                ring = ?ring_entity,
+                ?entity,
+                component = std::any::type_name::<T>(),
+                "Ring member is missing its component"
+            );
+            continue;
+        }
+
+        rotate::<T>(world, &entities);
+
+        let mut ring = world.get_mut::<RingComponent<T>>(ring_entity).unwrap();
+        ring.index = (ring.index + 1) % ring.len();
+        ring.mark_changed();
+
+        rotated.push(entities);
+    }
+    rotated
+}
+
+/// Move each entity's T to the next entity in `entities`, wrapping the last around to the first
+///
+/// Values are swapped in place through the first entity, so no archetypes change.
+fn rotate<T: Component>(world: &mut World, entities: &[Entity]) {
+    let (first, rest) = entities.split_first().unwrap();
+
+    let mut query = world.query_mut::<&mut T>();
+    let mut view = query.view();
+    for entity in rest {
+        let [carry, next] = view.get_mut_n([*first, *entity]);
+        std::mem::swap(carry.unwrap(), next.unwrap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Construct, Usage};
+
+    enum Frame {}
+    type FrameComponent = Changed<Usage<Frame, u32>>;
+
+    #[test]
+    fn test_ring() {
+        let mut world = World::new();
+        let entities = (0..3)
+            .map(|i| world.spawn((FrameComponent::construct(i),)))
+            .collect::<Vec<_>>();
+        let ring = world.spawn(ring_builder::<FrameComponent>(entities.clone()).build());
+
+        let values = |world: &World| {
+            entities
+                .iter()
+                .map(|entity| ***world.get::<FrameComponent>(*entity).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let archetypes = world.archetypes_generation();
+        ring_rotate_changed_system::<FrameComponent>(&mut world);
+        assert_eq!(values(&world), [2, 0, 1]);
+        assert_eq!(world.archetypes_generation(), archetypes);
+        assert!(
+            world
+                .get::<FrameComponent>(entities[1])
+                .unwrap()
+                .changed_tick()
+                > Default::default()
+        );
+
+        ring_rotate_system::<FrameComponent>(&mut world);
+        assert_eq!(values(&world), [1, 2, 0]);
+
+        let ring_component = world.get::<RingComponent<FrameComponent>>(ring).unwrap();
+        assert_eq!(ring_component.index(), 2);
+        assert_eq!(ring_component.entity(1), entities[1]);
+        assert!(ring_component.changed_tick() > Default::default());
+        drop(ring_component);
+
+        // Rings with missing members are left untouched
+        world.despawn(entities[2]).unwrap();
+        ring_rotate_system::<FrameComponent>(&mut world);
+        assert_eq!(***world.get::<FrameComponent>(entities[0]).unwrap(), 1);
+        assert_eq!(
+            world
+                .get::<RingComponent<FrameComponent>>(ring)
+                .unwrap()
+                .index(),
+            2
+        );
+    }
+}
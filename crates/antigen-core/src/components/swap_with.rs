@@ -31,7 +31,10 @@ pub fn swap_with_system<T: Component>(world: &mut hecs::World) {
         .with::<SwapWith<T>>();
 
     for (_, (component, indirect_component)) in query.into_iter() {
-        let mut query = indirect_component.get(world);
+        let mut query = match indirect_component.get(world) {
+            Ok(query) => query,
+            Err(_) => continue,
+        };
         let indirect_buffer = query.get().unwrap();
 
         std::mem::swap(component, indirect_buffer);
@@ -3,52 +3,132 @@ use std::{any::TypeId, collections::BTreeMap};
 use hecs::{Entity, Ref, RefMut, World};
 use usage::Usage;
 
+use crate::{MissingResource, Resources};
+
 pub enum TaggedEntity {}
 /// Component identifying an entity as a tagged singleton
 pub type TaggedEntityComponent = Usage<TaggedEntity, TypeId>;
 
 pub enum TaggedEntities {}
-/// TypeId -> Entity map for referring to singletons by tag
+/// TypeId -> Entity map for referring to singletons by tag, stored as a resource
 pub type TaggedEntitiesComponent = Usage<TaggedEntities, BTreeMap<TypeId, Entity>>;
 
+/// Error produced when a tagged entity lookup fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaggedEntityError {
+    /// The world has no [`TaggedEntitiesComponent`] resource
+    MissingResource(MissingResource),
+    /// No entity has been tagged with this type
+    UnknownTag(&'static str),
+    /// No entity matched the query used to find the entity to tag
+    NoMatch(&'static str),
+    /// More than one entity matched the query used to find the entity to tag
+    Ambiguous(&'static str),
+}
+
+impl std::fmt::Display for TaggedEntityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaggedEntityError::MissingResource(e) => e.fmt(f),
+            TaggedEntityError::UnknownTag(tag) => write!(f, "No entity tagged {}", tag),
+            TaggedEntityError::NoMatch(query) => write!(f, "No entity matches {}", query),
+            TaggedEntityError::Ambiguous(query) => {
+                write!(f, "Multiple entities match {}", query)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TaggedEntityError {}
+
+impl From<MissingResource> for TaggedEntityError {
+    fn from(e: MissingResource) -> Self {
+        TaggedEntityError::MissingResource(e)
+    }
+}
+
 pub fn get_tagged_entities(
-    world: &mut World,
-) -> Result<Ref<TaggedEntitiesComponent>, hecs::ComponentError> {
-    let query = world.query_mut::<&TaggedEntitiesComponent>();
-    let (entity, _) = query
-        .into_iter()
-        .next()
-        .expect("No tagged entities component");
-    world.get::<TaggedEntitiesComponent>(entity)
+    world: &World,
+) -> Result<Ref<'_, TaggedEntitiesComponent>, MissingResource> {
+    world.get_resource::<TaggedEntitiesComponent>()
 }
 
 pub fn get_tagged_entities_mut(
-    world: &mut World,
-) -> Result<RefMut<TaggedEntitiesComponent>, hecs::ComponentError> {
-    let query = world.query_mut::<&TaggedEntitiesComponent>();
-    let (entity, _) = query
-        .into_iter()
-        .next()
-        .expect("No tagged entities component");
-    world.get_mut::<TaggedEntitiesComponent>(entity)
+    world: &World,
+) -> Result<RefMut<'_, TaggedEntitiesComponent>, MissingResource> {
+    world.get_resource_mut::<TaggedEntitiesComponent>()
 }
 
-pub fn get_tagged_entity<T: 'static>(world: &mut World) -> Option<Entity> {
-    let type_id = std::any::TypeId::of::<T>();
-    let tagged_entities = get_tagged_entities(world)
-        .unwrap_or_else(|e| panic!("Error getting entity with tag {:?}: {}", type_id, e));
-    tagged_entities.get(&std::any::TypeId::of::<T>()).copied()
+pub fn get_tagged_entity<T: 'static>(world: &World) -> Result<Entity, TaggedEntityError> {
+    get_tagged_entities(world)?
+        .get(&TypeId::of::<T>())
+        .copied()
+        .ok_or_else(|| TaggedEntityError::UnknownTag(std::any::type_name::<T>()))
 }
 
-pub fn insert_tagged_entity<T: 'static>(world: &mut World, entity: Entity) {
-    let type_id = std::any::TypeId::of::<T>();
-    let mut tagged_entities = get_tagged_entities_mut(world).unwrap();
-    tagged_entities.insert(type_id, entity);
+pub fn insert_tagged_entity<T: 'static>(
+    world: &World,
+    entity: Entity,
+) -> Result<(), TaggedEntityError> {
+    get_tagged_entities_mut(world)?.insert(TypeId::of::<T>(), entity);
+    Ok(())
 }
 
+/// Tag the single entity matching Q with T, returning it
 pub fn insert_tagged_entity_by_query<Q: hecs::Query + Send + Sync + 'static, T: 'static>(
     world: &mut World,
-) {
-    let (entity, _) = world.query_mut::<Q>().into_iter().next().unwrap();
-    insert_tagged_entity::<T>(world, entity);
+) -> Result<Entity, TaggedEntityError> {
+    let mut entities = world.query_mut::<Q>().into_iter().map(|(entity, _)| entity);
+    let entity = match (entities.next(), entities.next()) {
+        (Some(entity), None) => entity,
+        (Some(_), Some(_)) => return Err(TaggedEntityError::Ambiguous(std::any::type_name::<Q>())),
+        (None, _) => return Err(TaggedEntityError::NoMatch(std::any::type_name::<Q>())),
+    };
+
+    insert_tagged_entity::<T>(world, entity)?;
+    Ok(entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Camera;
+
+    #[test]
+    fn test_tagged_entities() {
+        let mut world = World::new();
+        assert_eq!(
+            get_tagged_entity::<Camera>(&world),
+            Err(TaggedEntityError::MissingResource(MissingResource(
+                std::any::type_name::<TaggedEntitiesComponent>()
+            )))
+        );
+
+        world.insert_resource(TaggedEntitiesComponent::default());
+        assert_eq!(
+            get_tagged_entity::<Camera>(&world),
+            Err(TaggedEntityError::UnknownTag(
+                std::any::type_name::<Camera>()
+            ))
+        );
+
+        assert_eq!(
+            insert_tagged_entity_by_query::<&u32, Camera>(&mut world),
+            Err(TaggedEntityError::NoMatch(std::any::type_name::<&u32>()))
+        );
+
+        let camera = world.spawn((1u32,));
+        assert_eq!(
+            insert_tagged_entity_by_query::<&u32, Camera>(&mut world),
+            Ok(camera)
+        );
+        assert_eq!(get_tagged_entity::<Camera>(&world), Ok(camera));
+
+        world.spawn((2u32,));
+        assert_eq!(
+            insert_tagged_entity_by_query::<&u32, Camera>(&mut world),
+            Err(TaggedEntityError::Ambiguous(std::any::type_name::<&u32>()))
+        );
+    }
 }
@@ -0,0 +1,205 @@
+use hecs::{Component, Entity, World};
+use nalgebra::{UnitQuaternion, Vector3};
+
+use crate::{
+    get_children, Changed, ChangedTrait, ChildrenComponent, Construct, ParentComponent,
+    PositionComponent, RotationComponent, ScaleComponent, Usage,
+};
+
+// Local Position
+pub enum LocalPosition {}
+pub type LocalPositionComponent = Usage<LocalPosition, Vector3<f32>>;
+
+// Local Rotation
+pub enum LocalRotation {}
+pub type LocalRotationComponent = Usage<LocalRotation, UnitQuaternion<f32>>;
+
+// Local Scale
+pub enum LocalScale {}
+pub type LocalScaleComponent = Usage<LocalScale, Vector3<f32>>;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Transform {
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    scale: Vector3<f32>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            position: Vector3::zeros(),
+            rotation: UnitQuaternion::identity(),
+            scale: Vector3::repeat(1.0),
+        }
+    }
+}
+
+impl Transform {
+    fn local(world: &World, entity: Entity) -> Option<Self> {
+        let mut query = world
+            .query_one::<(
+                Option<&LocalPositionComponent>,
+                Option<&LocalRotationComponent>,
+                Option<&LocalScaleComponent>,
+            )>(entity)
+            .ok()?;
+
+        match query.get()? {
+            (None, None, None) => None,
+            (position, rotation, scale) => {
+                let identity = Transform::default();
+                Some(Transform {
+                    position: position.map_or(identity.position, |position| **position),
+                    rotation: rotation.map_or(identity.rotation, |rotation| **rotation),
+                    scale: scale.map_or(identity.scale, |scale| **scale),
+                })
+            }
+        }
+    }
+
+    fn global(world: &World, entity: Entity) -> Self {
+        let identity = Transform::default();
+        Transform {
+            position: read_global::<PositionComponent>(world, entity)
+                .map_or(identity.position, |position| *position),
+            rotation: read_global::<RotationComponent>(world, entity)
+                .map_or(identity.rotation, |rotation| *rotation),
+            scale: read_global::<ScaleComponent>(world, entity)
+                .map_or(identity.scale, |scale| *scale),
+        }
+    }
+
+    fn set_global(&self, world: &World, entity: Entity) {
+        write_global(world, entity, PositionComponent::construct(self.position));
+        write_global(world, entity, RotationComponent::construct(self.rotation));
+        write_global(world, entity, ScaleComponent::construct(self.scale));
+    }
+
+    /// Compose a child's local transform onto this one
+    fn child(&self, local: &Transform) -> Transform {
+        Transform {
+            position: self.position + self.rotation * self.scale.component_mul(&local.position),
+            rotation: self.rotation * local.rotation,
+            scale: self.scale.component_mul(&local.scale),
+        }
+    }
+}
+
+/// Read a global transform component, which may or may not be wrapped in [`Changed`]
+fn read_global<T: Component + Copy>(world: &World, entity: Entity) -> Option<T> {
+    if let Ok(value) = world.get::<T>(entity) {
+        return Some(*value);
+    }
+
+    world.get::<Changed<T>>(entity).ok().map(|value| **value)
+}
+
+/// Write a global transform component, which may or may not be wrapped in [`Changed`]
+///
+/// Entities with neither are left untouched.
+fn write_global<T: Component + Copy + PartialEq>(world: &World, entity: Entity, value: T) {
+    if let Ok(mut component) = world.get_mut::<T>(entity) {
+        *component = value;
+    } else if let Ok(mut component) = world.get_mut::<Changed<T>>(entity) {
+        if **component != value {
+            **component = value;
+            component.mark_changed();
+        }
+    }
+}
+
+/// Compute global transforms from local ones in hierarchy order
+///
+/// The global [`PositionComponent`], [`RotationComponent`] and [`ScaleComponent`]
+/// of an entity with a parent are its local transform composed onto its parent's global one.
+/// Missing local components are treated as identity.
+///
+/// Root entities with local components have them copied to their global ones,
+/// while those without keep their existing global transform.
+pub fn propagate_transforms_system(world: &World) {
+    let roots = world
+        .query::<()>()
+        .without::<ParentComponent>()
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+
+    let mut pending = vec![];
+    for root in roots {
+        let global = match Transform::local(world, root) {
+            Some(local) => {
+                local.set_global(world, root);
+                local
+            }
+            None => Transform::global(world, root),
+        };
+
+        if world.get::<ChildrenComponent>(root).is_ok() {
+            pending.push((root, global));
+        }
+    }
+
+    while let Some((parent, parent_global)) = pending.pop() {
+        for child in get_children(world, parent) {
+            let local = Transform::local(world, child).unwrap_or_default();
+            let global = parent_global.child(&local);
+            global.set_global(world, child);
+            pending.push((child, global));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_parent;
+
+    #[test]
+    fn test_propagate_transforms() {
+        let mut world = World::new();
+
+        let root = world.spawn((
+            LocalPositionComponent::construct(Vector3::new(1.0, 0.0, 0.0)),
+            LocalRotationComponent::construct(UnitQuaternion::from_euler_angles(
+                0.0,
+                0.0,
+                std::f32::consts::FRAC_PI_2,
+            )),
+            LocalScaleComponent::construct(Vector3::repeat(2.0)),
+            PositionComponent::default(),
+        ));
+
+        let child = world.spawn((
+            LocalPositionComponent::construct(Vector3::new(1.0, 0.0, 0.0)),
+            Changed::new(PositionComponent::default(), false),
+            ScaleComponent::default(),
+        ));
+
+        let grandchild = world.spawn((PositionComponent::default(),));
+
+        set_parent(&mut world, child, root).unwrap();
+        set_parent(&mut world, grandchild, child).unwrap();
+
+        let since = crate::ChangeTick::advance();
+        propagate_transforms_system(&world);
+
+        assert_eq!(
+            **world.get::<PositionComponent>(root).unwrap(),
+            Vector3::x()
+        );
+
+        let position = world.get::<Changed<PositionComponent>>(child).unwrap();
+        assert!((***position - Vector3::new(1.0, 2.0, 0.0)).norm() < 1e-5);
+        assert!(position.is_changed_since(since));
+        drop(position);
+
+        assert_eq!(
+            **world.get::<ScaleComponent>(child).unwrap(),
+            Vector3::repeat(2.0)
+        );
+
+        let position = world.get::<PositionComponent>(grandchild).unwrap();
+        assert!((**position - Vector3::new(1.0, 2.0, 0.0)).norm() < 1e-5);
+    }
+}
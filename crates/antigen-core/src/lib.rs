@@ -1,11 +1,29 @@
+extern crate self as antigen_core;
+
+mod commands;
 mod components;
+mod prefab;
+mod registry;
+mod resources;
+mod schedule;
+mod snapshot;
+mod time;
 mod traits;
 mod two_way_channel;
 mod world_exchange;
 
 pub mod peano;
 
+pub use antigen_macros::{ClonedBundle, Construct, With};
+
+pub use commands::*;
 pub use components::*;
+pub use prefab::*;
+pub use registry::*;
+pub use resources::*;
+pub use schedule::*;
+pub use snapshot::*;
+pub use time::*;
 pub use traits::*;
 pub use two_way_channel::*;
 pub use world_exchange::*;
@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    set_parent, ComponentRegistry, ComponentValue, MissingResource, RegistryError, Resources,
+};
+
+/// Error produced when resolving or instantiating a [`Prefab`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefabError {
+    UnknownPrefab(String),
+    /// The prefab inherits from itself, directly or indirectly
+    Cycle(String),
+    /// The prefab still inherits from another, and must be resolved via a [`PrefabLibrary`]
+    Unresolved(String),
+    MissingLibrary,
+    Registry(RegistryError),
+    Parse(String),
+}
+
+impl std::fmt::Display for PrefabError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrefabError::UnknownPrefab(name) => write!(f, "Unknown prefab {}", name),
+            PrefabError::Cycle(name) => write!(f, "Prefab {} inherits from itself", name),
+            PrefabError::Unresolved(name) => {
+                write!(f, "Prefab extends unresolved prefab {}", name)
+            }
+            PrefabError::MissingLibrary => write!(f, "No prefab library resource"),
+            PrefabError::Registry(e) => e.fmt(f),
+            PrefabError::Parse(e) => write!(f, "Failed to parse prefabs: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PrefabError {}
+
+impl From<RegistryError> for PrefabError {
+    fn from(e: RegistryError) -> Self {
+        PrefabError::Registry(e)
+    }
+}
+
+impl From<MissingResource> for PrefabError {
+    fn from(_: MissingResource) -> Self {
+        PrefabError::MissingLibrary
+    }
+}
+
+/// Template for an entity and its children, described by registered component names
+///
+/// A prefab that extends another inherits its components and children.
+/// Inherited components are overridden field-by-field where both are JSON objects,
+/// replaced otherwise, and removed if overridden with `null`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Prefab {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    pub components: BTreeMap<String, serde_json::Value>,
+    /// Spawned after, and parented to, the prefab entity
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Prefab>,
+}
+
+impl Prefab {
+    pub fn extending(name: impl Into<String>) -> Self {
+        Prefab {
+            extends: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_component(mut self, name: impl Into<String>, value: serde_json::Value) -> Self {
+        self.components.insert(name.into(), value);
+        self
+    }
+
+    pub fn with_child(mut self, child: Prefab) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Apply `self` on top of the resolved prefab `base`
+    fn inherit(self, mut base: Prefab) -> Prefab {
+        for (name, value) in self.components {
+            if value.is_null() {
+                base.components.remove(&name);
+            } else if let Some(inherited) = base.components.get_mut(&name) {
+                merge_json(inherited, value);
+            } else {
+                base.components.insert(name, value);
+            }
+        }
+
+        base.children.extend(self.children);
+        base.extends = None;
+        base
+    }
+}
+
+/// Recursively override the fields of `base` with those of `value`
+fn merge_json(base: &mut serde_json::Value, value: serde_json::Value) {
+    match (base, value) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(value)) => {
+            for (key, value) in value {
+                match base.get_mut(&key) {
+                    Some(inherited) => merge_json(inherited, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+/// Named collection of prefabs, loadable from JSON
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PrefabLibrary(BTreeMap<String, Prefab>);
+
+impl PrefabLibrary {
+    /// Parse a JSON object of prefabs keyed by name
+    pub fn from_json(json: &str) -> Result<Self, PrefabError> {
+        serde_json::from_str(json).map_err(|e| PrefabError::Parse(e.to_string()))
+    }
+
+    /// Insert a prefab under `name`, returning any it replaced
+    pub fn insert(&mut self, name: impl Into<String>, prefab: Prefab) -> Option<Prefab> {
+        self.0.insert(name.into(), prefab)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Prefab> {
+        self.0.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prefab> {
+        self.0.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Add every prefab in `other`, replacing any with the same name
+    pub fn extend(&mut self, other: PrefabLibrary) {
+        self.0.extend(other.0)
+    }
+
+    /// Flatten the named prefab and its children by applying their inheritance chains
+    pub fn resolve(&self, name: &str) -> Result<Prefab, PrefabError> {
+        self.resolve_inner(name, &mut BTreeSet::new())
+    }
+
+    fn resolve_inner<'a>(
+        &'a self,
+        name: &'a str,
+        visiting: &mut BTreeSet<&'a str>,
+    ) -> Result<Prefab, PrefabError> {
+        if !visiting.insert(name) {
+            return Err(PrefabError::Cycle(name.to_string()));
+        }
+
+        let prefab = self
+            .get(name)
+            .ok_or_else(|| PrefabError::UnknownPrefab(name.to_string()))?;
+        let resolved = self.resolve_prefab_inner(prefab, visiting);
+
+        visiting.remove(name);
+        resolved
+    }
+
+    /// Flatten an unnamed prefab, such as a child, against this library
+    pub fn resolve_prefab(&self, prefab: &Prefab) -> Result<Prefab, PrefabError> {
+        self.resolve_prefab_inner(prefab, &mut BTreeSet::new())
+    }
+
+    fn resolve_prefab_inner<'a>(
+        &'a self,
+        prefab: &'a Prefab,
+        visiting: &mut BTreeSet<&'a str>,
+    ) -> Result<Prefab, PrefabError> {
+        let base = match &prefab.extends {
+            Some(extends) => self.resolve_inner(extends, visiting)?,
+            None => Prefab::default(),
+        };
+
+        let mut resolved = Prefab {
+            extends: None,
+            components: prefab.components.clone(),
+            children: vec![],
+        }
+        .inherit(base);
+
+        for child in &prefab.children {
+            resolved
+                .children
+                .push(self.resolve_prefab_inner(child, visiting)?);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Resolve the named prefab and spawn it into `world`
+    pub fn instantiate(
+        &self,
+        registry: &ComponentRegistry,
+        world: &mut World,
+        name: &str,
+    ) -> Result<Vec<Entity>, PrefabError> {
+        registry.instantiate(world, &self.resolve(name)?)
+    }
+}
+
+/// Resolve the named prefab from the world's [`PrefabLibrary`] resource and spawn it
+pub fn instantiate_prefab(
+    registry: &ComponentRegistry,
+    world: &mut World,
+    name: &str,
+) -> Result<Vec<Entity>, PrefabError> {
+    let prefab = world.get_resource::<PrefabLibrary>()?.resolve(name)?;
+    registry.instantiate(world, &prefab)
+}
+
+impl ComponentRegistry {
+    /// Spawn a resolved prefab and its children into `world`,
+    /// returning the spawned entities in depth-first order starting with the root
+    ///
+    /// Every component is constructed before anything is spawned,
+    /// so a failed instantiation leaves the world untouched.
+    pub fn instantiate(
+        &self,
+        world: &mut World,
+        prefab: &Prefab,
+    ) -> Result<Vec<Entity>, PrefabError> {
+        let mut entities = vec![];
+        self.construct_prefab(prefab, None, &mut entities)?;
+
+        let mut spawned: Vec<Entity> = Vec::with_capacity(entities.len());
+        for (parent, components) in entities {
+            let entity = world.spawn(());
+            for component in components {
+                component.insert(world, entity)?;
+            }
+
+            if let Some(parent) = parent {
+                set_parent(world, entity, spawned[parent])
+                    .expect("Freshly-spawned prefab hierarchy is invalid");
+            }
+
+            spawned.push(entity);
+        }
+
+        Ok(spawned)
+    }
+
+    fn construct_prefab(
+        &self,
+        prefab: &Prefab,
+        parent: Option<usize>,
+        entities: &mut Vec<(Option<usize>, Vec<ComponentValue>)>,
+    ) -> Result<(), PrefabError> {
+        if let Some(extends) = &prefab.extends {
+            return Err(PrefabError::Unresolved(extends.clone()));
+        }
+
+        let components = prefab
+            .components
+            .iter()
+            .map(|(name, json)| self.construct_from_json(name, json.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let index = entities.len();
+        entities.push((parent, components));
+
+        for child in &prefab.children {
+            self.construct_prefab(child, Some(index), entities)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_children, NamedEntityComponent, PositionComponent, ScaleComponent};
+    use serde_json::json;
+
+    #[test]
+    fn test_prefabs() {
+        let mut registry = ComponentRegistry::default();
+        registry.register_core_components();
+
+        let mut world = World::new();
+        world.insert_resource(
+            PrefabLibrary::from_json(
+                r#"{
+                    "door": {
+                        "components": {
+                            "name": "door",
+                            "position": [0.0, 0.0, 0.0],
+                            "scale": [1.0, 2.0, 1.0]
+                        },
+                        "children": [{ "components": { "name": "handle" } }]
+                    },
+                    "wide_door": {
+                        "extends": "door",
+                        "components": {
+                            "name": "wide_door",
+                            "scale": null,
+                            "local_scale": [2.0, 2.0, 1.0]
+                        },
+                        "children": [{ "extends": "door" }]
+                    },
+                    "loop_a": { "extends": "loop_b" },
+                    "loop_b": { "extends": "loop_a" }
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        let entities = instantiate_prefab(&registry, &mut world, "wide_door").unwrap();
+        assert_eq!(entities.len(), 4);
+
+        let root = entities[0];
+        assert_eq!(
+            **world.get::<NamedEntityComponent>(root).unwrap(),
+            "wide_door"
+        );
+        assert!(world.get::<PositionComponent>(root).is_ok());
+        assert!(world.get::<ScaleComponent>(root).is_err());
+
+        // Inherited children precede the prefab's own
+        let children = get_children(&world, root);
+        assert_eq!(children, [entities[1], entities[2]]);
+        assert_eq!(
+            **world.get::<NamedEntityComponent>(children[1]).unwrap(),
+            "door"
+        );
+        assert_eq!(get_children(&world, children[1]), [entities[3]]);
+
+        assert_eq!(
+            instantiate_prefab(&registry, &mut world, "loop_a"),
+            Err(PrefabError::Cycle("loop_a".into()))
+        );
+
+        // Failed instantiation spawns nothing
+        let len = world.len();
+        let prefab = Prefab::default()
+            .with_component("name", json!("valid"))
+            .with_child(Prefab::default().with_component("unregistered", json!(0)));
+        assert_eq!(
+            registry.instantiate(&mut world, &prefab),
+            Err(PrefabError::Registry(RegistryError::UnknownComponent(
+                "unregistered".into()
+            )))
+        );
+        assert_eq!(world.len(), len);
+    }
+
+    #[test]
+    fn test_prefab_overrides() {
+        let mut library = PrefabLibrary::default();
+        library.insert(
+            "base",
+            Prefab::default().with_component("config", json!({ "a": 1, "b": { "c": 2, "d": 3 } })),
+        );
+        library.insert(
+            "derived",
+            Prefab::extending("base").with_component("config", json!({ "b": { "d": 4 } })),
+        );
+
+        assert_eq!(
+            library.resolve("derived").unwrap().components["config"],
+            json!({ "a": 1, "b": { "c": 2, "d": 4 } })
+        );
+    }
+}
@@ -0,0 +1,664 @@
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    error::Error,
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use hecs::{Component, Entity, World};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::EntityRemap;
+
+/// String properties to construct a component from, such as those of a map entity
+pub type PropertyMap<'a> = BTreeMap<&'a str, &'a str>;
+
+type StrConstructor = Box<dyn Fn(&str) -> Result<ComponentValue, RegistryError> + Send + Sync>;
+type PropertiesConstructor =
+    Box<dyn Fn(&PropertyMap) -> Result<ComponentValue, RegistryError> + Send + Sync>;
+type JsonConstructor =
+    Box<dyn Fn(serde_json::Value) -> Result<ComponentValue, RegistryError> + Send + Sync>;
+type Inserter =
+    fn(&mut World, Entity, Box<dyn Any + Send + Sync>) -> Result<(), hecs::NoSuchEntity>;
+type EntityMapper = Box<dyn Fn(&mut World, Entity, &EntityRemap) + Send + Sync>;
+type Predicate = Box<dyn Fn(&World, Entity) -> bool + Send + Sync>;
+type Inspector<R> = Box<dyn Fn(&World, Entity) -> Result<R, RegistryError> + Send + Sync>;
+
+/// Error produced by a [`ComponentRegistry`] operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// No component is registered under this name
+    UnknownComponent(String),
+    /// The component was registered without the hook this operation needs
+    Unsupported {
+        component: &'static str,
+        operation: &'static str,
+    },
+    NoSuchEntity(Entity),
+    MissingComponent {
+        component: &'static str,
+        entity: Entity,
+    },
+    /// A constructor hook failed to build the component
+    Construct {
+        component: &'static str,
+        error: String,
+    },
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::UnknownComponent(name) => write!(f, "Unknown component {}", name),
+            RegistryError::Unsupported {
+                component,
+                operation,
+            } => write!(f, "Component {} does not support {}", component, operation),
+            RegistryError::NoSuchEntity(entity) => write!(f, "No such entity {:?}", entity),
+            RegistryError::MissingComponent { component, entity } => {
+                write!(f, "Entity {:?} has no {} component", entity, component)
+            }
+            RegistryError::Construct { component, error } => {
+                write!(f, "Failed to construct {}: {}", component, error)
+            }
+        }
+    }
+}
+
+impl Error for RegistryError {}
+
+/// Type-erased component value, produced by a [`ComponentRegistry`]
+pub struct ComponentValue {
+    name: &'static str,
+    value: Box<dyn Any + Send + Sync>,
+    insert: Inserter,
+}
+
+impl std::fmt::Debug for ComponentValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ComponentValue")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl ComponentValue {
+    fn new<T: Component>(name: &'static str, value: T) -> Self {
+        ComponentValue {
+            name,
+            value: Box::new(value),
+            insert: |world, entity, value| {
+                world.insert_one(entity, *value.downcast::<T>().unwrap())
+            },
+        }
+    }
+
+    /// The registered name of this component
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn downcast<T: Component>(self) -> Result<T, Self> {
+        if self.value.is::<T>() {
+            Ok(*self.value.downcast::<T>().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Insert this value into `entity`, replacing any existing component of the same type
+    pub fn insert(self, world: &mut World, entity: Entity) -> Result<(), RegistryError> {
+        (self.insert)(world, entity, self.value).map_err(|_| RegistryError::NoSuchEntity(entity))
+    }
+}
+
+/// Runtime information and hooks for a registered component type
+pub struct ComponentRegistration {
+    name: &'static str,
+    type_id: TypeId,
+    type_name: &'static str,
+    contains: fn(&World, Entity) -> bool,
+    remove: fn(&mut World, Entity) -> bool,
+    from_str: Option<StrConstructor>,
+    from_properties: Option<PropertiesConstructor>,
+    from_json: Option<JsonConstructor>,
+    clone: Option<Inspector<ComponentValue>>,
+    debug: Option<Inspector<String>>,
+    to_json: Option<Inspector<serde_json::Value>>,
+    serialize_if: Option<Predicate>,
+    map_entities: Option<EntityMapper>,
+}
+
+impl ComponentRegistration {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns true if the component can be saved to and restored from a snapshot
+    pub fn is_serializable(&self) -> bool {
+        self.to_json.is_some() && self.from_json.is_some()
+    }
+
+    /// Returns true if the component held by `entity` should be saved to a snapshot
+    pub fn is_serializable_for(&self, world: &World, entity: Entity) -> bool {
+        self.is_serializable()
+            && self
+                .serialize_if
+                .as_ref()
+                .is_none_or(|serialize_if| serialize_if(world, entity))
+    }
+
+    pub(crate) fn map_entities(&self, world: &mut World, entity: Entity, remap: &EntityRemap) {
+        if let Some(map_entities) = &self.map_entities {
+            map_entities(world, entity, remap)
+        }
+    }
+
+    fn unsupported(&self, operation: &'static str) -> RegistryError {
+        RegistryError::Unsupported {
+            component: self.name,
+            operation,
+        }
+    }
+}
+
+/// Adds optional hooks to a newly registered component
+pub struct RegistrationBuilder<'a, T> {
+    registration: &'a mut ComponentRegistration,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Component> RegistrationBuilder<'a, T> {
+    /// Construct the component from a single string
+    pub fn from_str<F, E>(self, f: F) -> Self
+    where
+        F: Fn(&str) -> Result<T, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        let name = self.registration.name;
+        self.registration.from_str = Some(Box::new(move |string: &str| {
+            f(string)
+                .map(|value| ComponentValue::new(name, value))
+                .map_err(|e| construct_error(name, e))
+        }));
+        self
+    }
+
+    /// Construct the component by parsing a string as V and converting it
+    pub fn parse<V>(self) -> Self
+    where
+        V: FromStr + Into<T>,
+        V::Err: std::fmt::Display,
+    {
+        self.from_str(|string| string.parse::<V>().map(Into::into))
+    }
+
+    /// Construct the component from a set of properties
+    pub fn from_properties<F, E>(self, f: F) -> Self
+    where
+        F: Fn(&PropertyMap) -> Result<T, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        let name = self.registration.name;
+        self.registration.from_properties = Some(Box::new(move |properties: &PropertyMap| {
+            f(properties)
+                .map(|value| ComponentValue::new(name, value))
+                .map_err(|e| construct_error(name, e))
+        }));
+        self
+    }
+
+    pub fn cloneable(self) -> Self
+    where
+        T: Clone,
+    {
+        let name = self.registration.name;
+        self.registration.clone = Some(Box::new(move |world: &World, entity| {
+            let value = get::<T>(world, entity, name)?;
+            Ok(ComponentValue::new(name, T::clone(&value)))
+        }));
+        self
+    }
+
+    pub fn debuggable(self) -> Self
+    where
+        T: std::fmt::Debug,
+    {
+        let name = self.registration.name;
+        self.registration.debug = Some(Box::new(move |world: &World, entity| {
+            Ok(format!("{:?}", *get::<T>(world, entity, name)?))
+        }));
+        self
+    }
+
+    /// Convert the component to and from JSON
+    pub fn serde(self) -> Self
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let name = self.registration.name;
+        self.registration.from_json = Some(Box::new(move |json| {
+            serde_json::from_value::<T>(json)
+                .map(|value| ComponentValue::new(name, value))
+                .map_err(|e| construct_error(name, e))
+        }));
+        self.registration.to_json = Some(Box::new(move |world: &World, entity| {
+            serde_json::to_value(&*get::<T>(world, entity, name)?)
+                .map_err(|e| construct_error(name, e))
+        }));
+        self
+    }
+
+    /// Only snapshot values of the component for which `f` returns true
+    pub fn serialize_if<F>(self, f: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.registration.serialize_if = Some(Box::new(move |world: &World, entity| {
+            world.get::<T>(entity).is_ok_and(|component| f(&component))
+        }));
+        self
+    }
+
+    /// Rewrite the entities referred to by the component when it is restored from a snapshot
+    ///
+    /// The component is removed if `f` returns false,
+    /// such as when it refers to an entity that was not part of the snapshot.
+    pub fn map_entities<F>(self, f: F) -> Self
+    where
+        F: Fn(&mut T, &EntityRemap) -> bool + Send + Sync + 'static,
+    {
+        self.registration.map_entities = Some(Box::new(move |world: &mut World, entity, remap| {
+            let keep = match world.get_mut::<T>(entity) {
+                Ok(mut component) => f(&mut component, remap),
+                Err(_) => return,
+            };
+
+            if !keep {
+                world.remove_one::<T>(entity).ok();
+            }
+        }));
+        self
+    }
+}
+
+fn construct_error(component: &'static str, error: impl std::fmt::Display) -> RegistryError {
+    RegistryError::Construct {
+        component,
+        error: error.to_string(),
+    }
+}
+
+fn get<'a, T: Component>(
+    world: &'a World,
+    entity: Entity,
+    component: &'static str,
+) -> Result<hecs::Ref<'a, T>, RegistryError> {
+    world.get::<T>(entity).map_err(|e| match e {
+        hecs::ComponentError::NoSuchEntity => RegistryError::NoSuchEntity(entity),
+        hecs::ComponentError::MissingComponent(_) => {
+            RegistryError::MissingComponent { component, entity }
+        }
+    })
+}
+
+/// Registry of component types under stable names,
+/// allowing components to be constructed, inspected and copied at runtime
+///
+/// Every registered component can be detected and removed by name;
+/// construction, cloning, debug printing and serialization require
+/// the corresponding hook to be added via [`RegistrationBuilder`].
+#[derive(Default)]
+pub struct ComponentRegistry {
+    registrations: BTreeMap<&'static str, ComponentRegistration>,
+    names: BTreeMap<TypeId, &'static str>,
+}
+
+impl ComponentRegistry {
+    /// Register T under `name`, replacing any existing registration of either
+    pub fn register<T: Component>(&mut self, name: &'static str) -> RegistrationBuilder<'_, T> {
+        if let Some(previous) = self.names.insert(TypeId::of::<T>(), name) {
+            self.registrations.remove(previous);
+        }
+
+        if let Some(previous) = self.registrations.remove(name) {
+            self.names.remove(&previous.type_id);
+        }
+
+        let registration = self
+            .registrations
+            .entry(name)
+            .or_insert(ComponentRegistration {
+                name,
+                type_id: TypeId::of::<T>(),
+                type_name: std::any::type_name::<T>(),
+                contains: |world, entity| world.get::<T>(entity).is_ok(),
+                remove: |world, entity| world.remove_one::<T>(entity).is_ok(),
+                from_str: None,
+                from_properties: None,
+                from_json: None,
+                clone: None,
+                debug: None,
+                to_json: None,
+                serialize_if: None,
+                map_entities: None,
+            });
+
+        RegistrationBuilder {
+            registration,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<&ComponentRegistration, RegistryError> {
+        self.registrations
+            .get(name)
+            .ok_or_else(|| RegistryError::UnknownComponent(name.to_string()))
+    }
+
+    /// The name T is registered under
+    pub fn name_of<T: Component>(&self) -> Option<&'static str> {
+        self.names.get(&TypeId::of::<T>()).copied()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.registrations.keys().copied()
+    }
+
+    /// The names of the registered components held by `entity`
+    pub fn components_of(&self, world: &World, entity: Entity) -> Vec<&'static str> {
+        self.registrations
+            .values()
+            .filter(|registration| (registration.contains)(world, entity))
+            .map(ComponentRegistration::name)
+            .collect()
+    }
+
+    pub fn contains(
+        &self,
+        world: &World,
+        entity: Entity,
+        name: &str,
+    ) -> Result<bool, RegistryError> {
+        Ok((self.get(name)?.contains)(world, entity))
+    }
+
+    /// Remove a component by name, returning false if the entity did not have one
+    pub fn remove(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        name: &str,
+    ) -> Result<bool, RegistryError> {
+        Ok((self.get(name)?.remove)(world, entity))
+    }
+
+    pub fn construct_from_str(
+        &self,
+        name: &str,
+        string: &str,
+    ) -> Result<ComponentValue, RegistryError> {
+        let registration = self.get(name)?;
+        let from_str = registration
+            .from_str
+            .as_ref()
+            .ok_or_else(|| registration.unsupported("construction from a string"))?;
+        from_str(string)
+    }
+
+    /// Construct a component from properties
+    ///
+    /// Components without a properties constructor
+    /// are constructed from the property matching their name, if one exists.
+    pub fn construct_from_properties(
+        &self,
+        name: &str,
+        properties: &PropertyMap,
+    ) -> Result<ComponentValue, RegistryError> {
+        let registration = self.get(name)?;
+        match (&registration.from_properties, &registration.from_str) {
+            (Some(from_properties), _) => from_properties(properties),
+            (None, Some(from_str)) => match properties.get(name) {
+                Some(string) => from_str(string),
+                None => Err(construct_error(registration.name, "Missing property")),
+            },
+            (None, None) => Err(registration.unsupported("construction from properties")),
+        }
+    }
+
+    pub fn construct_from_json(
+        &self,
+        name: &str,
+        json: serde_json::Value,
+    ) -> Result<ComponentValue, RegistryError> {
+        let registration = self.get(name)?;
+        let from_json = registration
+            .from_json
+            .as_ref()
+            .ok_or_else(|| registration.unsupported("deserialization"))?;
+        from_json(json)
+    }
+
+    /// Clone a component from `entity`, for insertion into any entity of any world
+    pub fn clone_component(
+        &self,
+        world: &World,
+        entity: Entity,
+        name: &str,
+    ) -> Result<ComponentValue, RegistryError> {
+        let registration = self.get(name)?;
+        let clone = registration
+            .clone
+            .as_ref()
+            .ok_or_else(|| registration.unsupported("cloning"))?;
+        clone(world, entity)
+    }
+
+    pub fn debug(
+        &self,
+        world: &World,
+        entity: Entity,
+        name: &str,
+    ) -> Result<String, RegistryError> {
+        let registration = self.get(name)?;
+        let debug = registration
+            .debug
+            .as_ref()
+            .ok_or_else(|| registration.unsupported("debug printing"))?;
+        debug(world, entity)
+    }
+
+    pub fn to_json(
+        &self,
+        world: &World,
+        entity: Entity,
+        name: &str,
+    ) -> Result<serde_json::Value, RegistryError> {
+        let registration = self.get(name)?;
+        let to_json = registration
+            .to_json
+            .as_ref()
+            .ok_or_else(|| registration.unsupported("serialization"))?;
+        to_json(world, entity)
+    }
+
+    /// Construct each component with a matching property and insert it into `entity`,
+    /// returning the names of those inserted
+    ///
+    /// Properties without a matching component are ignored.
+    pub fn insert_from_properties(
+        &self,
+        world: &mut World,
+        entity: Entity,
+        properties: &PropertyMap,
+    ) -> Result<Vec<&'static str>, RegistryError> {
+        let mut inserted = vec![];
+        for registration in self.registrations.values() {
+            let matches = match registration.from_properties {
+                Some(_) => properties
+                    .keys()
+                    .any(|key| property_component(key) == registration.name),
+                None => {
+                    registration.from_str.is_some() && properties.contains_key(registration.name)
+                }
+            };
+
+            if matches {
+                self.construct_from_properties(registration.name, properties)?
+                    .insert(world, entity)?;
+                inserted.push(registration.name);
+            }
+        }
+        Ok(inserted)
+    }
+}
+
+/// The component a property key refers to, given keys of the form `component` or `component.field`
+fn property_component(key: &str) -> &str {
+    key.split('.').next().unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Construct, Usage};
+
+    enum Speed {}
+    type SpeedComponent = Usage<Speed, f32>;
+
+    #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Mover {
+        open: bool,
+        distance: f32,
+    }
+
+    fn registry() -> ComponentRegistry {
+        let mut registry = ComponentRegistry::default();
+        registry
+            .register::<SpeedComponent>("speed")
+            .parse::<f32>()
+            .cloneable()
+            .debuggable();
+        registry
+            .register::<Mover>("mover")
+            .from_properties(|properties| -> Result<Mover, Box<dyn Error>> {
+                Ok(Mover {
+                    open: properties.get("mover.open").ok_or("No open")?.parse()?,
+                    distance: properties
+                        .get("mover.distance")
+                        .ok_or("No distance")?
+                        .parse()?,
+                })
+            })
+            .serde();
+        registry
+    }
+
+    #[test]
+    fn test_registry_properties() {
+        let registry = registry();
+        assert_eq!(registry.name_of::<Mover>(), Some("mover"));
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["mover", "speed"]);
+
+        let mut world = World::new();
+        let entity = world.spawn(());
+
+        let properties: PropertyMap = [
+            ("speed", "2.5"),
+            ("mover.open", "true"),
+            ("mover.distance", "8"),
+            ("classname", "func_door"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            registry.insert_from_properties(&mut world, entity, &properties),
+            Ok(vec!["mover", "speed"])
+        );
+        assert_eq!(registry.components_of(&world, entity), ["mover", "speed"]);
+        assert_eq!(**world.get::<SpeedComponent>(entity).unwrap(), 2.5);
+        assert_eq!(
+            *world.get::<Mover>(entity).unwrap(),
+            Mover {
+                open: true,
+                distance: 8.0
+            }
+        );
+
+        assert!(matches!(
+            registry.construct_from_str("speed", "fast"),
+            Err(RegistryError::Construct {
+                component: "speed",
+                ..
+            })
+        ));
+        assert_eq!(
+            registry.construct_from_str("mover", "open").unwrap_err(),
+            RegistryError::Unsupported {
+                component: "mover",
+                operation: "construction from a string"
+            }
+        );
+        assert_eq!(
+            registry.construct_from_str("gravity", "1").unwrap_err(),
+            RegistryError::UnknownComponent("gravity".into())
+        );
+    }
+
+    #[test]
+    fn test_registry_inspection() {
+        let registry = registry();
+
+        let mut world = World::new();
+        let entity = world.spawn((
+            SpeedComponent::construct(4.0),
+            Mover {
+                open: false,
+                distance: 2.0,
+            },
+        ));
+
+        assert_eq!(
+            registry.debug(&world, entity, "speed").unwrap(),
+            format!("{:?}", SpeedComponent::construct(4.0))
+        );
+
+        let json = registry.to_json(&world, entity, "mover").unwrap();
+        assert_eq!(json, serde_json::json!({ "open": false, "distance": 2.0 }));
+
+        // Copy components by name into another world
+        let mut other = World::new();
+        let copy = other.spawn(());
+        registry
+            .clone_component(&world, entity, "speed")
+            .unwrap()
+            .insert(&mut other, copy)
+            .unwrap();
+        registry
+            .construct_from_json("mover", json)
+            .unwrap()
+            .insert(&mut other, copy)
+            .unwrap();
+        assert_eq!(registry.components_of(&other, copy), ["mover", "speed"]);
+
+        assert_eq!(registry.remove(&mut other, copy, "speed"), Ok(true));
+        assert_eq!(registry.contains(&other, copy, "speed"), Ok(false));
+        assert_eq!(
+            registry.debug(&other, copy, "speed").unwrap_err(),
+            RegistryError::MissingComponent {
+                component: "speed",
+                entity: copy
+            }
+        );
+    }
+}
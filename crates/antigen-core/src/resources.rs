@@ -0,0 +1,141 @@
+use hecs::{Component, DynamicBundle, Entity, Query, QueryItem, Ref, RefMut, World};
+
+/// Marks the entity holding a world's resources
+pub struct ResourceEntity;
+
+/// Error produced when a resource is requested from a world that does not hold one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MissingResource(pub &'static str);
+
+impl std::fmt::Display for MissingResource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Missing resource {}", self.0)
+    }
+}
+
+impl std::error::Error for MissingResource {}
+
+/// Typed store of singleton values, at most one per type
+///
+/// Resources are held as components of a single entity,
+/// so are subject to the same borrow checking and scheduler access declarations as components.
+pub trait Resources {
+    /// The entity holding this world's resources, if any have been inserted
+    fn resource_entity(&self) -> Option<Entity>;
+
+    /// Insert a resource, returning the one it replaced
+    fn insert_resource<T: Component>(&mut self, resource: T) -> Option<T>;
+
+    /// Insert each component of `bundle` as a resource, replacing any existing ones
+    fn insert_resources(&mut self, bundle: impl DynamicBundle);
+
+    fn contains_resource<T: Component>(&self) -> bool;
+
+    fn get_resource<T: Component>(&self) -> Result<Ref<'_, T>, MissingResource>;
+
+    fn get_resource_mut<T: Component>(&self) -> Result<RefMut<'_, T>, MissingResource>;
+
+    fn remove_resource<T: Component>(&mut self) -> Result<T, MissingResource>;
+
+    /// Query several resources at once
+    fn query_resources_mut<Q: Query>(&mut self) -> Result<QueryItem<'_, Q>, MissingResource>;
+}
+
+impl Resources for World {
+    fn resource_entity(&self) -> Option<Entity> {
+        self.query::<()>()
+            .with::<ResourceEntity>()
+            .iter()
+            .next()
+            .map(|(entity, _)| entity)
+    }
+
+    fn insert_resource<T: Component>(&mut self, resource: T) -> Option<T> {
+        let previous = self.remove_resource::<T>().ok();
+        self.insert_resources((resource,));
+        previous
+    }
+
+    fn insert_resources(&mut self, bundle: impl DynamicBundle) {
+        match self.resource_entity() {
+            Some(entity) => self.insert(entity, bundle).unwrap(),
+            None => {
+                let entity = self.spawn((ResourceEntity,));
+                self.insert(entity, bundle).unwrap();
+            }
+        }
+    }
+
+    fn contains_resource<T: Component>(&self) -> bool {
+        self.resource_entity()
+            .and_then(|entity| self.entity(entity).ok())
+            .map(|entity| entity.has::<T>())
+            .unwrap_or_default()
+    }
+
+    fn get_resource<T: Component>(&self) -> Result<Ref<'_, T>, MissingResource> {
+        self.resource_entity()
+            .and_then(|entity| self.get::<T>(entity).ok())
+            .ok_or_else(missing::<T>)
+    }
+
+    fn get_resource_mut<T: Component>(&self) -> Result<RefMut<'_, T>, MissingResource> {
+        self.resource_entity()
+            .and_then(|entity| self.get_mut::<T>(entity).ok())
+            .ok_or_else(missing::<T>)
+    }
+
+    fn remove_resource<T: Component>(&mut self) -> Result<T, MissingResource> {
+        self.resource_entity()
+            .and_then(|entity| self.remove_one::<T>(entity).ok())
+            .ok_or_else(missing::<T>)
+    }
+
+    fn query_resources_mut<Q: Query>(&mut self) -> Result<QueryItem<'_, Q>, MissingResource> {
+        let entity = self.resource_entity().ok_or_else(missing::<Q>)?;
+        self.query_one_mut::<Q>(entity).map_err(|_| missing::<Q>())
+    }
+}
+
+fn missing<T>() -> MissingResource {
+    MissingResource(std::any::type_name::<T>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Gravity(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Timestep(i32);
+
+    #[test]
+    fn test_resources() {
+        let mut world = World::new();
+        assert_eq!(world.resource_entity(), None);
+        assert_eq!(
+            world.get_resource::<Gravity>().err(),
+            Some(MissingResource(std::any::type_name::<Gravity>()))
+        );
+
+        assert_eq!(world.insert_resource(Gravity(-10)), None);
+        assert_eq!(world.insert_resource(Gravity(-20)), Some(Gravity(-10)));
+        world.insert_resources((Timestep(60),));
+        assert_eq!(world.len(), 1);
+
+        world.get_resource_mut::<Timestep>().unwrap().0 = 30;
+        {
+            let (gravity, timestep) = world
+                .query_resources_mut::<(&Gravity, &Timestep)>()
+                .unwrap();
+            assert_eq!((gravity.0, timestep.0), (-20, 30));
+        }
+
+        assert_eq!(world.remove_resource::<Gravity>(), Ok(Gravity(-20)));
+        assert!(!world.contains_resource::<Gravity>());
+        assert!(world.contains_resource::<Timestep>());
+        assert!(world.query_resources_mut::<&Gravity>().is_err());
+    }
+}
@@ -0,0 +1,610 @@
+use hecs::{Component, World};
+use rayon::iter::{IntoParallelRefMutIterator, ParallelIterator};
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use crate::{apply_commands, init_commands, init_observed_ticks};
+
+/// Components read and written by a system
+#[derive(Debug, Default, Clone)]
+pub struct Access {
+    reads: BTreeMap<TypeId, &'static str>,
+    writes: BTreeMap<TypeId, &'static str>,
+}
+
+impl Access {
+    pub fn read<T: Component>(&mut self) -> &mut Self {
+        self.reads
+            .insert(TypeId::of::<T>(), std::any::type_name::<T>());
+        self
+    }
+
+    pub fn write<T: Component>(&mut self) -> &mut Self {
+        self.writes
+            .insert(TypeId::of::<T>(), std::any::type_name::<T>());
+        self
+    }
+
+    /// Returns the name of a component accessed by both, where at least one access is a write
+    pub fn conflict(&self, other: &Access) -> Option<&'static str> {
+        let writes_read = |writes: &BTreeMap<TypeId, &'static str>, access: &Access| {
+            writes
+                .iter()
+                .find(|(id, _)| access.reads.contains_key(id) || access.writes.contains_key(id))
+                .map(|(_, name)| *name)
+        };
+
+        writes_read(&self.writes, other).or_else(|| writes_read(&other.writes, self))
+    }
+}
+
+enum SystemFn {
+    Exclusive(Box<dyn FnMut(&mut World) + Send>),
+    Shared(Box<dyn FnMut(&World) + Send>),
+}
+
+/// A system function, along with its data access and ordering constraints
+///
+/// Shared systems only borrow components, and run in parallel with non-conflicting systems.
+/// Their declared access must cover every component they query,
+/// as an undeclared conflict will panic when the world detects the overlapping borrow.
+///
+/// Exclusive systems have unrestricted world access, and run alone.
+/// They are ordered with respect to every other system in the order they were added.
+pub struct System {
+    name: &'static str,
+    access: Access,
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    f: SystemFn,
+}
+
+impl System {
+    pub fn exclusive<F>(f: F) -> Self
+    where
+        F: FnMut(&mut World) + Send + 'static,
+    {
+        System::new(std::any::type_name::<F>(), SystemFn::Exclusive(Box::new(f)))
+    }
+
+    pub fn shared<F>(f: F) -> Self
+    where
+        F: FnMut(&World) + Send + 'static,
+    {
+        System::new(std::any::type_name::<F>(), SystemFn::Shared(Box::new(f)))
+    }
+
+    fn new(name: &'static str, f: SystemFn) -> Self {
+        System {
+            name,
+            access: Default::default(),
+            labels: Default::default(),
+            before: Default::default(),
+            after: Default::default(),
+            f,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn access(&self) -> &Access {
+        &self.access
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        matches!(self.f, SystemFn::Exclusive(_))
+    }
+
+    /// Override the name used for this system in errors and traces,
+    /// which defaults to the type name of its function
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn reads<T: Component>(mut self) -> Self {
+        self.access.read::<T>();
+        self
+    }
+
+    pub fn writes<T: Component>(mut self) -> Self {
+        self.access.write::<T>();
+        self
+    }
+
+    /// Add a label that other systems can be ordered against
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    /// Run this system before every system with the given label
+    pub fn before(mut self, label: &'static str) -> Self {
+        self.before.push(label);
+        self
+    }
+
+    /// Run this system after every system with the given label
+    pub fn after(mut self, label: &'static str) -> Self {
+        self.after.push(label);
+        self
+    }
+
+    fn run(&mut self, world: &mut World) {
+        let _span = tracing::trace_span!("system", name = self.name).entered();
+        match &mut self.f {
+            SystemFn::Exclusive(f) => f(world),
+            SystemFn::Shared(f) => f(world),
+        }
+    }
+
+    fn run_shared(&mut self, world: &World) {
+        let _span = tracing::trace_span!("system", name = self.name).entered();
+        match &mut self.f {
+            SystemFn::Shared(f) => f(world),
+            SystemFn::Exclusive(_) => {
+                unreachable!("Exclusive system {} scheduled in parallel", self.name)
+            }
+        }
+    }
+}
+
+/// Error produced when a set of systems cannot be scheduled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// A system is ordered against a label that no system has
+    UnknownLabel {
+        system: &'static str,
+        label: &'static str,
+    },
+    /// The ordering constraints between these systems form a cycle
+    Cycle(Vec<&'static str>),
+    /// Two shared systems access the same component, and at least one writes it,
+    /// but neither is ordered before the other
+    Conflict {
+        first: &'static str,
+        second: &'static str,
+        component: &'static str,
+    },
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::UnknownLabel { system, label } => {
+                write!(
+                    f,
+                    "System {} is ordered against unknown label {}",
+                    system, label
+                )
+            }
+            ScheduleError::Cycle(systems) => {
+                write!(f, "Ordering cycle: {}", systems.join(" -> "))
+            }
+            ScheduleError::Conflict {
+                first,
+                second,
+                component,
+            } => write!(
+                f,
+                "Systems {} and {} both access {} without an ordering between them",
+                first, second, component
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Collects systems and their constraints for validation into a Schedule
+#[derive(Default)]
+pub struct ScheduleBuilder {
+    systems: Vec<System>,
+}
+
+impl ScheduleBuilder {
+    pub fn add(&mut self, system: System) -> &mut Self {
+        self.systems.push(system);
+        self
+    }
+
+    /// Validate the added systems and group them into stages,
+    /// leaving the builder empty
+    pub fn build(&mut self) -> Result<Schedule, ScheduleError> {
+        let systems = std::mem::take(&mut self.systems);
+        let successors = ordering(&systems)?;
+        let order = topological_order(&systems, &successors)?;
+
+        // Every system reachable from each system
+        let mut descendants = vec![BTreeSet::<usize>::new(); systems.len()];
+        for &i in order.iter().rev() {
+            let mut reachable = BTreeSet::new();
+            for &successor in &successors[i] {
+                reachable.insert(successor);
+                reachable.extend(descendants[successor].iter().copied());
+            }
+            descendants[i] = reachable;
+        }
+
+        for (i, first) in systems.iter().enumerate() {
+            for (j, second) in systems.iter().enumerate().skip(i + 1) {
+                if descendants[i].contains(&j) || descendants[j].contains(&i) {
+                    continue;
+                }
+
+                if let Some(component) = first.access.conflict(&second.access) {
+                    return Err(ScheduleError::Conflict {
+                        first: first.name,
+                        second: second.name,
+                        component,
+                    });
+                }
+            }
+        }
+
+        // Place each system one stage after the latest of its predecessors
+        let mut depths = vec![0; systems.len()];
+        for &i in &order {
+            for &successor in &successors[i] {
+                depths[successor] = depths[successor].max(depths[i] + 1);
+            }
+        }
+
+        let stage_count = depths
+            .iter()
+            .max()
+            .map(|depth| depth + 1)
+            .unwrap_or_default();
+        let mut stages = (0..stage_count).map(|_| Vec::new()).collect::<Vec<_>>();
+        for (system, depth) in systems.into_iter().zip(depths) {
+            stages[depth].push(system);
+        }
+
+        Ok(Schedule { stages })
+    }
+}
+
+/// Build the ordering graph for a set of systems as a list of successors per system
+fn ordering(systems: &[System]) -> Result<Vec<BTreeSet<usize>>, ScheduleError> {
+    let mut labels = BTreeMap::<&'static str, Vec<usize>>::new();
+    for (i, system) in systems.iter().enumerate() {
+        for label in &system.labels {
+            labels.entry(label).or_default().push(i);
+        }
+    }
+
+    let labelled = |system: &System, label: &'static str| {
+        labels.get(label).ok_or(ScheduleError::UnknownLabel {
+            system: system.name,
+            label,
+        })
+    };
+
+    let mut successors = vec![BTreeSet::new(); systems.len()];
+    for (i, system) in systems.iter().enumerate() {
+        for label in &system.before {
+            for &j in labelled(system, label)? {
+                if i != j {
+                    successors[i].insert(j);
+                }
+            }
+        }
+
+        for label in &system.after {
+            for &j in labelled(system, label)? {
+                if i != j {
+                    successors[j].insert(i);
+                }
+            }
+        }
+
+        if system.is_exclusive() {
+            for predecessor in &mut successors[..i] {
+                predecessor.insert(i);
+            }
+            for j in i + 1..systems.len() {
+                successors[i].insert(j);
+            }
+        }
+    }
+
+    Ok(successors)
+}
+
+/// Order systems such that each precedes its successors, preferring the order they were added
+fn topological_order(
+    systems: &[System],
+    successors: &[BTreeSet<usize>],
+) -> Result<Vec<usize>, ScheduleError> {
+    let mut predecessors = vec![0; systems.len()];
+    for successor in successors.iter().flatten() {
+        predecessors[*successor] += 1;
+    }
+
+    let mut ready = (0..systems.len())
+        .filter(|i| predecessors[*i] == 0)
+        .collect::<BTreeSet<_>>();
+
+    let mut order = Vec::with_capacity(systems.len());
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+        for &successor in &successors[i] {
+            predecessors[successor] -= 1;
+            if predecessors[successor] == 0 {
+                ready.insert(successor);
+            }
+        }
+    }
+
+    if order.len() < systems.len() {
+        return Err(ScheduleError::Cycle(find_cycle(systems, successors)));
+    }
+
+    Ok(order)
+}
+
+/// Find the names of the systems along a cycle in the ordering graph
+fn find_cycle(systems: &[System], successors: &[BTreeSet<usize>]) -> Vec<&'static str> {
+    fn visit(
+        i: usize,
+        successors: &[BTreeSet<usize>],
+        visited: &mut BTreeSet<usize>,
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<usize>> {
+        if let Some(start) = path.iter().position(|j| *j == i) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(i);
+            return Some(cycle);
+        }
+
+        if !visited.insert(i) {
+            return None;
+        }
+
+        path.push(i);
+        for &successor in &successors[i] {
+            if let Some(cycle) = visit(successor, successors, visited, path) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+
+        None
+    }
+
+    let mut visited = BTreeSet::new();
+    (0..systems.len())
+        .find_map(|i| visit(i, successors, &mut visited, &mut vec![]))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|i| systems[i].name)
+        .collect()
+}
+
+/// A validated set of systems, grouped into stages that run in sequence
+///
+/// Systems within a stage have no ordering constraints or conflicting access between them,
+/// and are run in parallel.
+/// [`Commands`](crate::Commands) recorded by a stage are applied before the next begins.
+pub struct Schedule {
+    stages: Vec<Vec<System>>,
+}
+
+impl Schedule {
+    pub fn builder() -> ScheduleBuilder {
+        ScheduleBuilder::default()
+    }
+
+    /// The names of the systems in each stage
+    pub fn stages(&self) -> Vec<Vec<&'static str>> {
+        self.stages
+            .iter()
+            .map(|stage| stage.iter().map(System::name).collect())
+            .collect()
+    }
+
+    pub fn run(&mut self, world: &mut World) {
+        init_observed_ticks(world);
+        init_commands(world);
+
+        for stage in &mut self.stages {
+            match stage.as_mut_slice() {
+                [system] => system.run(world),
+                systems => {
+                    let world = &*world;
+                    systems
+                        .par_iter_mut()
+                        .for_each(|system| system.run_shared(world));
+                }
+            }
+
+            apply_commands(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Resources;
+
+    struct Position(i32);
+    struct Velocity(i32);
+    struct Health(i32);
+
+    fn apply_velocity(world: &World) {
+        for (_, (position, velocity)) in world.query::<(&mut Position, &Velocity)>().iter() {
+            position.0 += velocity.0;
+        }
+    }
+
+    fn regenerate(world: &World) {
+        for (_, health) in world.query::<&mut Health>().iter() {
+            health.0 += 1;
+        }
+    }
+
+    fn stages(builder: &mut ScheduleBuilder) -> Vec<Vec<&'static str>> {
+        builder.build().unwrap().stages()
+    }
+
+    #[test]
+    fn test_schedule_stages() {
+        let mut world = World::new();
+        world.spawn((Position(0), Velocity(2), Health(0)));
+
+        // Non-conflicting systems share a stage
+        let mut schedule = Schedule::builder()
+            .add(
+                System::shared(apply_velocity)
+                    .named("movement")
+                    .reads::<Velocity>()
+                    .writes::<Position>(),
+            )
+            .add(
+                System::shared(regenerate)
+                    .named("regeneration")
+                    .writes::<Health>(),
+            )
+            .add(
+                System::exclusive(|world: &mut World| {
+                    world.spawn((Health(10),));
+                })
+                .named("spawn"),
+            )
+            .add(
+                System::shared(regenerate)
+                    .named("late_regeneration")
+                    .writes::<Health>(),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            schedule.stages(),
+            vec![
+                vec!["movement", "regeneration"],
+                vec!["spawn"],
+                vec!["late_regeneration"]
+            ]
+        );
+
+        schedule.run(&mut world);
+
+        let mut health = world
+            .query::<&Health>()
+            .iter()
+            .map(|(_, health)| health.0)
+            .collect::<Vec<_>>();
+        health.sort_unstable();
+        assert_eq!(health, vec![2, 11]);
+        assert_eq!(world.query::<&Position>().iter().next().unwrap().1 .0, 2);
+
+        // Ordering constraints override the order systems were added in
+        assert_eq!(
+            stages(
+                Schedule::builder()
+                    .add(
+                        System::shared(regenerate)
+                            .named("second")
+                            .label("second_label")
+                            .after("first")
+                    )
+                    .add(
+                        System::shared(regenerate)
+                            .named("third")
+                            .after("second_label")
+                    )
+                    .add(
+                        System::shared(regenerate)
+                            .named("first")
+                            .label("first")
+                            .before("second_label")
+                    )
+                    .add(System::shared(regenerate).named("free"))
+            ),
+            vec![vec!["first", "free"], vec!["second"], vec!["third"]]
+        );
+    }
+
+    #[test]
+    fn test_schedule_errors() {
+        assert_eq!(
+            Schedule::builder()
+                .add(System::shared(regenerate).named("a").after("missing"))
+                .build()
+                .err(),
+            Some(ScheduleError::UnknownLabel {
+                system: "a",
+                label: "missing"
+            })
+        );
+
+        assert_eq!(
+            Schedule::builder()
+                .add(System::shared(regenerate).named("a").label("a").after("c"))
+                .add(System::shared(regenerate).named("b").label("b").after("a"))
+                .add(System::shared(regenerate).named("c").label("c").after("b"))
+                .build()
+                .err(),
+            Some(ScheduleError::Cycle(vec!["a", "b", "c", "a"]))
+        );
+
+        assert_eq!(
+            Schedule::builder()
+                .add(System::shared(regenerate).named("a").writes::<Health>())
+                .add(System::shared(regenerate).named("b").reads::<Health>())
+                .build()
+                .err(),
+            Some(ScheduleError::Conflict {
+                first: "a",
+                second: "b",
+                component: std::any::type_name::<Health>(),
+            })
+        );
+
+        // Conflicts are resolved by ordering either way
+        assert!(Schedule::builder()
+            .add(System::shared(regenerate).writes::<Health>().after("b"))
+            .add(System::shared(regenerate).reads::<Health>().label("b"))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_schedule_commands() {
+        let mut world = World::new();
+        world.spawn((Health(0),));
+
+        // Commands recorded during a stage are visible to the next
+        let mut schedule = Schedule::builder()
+            .add(
+                System::shared(|world: &World| {
+                    let commands = world.get_resource::<crate::Commands>().unwrap();
+                    for (entity, _) in world.query::<&Health>().iter() {
+                        commands.insert(entity, (Position(0), Velocity(3)));
+                    }
+                })
+                .named("insert")
+                .reads::<Health>()
+                .label("insert"),
+            )
+            .add(
+                System::shared(apply_velocity)
+                    .named("movement")
+                    .reads::<Velocity>()
+                    .writes::<Position>()
+                    .after("insert"),
+            )
+            .build()
+            .unwrap();
+
+        schedule.run(&mut world);
+        assert_eq!(world.query::<&Position>().iter().next().unwrap().1 .0, 3);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::BTreeMap;
+
+use hecs::{Entity, World};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ChildrenComponent, ComponentRegistry, Construct, LocalPositionComponent,
+    LocalRotationComponent, LocalScaleComponent, NamedEntityComponent, ParentComponent,
+    PositionComponent, RegistryError, RotationComponent, ScaleComponent,
+};
+
+/// Serializable components of a single entity, keyed by registered name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    pub entity: Entity,
+    pub components: BTreeMap<String, serde_json::Value>,
+}
+
+/// Serializable copy of the registered components in a world
+///
+/// Entities are recorded by their ID in the source world,
+/// and are remapped to newly-spawned ones when restored.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub entities: Vec<EntitySnapshot>,
+}
+
+/// Mapping from the entities of a snapshot to those restored from it
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EntityRemap(BTreeMap<Entity, Entity>);
+
+impl EntityRemap {
+    /// The restored entity corresponding to a snapshot entity
+    pub fn get(&self, entity: Entity) -> Option<Entity> {
+        self.0.get(&entity).copied()
+    }
+
+    /// The restored entity corresponding to a snapshot entity,
+    /// or the entity itself if it was not part of the snapshot
+    pub fn map(&self, entity: Entity) -> Entity {
+        self.get(entity).unwrap_or(entity)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.0.iter().map(|(from, to)| (*from, *to))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl ComponentRegistry {
+    /// Register the built-in transform, name and hierarchy components for snapshots
+    pub fn register_core_components(&mut self) -> &mut Self {
+        self.register::<PositionComponent>("position")
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<RotationComponent>("rotation")
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<ScaleComponent>("scale")
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<LocalPositionComponent>("local_position")
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<LocalRotationComponent>("local_rotation")
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<LocalScaleComponent>("local_scale")
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<NamedEntityComponent>("name")
+            .from_str(|name| {
+                Ok::<_, std::convert::Infallible>(NamedEntityComponent::construct(
+                    name.to_string().into(),
+                ))
+            })
+            .cloneable()
+            .debuggable()
+            .serde();
+        self.register::<ParentComponent>("parent")
+            .debuggable()
+            .serde()
+            .map_entities(|parent, remap| match remap.get(**parent) {
+                Some(restored) => {
+                    **parent = restored;
+                    true
+                }
+                None => false,
+            });
+        self.register::<ChildrenComponent>("children")
+            .debuggable()
+            .serde()
+            .map_entities(|children, remap| {
+                children.retain(|child| remap.get(*child).is_some());
+                for child in children.iter_mut() {
+                    *child = remap.map(*child);
+                }
+                true
+            });
+        self
+    }
+
+    /// Snapshot the serializable components of every entity that has any
+    pub fn snapshot(&self, world: &World) -> Result<WorldSnapshot, RegistryError> {
+        let entities = world
+            .iter()
+            .map(|entity| entity.entity())
+            .collect::<Vec<_>>();
+        self.snapshot_entities(world, entities)
+    }
+
+    /// Snapshot the serializable components of the given entities
+    ///
+    /// Component values excluded by their registration's `serialize_if` hook are omitted,
+    /// as are entities without any serializable components.
+    pub fn snapshot_entities(
+        &self,
+        world: &World,
+        entities: impl IntoIterator<Item = Entity>,
+    ) -> Result<WorldSnapshot, RegistryError> {
+        let mut snapshot = WorldSnapshot::default();
+
+        for entity in entities {
+            let mut components = BTreeMap::new();
+            for name in self.components_of(world, entity) {
+                if self.get(name)?.is_serializable_for(world, entity) {
+                    components.insert(name.to_string(), self.to_json(world, entity, name)?);
+                }
+            }
+
+            if !components.is_empty() {
+                snapshot
+                    .entities
+                    .push(EntitySnapshot { entity, components });
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Spawn the entities of a snapshot into `world`,
+    /// returning the mapping from snapshot entities to spawned ones
+    ///
+    /// Entity references within restored components are remapped via their registered hooks.
+    /// Components are deserialized up front, so nothing is spawned if any of them fails.
+    pub fn restore(
+        &self,
+        world: &mut World,
+        snapshot: &WorldSnapshot,
+    ) -> Result<EntityRemap, RegistryError> {
+        let entities = snapshot
+            .entities
+            .iter()
+            .map(|EntitySnapshot { entity, components }| {
+                components
+                    .iter()
+                    .map(|(name, json)| self.construct_from_json(name, json.clone()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(|components| (*entity, components))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut remap = EntityRemap::default();
+        for (entity, _) in &entities {
+            remap.0.insert(*entity, world.spawn(()));
+        }
+
+        for (entity, components) in entities {
+            let restored = remap.map(entity);
+            for component in components {
+                component.insert(world, restored)?;
+            }
+        }
+
+        for EntitySnapshot { entity, components } in &snapshot.entities {
+            let restored = remap.map(*entity);
+            for name in components.keys() {
+                self.get(name)?.map_entities(world, restored, &remap);
+            }
+        }
+
+        Ok(remap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_children, get_parent, set_parent, Changed, ChangedTrait, LazyComponent};
+
+    type PendingComponent = LazyComponent<std::sync::Arc<()>, String>;
+
+    #[test]
+    fn test_snapshot() {
+        let mut registry = ComponentRegistry::default();
+        registry.register_core_components();
+        registry
+            .register::<Changed<PositionComponent>>("changed_position")
+            .serde();
+        registry
+            .register::<PendingComponent>("pending")
+            .serde()
+            .serialize_if(PendingComponent::is_pending);
+
+        let mut world = World::new();
+        let unsaved = world.spawn((1u32,));
+        let root = world.spawn((
+            NamedEntityComponent::construct("root".into()),
+            PendingComponent::new("pending".into()),
+        ));
+        let child = world.spawn((
+            Changed::new(
+                PositionComponent::construct(nalgebra::vector![1.0, 2.0, 3.0]),
+                true,
+            ),
+            PendingComponent::Ready(Default::default()),
+        ));
+        set_parent(&mut world, child, root).unwrap();
+        world.despawn(unsaved).unwrap();
+
+        // Only pending lazy components are serialized
+        let snapshot = registry.snapshot(&world).unwrap();
+        assert_eq!(snapshot.entities.len(), 2);
+        assert!(snapshot
+            .entities
+            .iter()
+            .all(|entity| entity.entity == root || !entity.components.contains_key("pending")));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot = serde_json::from_str::<WorldSnapshot>(&json).unwrap();
+
+        // Restore alongside existing entities, so that IDs must be remapped
+        let mut restored = World::new();
+        restored.spawn(());
+        restored.spawn(());
+        let remap = registry.restore(&mut restored, &snapshot).unwrap();
+        assert_eq!(remap.len(), 2);
+
+        let (new_root, new_child) = (remap.get(root).unwrap(), remap.get(child).unwrap());
+        assert_ne!(new_root, root);
+        assert_eq!(get_children(&restored, new_root), [new_child]);
+        assert_eq!(get_parent(&restored, new_child), Some(new_root));
+
+        assert_eq!(
+            **restored.get::<NamedEntityComponent>(new_root).unwrap(),
+            "root"
+        );
+
+        let position = restored
+            .get::<Changed<PositionComponent>>(new_child)
+            .unwrap();
+        assert_eq!(***position, nalgebra::vector![1.0, 2.0, 3.0]);
+        assert!(position.changed_tick() > Default::default());
+        drop(position);
+
+        assert_eq!(
+            *restored.get::<PendingComponent>(new_root).unwrap(),
+            LazyComponent::Pending("pending".to_string())
+        );
+        assert!(restored.get::<PendingComponent>(new_child).is_err());
+
+        // Failed restores spawn nothing
+        let mut invalid = snapshot.clone();
+        invalid.entities[1]
+            .components
+            .insert("position".into(), serde_json::json!("invalid"));
+        let len = restored.len();
+        assert!(matches!(
+            registry.restore(&mut restored, &invalid),
+            Err(RegistryError::Construct {
+                component: "position",
+                ..
+            })
+        ));
+        assert_eq!(restored.len(), len);
+    }
+
+    #[test]
+    fn test_snapshot_subset() {
+        let mut registry = ComponentRegistry::default();
+        registry.register_core_components();
+
+        let mut world = World::new();
+        let root = world.spawn((NamedEntityComponent::construct("root".into()),));
+        let child = world.spawn((NamedEntityComponent::construct("child".into()),));
+        set_parent(&mut world, child, root).unwrap();
+
+        let snapshot = registry.snapshot_entities(&world, [child]).unwrap();
+        assert_eq!(snapshot.entities.len(), 1);
+
+        // Restore alongside existing entities, so the source parent ID refers to an unrelated one
+        let mut restored = World::new();
+        let unrelated = restored.spawn(());
+        assert_eq!(unrelated, root);
+        let remap = registry.restore(&mut restored, &snapshot).unwrap();
+
+        let new_child = remap.get(child).unwrap();
+        assert_eq!(get_parent(&restored, new_child), None);
+        assert!(restored.get::<ParentComponent>(new_child).is_err());
+        assert!(get_children(&restored, unrelated).is_empty());
+    }
+}
@@ -0,0 +1,334 @@
+use std::time::{Duration, Instant};
+
+use hecs::World;
+
+use crate::{Resources, System};
+
+/// Label of the [`update_time`] system, for ordering readers of [`Time`] and [`FixedTime`]
+pub const UPDATE_TIME: &str = "update_time";
+
+/// Variable-rate clock, advanced once per frame by [`update_time_system`]
+///
+/// Delta and elapsed time are scaled, and stop advancing while paused.
+#[derive(Debug, Copy, Clone)]
+pub struct Time {
+    startup: Instant,
+    last_update: Option<Instant>,
+    raw_delta: Duration,
+    delta: Duration,
+    elapsed: Duration,
+    scale: f64,
+    paused: bool,
+    frame_count: u64,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Time::new(Instant::now())
+    }
+}
+
+impl Time {
+    pub fn new(startup: Instant) -> Self {
+        Time {
+            startup,
+            last_update: None,
+            raw_delta: Duration::ZERO,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            scale: 1.0,
+            paused: false,
+            frame_count: 0,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.update_with_instant(Instant::now())
+    }
+
+    /// Advance the clock to `now`
+    ///
+    /// The first update has a delta of zero.
+    pub fn update_with_instant(&mut self, now: Instant) {
+        self.raw_delta = self
+            .last_update
+            .map(|last_update| now.saturating_duration_since(last_update))
+            .unwrap_or_default();
+        self.last_update = Some(now);
+
+        self.delta = if self.paused {
+            Duration::ZERO
+        } else {
+            self.raw_delta.mul_f64(self.scale)
+        };
+
+        self.elapsed += self.delta;
+        self.frame_count += 1;
+    }
+
+    /// Scaled time between the last two updates
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Unscaled time between the last two updates, including while paused
+    pub fn raw_delta(&self) -> Duration {
+        self.raw_delta
+    }
+
+    /// Sum of scaled deltas since startup
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    /// Wall-clock time between startup and the last update
+    pub fn raw_elapsed(&self) -> Duration {
+        self.last_update
+            .map(|last_update| last_update.saturating_duration_since(self.startup))
+            .unwrap_or_default()
+    }
+
+    pub fn startup(&self) -> Instant {
+        self.startup
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Set the rate at which time passes relative to wall-clock time
+    ///
+    /// Negative scales are clamped to zero.
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale.max(0.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+}
+
+/// Fixed-rate clock, accumulating scaled delta time from [`Time`] to be consumed in whole steps
+///
+/// Steps are run by [`run_fixed_steps`].
+/// If more than `max_substeps` steps accumulate in a single frame,
+/// the excess is discarded so that a slow frame cannot cause a spiral of ever-longer updates.
+#[derive(Debug, Copy, Clone)]
+pub struct FixedTime {
+    timestep: Duration,
+    max_substeps: u32,
+    accumulator: Duration,
+    elapsed: Duration,
+    step_count: u64,
+    discarded: Duration,
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        FixedTime::from_hz(60.0)
+    }
+}
+
+impl FixedTime {
+    pub const DEFAULT_MAX_SUBSTEPS: u32 = 8;
+
+    pub fn new(timestep: Duration) -> Self {
+        assert!(!timestep.is_zero(), "Fixed timestep must be non-zero");
+
+        FixedTime {
+            timestep,
+            max_substeps: Self::DEFAULT_MAX_SUBSTEPS,
+            accumulator: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            step_count: 0,
+            discarded: Duration::ZERO,
+        }
+    }
+
+    pub fn from_hz(hz: f64) -> Self {
+        FixedTime::new(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    pub fn with_max_substeps(mut self, max_substeps: u32) -> Self {
+        self.max_substeps = max_substeps;
+        self
+    }
+
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    pub fn timestep_seconds(&self) -> f32 {
+        self.timestep.as_secs_f32()
+    }
+
+    pub fn set_timestep(&mut self, timestep: Duration) {
+        assert!(!timestep.is_zero(), "Fixed timestep must be non-zero");
+        self.timestep = timestep;
+    }
+
+    pub fn max_substeps(&self) -> u32 {
+        self.max_substeps
+    }
+
+    pub fn set_max_substeps(&mut self, max_substeps: u32) {
+        self.max_substeps = max_substeps;
+    }
+
+    /// Add time to be consumed by fixed steps, discarding any beyond `max_substeps` steps
+    pub fn accumulate(&mut self, delta: Duration) {
+        self.accumulator += delta;
+
+        let max = self.timestep * self.max_substeps;
+        if self.accumulator > max {
+            self.discarded += self.accumulator - max;
+            self.accumulator = max;
+        }
+    }
+
+    /// Consume one step of accumulated time, returning false if less than a step remains
+    pub fn expend(&mut self) -> bool {
+        if self.accumulator < self.timestep {
+            return false;
+        }
+
+        self.accumulator -= self.timestep;
+        self.elapsed += self.timestep;
+        self.step_count += 1;
+        true
+    }
+
+    /// Fraction of a step left in the accumulator,
+    /// for interpolating between the last two fixed states when rendering
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator.as_secs_f64() / self.timestep.as_secs_f64()) as f32
+    }
+
+    /// Simulated time, in whole steps
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Total time dropped by the substep limit
+    pub fn discarded(&self) -> Duration {
+        self.discarded
+    }
+}
+
+/// Advance the [`Time`] resource, and accumulate its delta into the [`FixedTime`] resource if present
+///
+/// Should be run once per frame, before any systems reading time.
+pub fn update_time_system(world: &World) {
+    let delta = match world.get_resource_mut::<Time>() {
+        Ok(mut time) => {
+            time.update();
+            time.delta()
+        }
+        Err(_) => return,
+    };
+
+    if let Ok(mut fixed_time) = world.get_resource_mut::<FixedTime>() {
+        fixed_time.accumulate(delta);
+    }
+}
+
+/// Schedulable descriptor for [`update_time_system`], declaring both of the resources it writes
+pub fn update_time() -> System {
+    System::shared(update_time_system)
+        .named(UPDATE_TIME)
+        .label(UPDATE_TIME)
+        .writes::<Time>()
+        .writes::<FixedTime>()
+}
+
+/// Run `f` once for each whole step accumulated in the [`FixedTime`] resource
+pub fn run_fixed_steps(world: &mut World, mut f: impl FnMut(&mut World)) {
+    while world
+        .get_resource_mut::<FixedTime>()
+        .map(|mut fixed_time| fixed_time.expend())
+        .unwrap_or_default()
+    {
+        f(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time() {
+        let start = Instant::now();
+        let mut time = Time::new(start);
+        time.update_with_instant(start);
+        assert_eq!(time.delta(), Duration::ZERO);
+
+        time.set_scale(2.0);
+        time.update_with_instant(start + Duration::from_millis(100));
+        assert_eq!(time.delta(), Duration::from_millis(200));
+
+        time.pause();
+        time.update_with_instant(start + Duration::from_millis(200));
+        assert_eq!(time.delta(), Duration::ZERO);
+        assert_eq!(time.raw_delta(), Duration::from_millis(100));
+        assert_eq!(time.elapsed(), Duration::from_millis(200));
+        assert_eq!(time.raw_elapsed(), Duration::from_millis(200));
+        assert_eq!(time.frame_count(), 3);
+    }
+
+    #[test]
+    fn test_fixed_steps() {
+        let mut world = World::new();
+        world.insert_resource(FixedTime::new(Duration::from_millis(10)).with_max_substeps(4));
+
+        let mut steps = 0;
+        let mut run = |world: &mut World, delta| {
+            world
+                .get_resource_mut::<FixedTime>()
+                .unwrap()
+                .accumulate(delta);
+            steps = 0;
+            run_fixed_steps(world, |_| steps += 1);
+            steps
+        };
+
+        assert_eq!(run(&mut world, Duration::from_millis(25)), 2);
+        assert!((world.get_resource::<FixedTime>().unwrap().alpha() - 0.5).abs() < 1e-5);
+
+        // The remainder carries over into the next frame
+        assert_eq!(run(&mut world, Duration::from_millis(5)), 1);
+
+        // Long frames are clamped to the substep limit
+        assert_eq!(run(&mut world, Duration::from_millis(1000)), 4);
+
+        let fixed_time = world.get_resource::<FixedTime>().unwrap();
+        assert_eq!(fixed_time.step_count(), 7);
+        assert_eq!(fixed_time.elapsed(), Duration::from_millis(70));
+        assert_eq!(fixed_time.discarded(), Duration::from_millis(960));
+        assert_eq!(fixed_time.alpha(), 0.0);
+    }
+}
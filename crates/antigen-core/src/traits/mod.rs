@@ -3,3 +3,51 @@ mod with;
 
 pub use construct::*;
 pub use with::*;
+
+#[cfg(test)]
+mod tests {
+    use crate::{peano::Z, Construct, Usage, With};
+
+    #[derive(Debug, PartialEq, Construct, With)]
+    struct Meters(f32);
+
+    #[derive(Debug, PartialEq, Construct, With)]
+    struct Wrapped<T> {
+        inner: T,
+    }
+
+    #[derive(Debug, PartialEq, Construct, With)]
+    struct Range {
+        start: u32,
+        end: u32,
+        label: &'static str,
+    }
+
+    enum Distance {}
+    type DistanceComponent = Usage<Distance, Meters>;
+
+    #[test]
+    fn test_derive_construct_with() {
+        assert_eq!(Meters::construct(1.0), Meters(1.0));
+        assert_eq!(Meters(1.0).with(2.0), Meters(2.0));
+
+        // Nested construction and forwarding through derived newtypes
+        let wrapped = Wrapped::<DistanceComponent>::construct(1.0);
+        assert_eq!(*wrapped.inner, Meters(1.0));
+        assert_eq!(*wrapped.with(3.0).inner, Meters(3.0));
+
+        // Multi-field structs construct from a tuple, and index same-typed fields by position
+        let range = Range::construct((1, 2, "a"));
+        assert_eq!(
+            range,
+            Range {
+                start: 1,
+                end: 2,
+                label: "a"
+            }
+        );
+
+        let range = With::<u32, Z>::with(range, 5).with("b");
+        assert_eq!((range.start, range.end, range.label), (5, 2, "b"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::any::TypeId;
+
+/// Distinguishes between multiple worlds of the same type
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InstanceId {
+    Index(usize),
+    Named(&'static str),
+}
+
+impl Default for InstanceId {
+    fn default() -> Self {
+        InstanceId::Index(0)
+    }
+}
+
+impl From<usize> for InstanceId {
+    fn from(index: usize) -> Self {
+        InstanceId::Index(index)
+    }
+}
+
+impl From<&'static str> for InstanceId {
+    fn from(name: &'static str) -> Self {
+        InstanceId::Named(name)
+    }
+}
+
+/// Identifies a world registered with an exchange by its type and instance
+#[derive(Debug, Copy, Clone)]
+pub struct WorldId {
+    type_id: TypeId,
+    name: &'static str,
+    instance: InstanceId,
+}
+
+impl WorldId {
+    /// The default instance of world U
+    pub fn of<U: 'static>() -> Self {
+        WorldId::instance::<U, _>(InstanceId::default())
+    }
+
+    /// A specific instance of world U
+    pub fn instance<U: 'static, I: Into<InstanceId>>(instance: I) -> Self {
+        WorldId {
+            type_id: TypeId::of::<U>(),
+            name: std::any::type_name::<U>(),
+            instance: instance.into(),
+        }
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn instance_id(&self) -> InstanceId {
+        self.instance
+    }
+
+    /// Returns true if both IDs refer to worlds of the same type, regardless of instance
+    pub fn is_same_type(&self, other: &WorldId) -> bool {
+        self.type_id == other.type_id
+    }
+}
+
+impl PartialEq for WorldId {
+    fn eq(&self, other: &Self) -> bool {
+        self.type_id == other.type_id && self.instance == other.instance
+    }
+}
+
+impl Eq for WorldId {}
+
+impl PartialOrd for WorldId {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for WorldId {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.type_id
+            .cmp(&other.type_id)
+            .then(self.instance.cmp(&other.instance))
+    }
+}
+
+impl std::hash::Hash for WorldId {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.type_id.hash(state);
+        self.instance.hash(state);
+    }
+}
+
+impl std::fmt::Display for WorldId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.instance {
+            InstanceId::Index(0) => f.write_str(self.name),
+            InstanceId::Index(index) => write!(f, "{}#{}", self.name, index),
+            InstanceId::Named(name) => write!(f, "{}({})", self.name, name),
+        }
+    }
+}
+
+/// The set of worlds a message is addressed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipients {
+    /// A single world
+    World(WorldId),
+    /// An explicit list of worlds
+    Multicast(Vec<WorldId>),
+    /// Every connected world except the sender
+    Broadcast,
+    /// One connected instance of the given world's type, chosen round-robin
+    ///
+    /// The instance of the provided ID is ignored.
+    Pool(WorldId),
+}
@@ -0,0 +1,281 @@
+use std::any::TypeId;
+
+use hecs::{Component, DynamicBundle, TypeInfo};
+
+/// A set of borrowed components that can be cloned into an owned bundle
+///
+/// Implemented for tuples of up to 30 component references,
+/// and derivable for query structs of any size via `#[derive(ClonedBundle)]`.
+/// Tuples beyond the hecs tuple limit clone into a [`ChainedBundle`].
+pub trait ClonedBundle {
+    type Bundle: DynamicBundle + Send + Sync + 'static;
+
+    fn cloned_bundle(&self) -> Self::Bundle;
+}
+
+macro_rules! cloned_bundle_tuple {
+    ($($name: ident),*) => {
+        impl<$($name),*> ClonedBundle for ($(&$name,)*)
+        where
+            $($name: Clone + Component),*
+        {
+            type Bundle = ($($name,)*);
+
+            #[allow(non_snake_case)]
+            fn cloned_bundle(&self) -> Self::Bundle {
+                let ($($name,)*) = self;
+                ($(<$name as Clone>::clone(*$name),)*)
+            }
+        }
+    };
+}
+
+macro_rules! smaller_tuples_too {
+    ($m: ident, $ty: ident) => {
+        $m!{$ty}
+    };
+    ($m: ident, $ty: ident, $($tt: ident),*) => {
+        smaller_tuples_too!{$m, $($tt),*}
+        $m!{$ty, $($tt),*}
+    };
+}
+
+smaller_tuples_too!(
+    cloned_bundle_tuple,
+    O,
+    N,
+    M,
+    L,
+    K,
+    J,
+    I,
+    H,
+    G,
+    F,
+    E,
+    D,
+    C,
+    B,
+    A
+);
+
+macro_rules! cloned_bundle_chained_tuple {
+    (($($head: ident),*), ($($tail: ident),*)) => {
+        impl<$($head,)* $($tail),*> ClonedBundle for ($(&$head,)* $(&$tail,)*)
+        where
+            $($head: Clone + Component,)*
+            $($tail: Clone + Component),*
+        {
+            type Bundle = ChainedBundle<($($head,)*), ($($tail,)*)>;
+
+            #[allow(non_snake_case)]
+            fn cloned_bundle(&self) -> Self::Bundle {
+                let ($($head,)* $($tail,)*) = self;
+                ChainedBundle(
+                    ($(<$head as Clone>::clone(*$head),)*),
+                    ($(<$tail as Clone>::clone(*$tail),)*),
+                )
+            }
+        }
+    };
+}
+
+/// Invoke `$m` with a full first chunk and each non-empty suffix-truncation of the second
+macro_rules! chained_tuples_too {
+    ($m: ident, $head: tt, $ty: ident) => {
+        $m!{$head, ($ty)}
+    };
+    ($m: ident, $head: tt, $ty: ident, $($tt: ident),*) => {
+        chained_tuples_too!{$m, $head, $($tt),*}
+        $m!{$head, ($ty, $($tt),*)}
+    };
+}
+
+chained_tuples_too!(
+    cloned_bundle_chained_tuple,
+    (A, B, C, D, E, F, G, H, I, J, K, L, M, N, O),
+    AD,
+    AC,
+    AB,
+    AA,
+    Z,
+    Y,
+    X,
+    W,
+    V,
+    U,
+    T,
+    S,
+    R,
+    Q,
+    P
+);
+
+/// Pair of bundles inserted as one,
+/// used to clone more components than hecs supports in a single tuple
+pub struct ChainedBundle<A, B>(pub A, pub B);
+
+unsafe impl<A, B> DynamicBundle for ChainedBundle<A, B>
+where
+    A: DynamicBundle,
+    B: DynamicBundle,
+{
+    fn with_ids<T>(&self, f: impl FnOnce(&[TypeId]) -> T) -> T {
+        let ids = self
+            .type_info()
+            .iter()
+            .map(TypeInfo::id)
+            .collect::<Vec<_>>();
+        f(&ids)
+    }
+
+    fn type_info(&self) -> Vec<TypeInfo> {
+        let mut info = self.0.type_info();
+        info.extend(self.1.type_info());
+        info.sort_unstable();
+        info
+    }
+
+    unsafe fn put(self, mut f: impl FnMut(*mut u8, TypeInfo)) {
+        self.0.put(&mut f);
+        self.1.put(f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{receive_messages, send_clone_query, ClonedBundle, WorldExchange};
+    use hecs::World;
+
+    enum Game {}
+    enum Render {}
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct C<const N: usize>(String);
+
+    #[derive(hecs::Query, ClonedBundle)]
+    struct Wide<'a> {
+        c0: &'a C<0>,
+        c1: &'a C<1>,
+        c2: &'a C<2>,
+        c3: &'a C<3>,
+        c4: &'a C<4>,
+        c5: &'a C<5>,
+        c6: &'a C<6>,
+        c7: &'a C<7>,
+        c8: &'a C<8>,
+        c9: &'a C<9>,
+        c10: &'a C<10>,
+        c11: &'a C<11>,
+        c12: &'a C<12>,
+        c13: &'a C<13>,
+        c14: &'a C<14>,
+        c15: &'a mut C<15>,
+    }
+
+    #[test]
+    fn test_cloned_bundle() {
+        let mut world = World::new();
+
+        let (a, b) = (C::<0>("a".into()), C::<1>("b".into()));
+        let entity = world.spawn((&a, &b).cloned_bundle());
+        assert_eq!(*world.get::<C<1>>(entity).unwrap(), b);
+
+        let mut c15 = C("15".into());
+        let wide = Wide {
+            c0: &a,
+            c1: &b,
+            c2: &C("2".into()),
+            c3: &C("3".into()),
+            c4: &C("4".into()),
+            c5: &C("5".into()),
+            c6: &C("6".into()),
+            c7: &C("7".into()),
+            c8: &C("8".into()),
+            c9: &C("9".into()),
+            c10: &C("10".into()),
+            c11: &C("11".into()),
+            c12: &C("12".into()),
+            c13: &C("13".into()),
+            c14: &C("14".into()),
+            c15: &mut c15,
+        };
+
+        let entity = world.spawn(wide.cloned_bundle());
+        assert_eq!(*world.get::<C<0>>(entity).unwrap(), a);
+        assert_eq!(world.get::<C<14>>(entity).unwrap().0, "14");
+        assert_eq!(world.get::<C<15>>(entity).unwrap().0, "15");
+        assert_eq!(world.entity(entity).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_cloned_bundle_chained_tuple() {
+        let mut world = World::new();
+
+        let entity = world.spawn(
+            (
+                &C::<0>("0".into()),
+                &C::<1>("1".into()),
+                &C::<2>("2".into()),
+                &C::<3>("3".into()),
+                &C::<4>("4".into()),
+                &C::<5>("5".into()),
+                &C::<6>("6".into()),
+                &C::<7>("7".into()),
+                &C::<8>("8".into()),
+                &C::<9>("9".into()),
+                &C::<10>("10".into()),
+                &C::<11>("11".into()),
+                &C::<12>("12".into()),
+                &C::<13>("13".into()),
+                &C::<14>("14".into()),
+                &C::<15>("15".into()),
+            )
+                .cloned_bundle(),
+        );
+
+        assert_eq!(world.get::<C<0>>(entity).unwrap().0, "0");
+        assert_eq!(world.get::<C<15>>(entity).unwrap().0, "15");
+        assert_eq!(world.entity(entity).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_send_clone_query_derived() {
+        let mut exchange = WorldExchange::default();
+        let game = exchange.create_channel::<Game>();
+        let render = exchange.create_channel::<Render>();
+        let _exchange = exchange.spawn();
+
+        let mut game_world = World::new();
+        let mut render_world = World::new();
+
+        let entity = render_world.spawn((
+            C::<0>("0".into()),
+            C::<1>("1".into()),
+            C::<2>("2".into()),
+            C::<3>("3".into()),
+            C::<4>("4".into()),
+            C::<5>("5".into()),
+            C::<6>("6".into()),
+            C::<7>("7".into()),
+            C::<8>("8".into()),
+            C::<9>("9".into()),
+            C::<10>("10".into()),
+            C::<11>("11".into()),
+            C::<12>("12".into()),
+            C::<13>("13".into()),
+            C::<14>("14".into()),
+        ));
+        render_world
+            .insert_one(entity, C::<15>("15".into()))
+            .unwrap();
+
+        send_clone_query::<Wide, Game>(entity)((&mut render_world, &render)).unwrap();
+        receive_messages(&mut game_world, &game).unwrap();
+
+        let mirror = render.mirror_entity(entity, game.world_id()).unwrap();
+        assert_eq!(game_world.get::<C<0>>(mirror).unwrap().0, "0");
+        assert_eq!(game_world.get::<C<15>>(mirror).unwrap().0, "15");
+        assert_eq!(game_world.entity(mirror).unwrap().len(), 16);
+    }
+}
@@ -0,0 +1,331 @@
+use super::{MessageContext, MessageResult, WorldChannel, WorldId};
+use hecs::{Component, DynamicBundle, Entity};
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+
+/// Correspondence between entities and their mirrors in other worlds
+///
+/// Each pair is stored in both directions,
+/// so either world can find its counterpart to a given entity.
+#[derive(Debug, Default)]
+pub struct EntityMap {
+    pairs: BTreeMap<(WorldId, Entity), BTreeMap<WorldId, Entity>>,
+}
+
+impl EntityMap {
+    /// Record `a` and `b` as mirrors of one another,
+    /// replacing any existing mirror of either in the other's world
+    pub fn insert(&mut self, a: (WorldId, Entity), b: (WorldId, Entity)) {
+        if let Some(previous) = self.get(a.0, a.1, b.0) {
+            self.remove_pair(a, (b.0, previous));
+        }
+        if let Some(previous) = self.get(b.0, b.1, a.0) {
+            self.remove_pair(b, (a.0, previous));
+        }
+
+        self.pairs.entry(a).or_default().insert(b.0, b.1);
+        self.pairs.entry(b).or_default().insert(a.0, a.1);
+    }
+
+    /// Returns the mirror of `entity` from `world` in world `other`
+    pub fn get(&self, world: WorldId, entity: Entity, other: WorldId) -> Option<Entity> {
+        self.pairs
+            .get(&(world, entity))
+            .and_then(|mirrors| mirrors.get(&other))
+            .copied()
+    }
+
+    /// Iterate over the mirrors of `entity` from `world` in all other worlds
+    pub fn mirrors(
+        &self,
+        world: WorldId,
+        entity: Entity,
+    ) -> impl Iterator<Item = (WorldId, Entity)> + '_ {
+        self.pairs
+            .get(&(world, entity))
+            .into_iter()
+            .flat_map(|mirrors| mirrors.iter().map(|(world, entity)| (*world, *entity)))
+    }
+
+    /// Forget that `a` and `b` are mirrors of one another
+    pub fn remove_pair(&mut self, a: (WorldId, Entity), b: (WorldId, Entity)) {
+        for (from, to) in [(a, b), (b, a)] {
+            if let Some(mirrors) = self.pairs.get_mut(&from) {
+                if mirrors.get(&to.0) == Some(&to.1) {
+                    mirrors.remove(&to.0);
+                }
+                if mirrors.is_empty() {
+                    self.pairs.remove(&from);
+                }
+            }
+        }
+    }
+
+    /// Forget every pair involving `entity` from `world`, returning its former mirrors
+    pub fn remove_entity(&mut self, world: WorldId, entity: Entity) -> Vec<(WorldId, Entity)> {
+        let mirrors = self.mirrors(world, entity).collect::<Vec<_>>();
+        for mirror in &mirrors {
+            self.remove_pair((world, entity), *mirror);
+        }
+        mirrors
+    }
+
+    /// Forget every pair involving an entity from `world`
+    pub fn remove_world(&mut self, world: WorldId) {
+        let entities = self
+            .pairs
+            .keys()
+            .filter(|(candidate, _)| *candidate == world)
+            .map(|(_, entity)| *entity)
+            .collect::<Vec<_>>();
+
+        for entity in entities {
+            self.remove_entity(world, entity);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.values().map(BTreeMap::len).sum::<usize>() / 2
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl WorldChannel {
+    /// The entity map shared by every world on this channel's exchange
+    pub fn entity_map(&self) -> &RwLock<EntityMap> {
+        &self.entity_map
+    }
+
+    /// Returns the mirror of local `entity` in world `other`
+    pub fn mirror_entity(&self, entity: Entity, other: WorldId) -> Option<Entity> {
+        self.entity_map.read().get(self.world_id, entity, other)
+    }
+
+    /// Returns the local mirror of `entity` from world `other`
+    pub fn local_entity(&self, other: WorldId, entity: Entity) -> Option<Entity> {
+        self.entity_map.read().get(other, entity, self.world_id)
+    }
+
+    /// Record local `entity` as the mirror of `remote` from world `other`
+    pub fn map_entity(&self, entity: Entity, other: WorldId, remote: Entity) {
+        self.entity_map
+            .write()
+            .insert((self.world_id, entity), (other, remote));
+    }
+}
+
+/// Returns a function that will insert `bundle` into the local mirror
+/// of `entity` from world `sender`, spawning and mapping a new mirror if none exists
+pub(crate) fn insert_mirrored<C: DynamicBundle>(
+    sender: WorldId,
+    entity: Entity,
+    bundle: C,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        let mirror = channel
+            .local_entity(sender, entity)
+            .filter(|mirror| world.contains(*mirror));
+
+        match mirror {
+            Some(mirror) => {
+                tracing::debug!(
+                    bundle = std::any::type_name::<C>(),
+                    ?mirror,
+                    "Updating mirrored bundle"
+                );
+                world.insert(mirror, bundle)?;
+            }
+            None => {
+                tracing::debug!(
+                    bundle = std::any::type_name::<C>(),
+                    %sender,
+                    ?entity,
+                    "Spawning mirrored bundle"
+                );
+                let mirror = world.spawn(bundle);
+                channel.map_entity(mirror, sender, entity);
+            }
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Returns a function that will remove component C from the local mirror
+/// of `entity` from world `sender`, despawning and unmapping the mirror if it is left empty
+pub(crate) fn remove_mirrored<C: Component>(
+    sender: WorldId,
+    entity: Entity,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        let mirror = match channel.local_entity(sender, entity) {
+            Some(mirror) => mirror,
+            None => return Ok(ctx),
+        };
+
+        tracing::debug!(
+            component = std::any::type_name::<C>(),
+            ?mirror,
+            "Removing mirrored component"
+        );
+
+        // The component may already have been removed locally
+        let _ = world.remove_one::<C>(mirror);
+
+        let is_empty = world
+            .entity(mirror)
+            .map(|mirror| mirror.is_empty())
+            .unwrap_or(true);
+
+        if is_empty {
+            let _ = world.despawn(mirror);
+            channel
+                .entity_map()
+                .write()
+                .remove_pair((channel.world_id(), mirror), (sender, entity));
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Despawn `entity` and its mirrors in every other world
+pub fn despawn_mirrored(
+    entity: Entity,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        // A missing local entity is not an error, as its mirrors may still be live
+        let _ = world.despawn(entity);
+
+        let mirrors = channel
+            .entity_map()
+            .write()
+            .remove_entity(channel.world_id(), entity);
+
+        for (world_id, mirror) in mirrors {
+            tracing::debug!(%world_id, ?mirror, "Despawning mirror");
+            channel.send_to_instance(world_id, move |mut ctx| {
+                let (world, channel) = &mut ctx;
+                let _ = world.despawn(mirror);
+                channel
+                    .entity_map()
+                    .write()
+                    .remove_entity(channel.world_id(), mirror);
+                Ok(ctx)
+            })?;
+        }
+
+        Ok(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{receive_messages, send_clone_query, send_component, WorldExchange};
+    use hecs::World;
+
+    enum Game {}
+    enum Render {}
+    enum Audio {}
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Position(i32);
+
+    #[test]
+    fn test_entity_map() {
+        let mut world = World::new();
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        let game = WorldId::of::<Game>();
+        let render = WorldId::of::<Render>();
+        let audio = WorldId::of::<Audio>();
+
+        let mut map = EntityMap::default();
+        map.insert((game, a), (render, b));
+        map.insert((game, a), (audio, c));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(game, a, render), Some(b));
+        assert_eq!(map.get(render, b, game), Some(a));
+        assert_eq!(map.get(render, b, audio), None);
+
+        // Remapping replaces the previous mirror in both directions
+        map.insert((game, a), (render, c));
+        assert_eq!(map.get(game, a, render), Some(c));
+        assert_eq!(map.get(render, b, game), None);
+
+        assert_eq!(map.remove_entity(game, a).len(), 2);
+        assert!(map.is_empty());
+
+        map.insert((game, a), (render, b));
+        map.remove_world(render);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_mirrored_entities() {
+        let mut exchange = WorldExchange::default();
+        let game = exchange.create_channel::<Game>();
+        let render = exchange.create_channel::<Render>();
+        let _exchange = exchange.spawn();
+
+        let mut game_world = World::new();
+        let mut render_world = World::new();
+
+        let entity = render_world.spawn((Position(1),));
+        send_clone_query::<(&Position,), Game>(entity)((&mut render_world, &render)).unwrap();
+        receive_messages(&mut game_world, &game).unwrap();
+
+        let mirror = render.mirror_entity(entity, game.world_id()).unwrap();
+        assert_eq!(game.local_entity(render.world_id(), entity), Some(mirror));
+        assert_eq!(*game_world.get::<Position>(mirror).unwrap(), Position(1));
+
+        // Subsequent sends update the existing mirror
+        *render_world.get_mut::<Position>(entity).unwrap() = Position(2);
+        send_clone_query::<(&Position,), Game>(entity)((&mut render_world, &render)).unwrap();
+        receive_messages(&mut game_world, &game).unwrap();
+
+        assert_eq!(game_world.len(), 1);
+        assert_eq!(*game_world.get::<Position>(mirror).unwrap(), Position(2));
+
+        despawn_mirrored(entity)((&mut render_world, &render)).unwrap();
+        receive_messages(&mut game_world, &game).unwrap();
+
+        assert_eq!(render_world.len(), 0);
+        assert_eq!(game_world.len(), 0);
+        assert!(render.entity_map().read().is_empty());
+    }
+
+    #[test]
+    fn test_send_component_despawned() {
+        let mut exchange = WorldExchange::default();
+        let game = exchange.create_channel::<Game>();
+        let render = exchange.create_channel::<Render>();
+        let _exchange = exchange.spawn();
+
+        let mut game_world = World::new();
+        let mut render_world = World::new();
+
+        let target = game_world.spawn(());
+        game_world.despawn(target).unwrap();
+
+        render_world.spawn(("key", Position(1)));
+        send_component::<Position, Game, _>("key", target)((&mut render_world, &render)).unwrap();
+
+        // Inserting into a despawned entity fails the message rather than panicking
+        let error = receive_messages(&mut game_world, &game).unwrap_err();
+        assert!(error.is::<hecs::NoSuchEntity>());
+        assert_eq!(game_world.len(), 0);
+    }
+}
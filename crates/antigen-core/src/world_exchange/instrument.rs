@@ -0,0 +1,273 @@
+use super::{RoutingError, WorldChannel, WorldExchange, WorldId};
+use parking_lot::{Mutex, RwLock};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+/// The path a message takes through the exchange
+///
+/// Messages without a sender originate from the exchange itself.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Route {
+    pub sender: Option<WorldId>,
+    pub receiver: WorldId,
+}
+
+impl std::fmt::Display for Route {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.sender {
+            Some(sender) => write!(f, "{} -> {}", sender, self.receiver),
+            None => write!(f, "exchange -> {}", self.receiver),
+        }
+    }
+}
+
+/// Observer for traffic passing through a WorldExchange
+///
+/// Routing hooks are called from the exchange thread,
+/// handler hooks from the thread of the receiving world.
+pub trait Instrument: Send + Sync {
+    /// A message is being delivered, leaving `queue_depth` messages pending for its receiver
+    ///
+    /// Called before the message is sent, so precedes any report of it being handled.
+    /// If its receiver has disconnected, the message is then reported as dead-lettered.
+    fn message_routed(&self, _route: &Route, _queue_depth: usize) {}
+
+    /// A message could not be delivered
+    fn message_dead_lettered(&self, _route: &Route, _error: &RoutingError) {}
+
+    /// A message was handled by its receiver, returning an error if `ok` is false
+    fn message_handled(&self, _route: &Route, _latency: Duration, _ok: bool) {}
+}
+
+/// Message statistics for a single route
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct RouteStats {
+    pub routed: u64,
+    pub dead_letters: u64,
+    pub handled: u64,
+    pub failed: u64,
+    pub total_latency: Duration,
+    pub max_latency: Duration,
+}
+
+impl RouteStats {
+    /// Average time spent handling a message on this route
+    pub fn mean_latency(&self) -> Option<Duration> {
+        if self.handled == 0 {
+            return None;
+        }
+
+        // Divided in nanoseconds, as the message count may not fit in a u32
+        let nanos = self.total_latency.as_nanos() / u128::from(self.handled);
+        Some(Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        ))
+    }
+}
+
+/// Pending message statistics for a single world
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Pending messages as of the most recent delivery
+    pub depth: usize,
+    pub max_depth: usize,
+}
+
+/// Snapshot of the statistics gathered by an exchange
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExchangeStats {
+    pub routes: BTreeMap<Route, RouteStats>,
+    pub queues: BTreeMap<WorldId, QueueStats>,
+}
+
+impl ExchangeStats {
+    /// Statistics for messages sent from `sender` to `receiver`
+    pub fn route(&self, sender: WorldId, receiver: WorldId) -> RouteStats {
+        self.routes
+            .get(&Route {
+                sender: Some(sender),
+                receiver,
+            })
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Pending message statistics for `world_id`
+    pub fn queue(&self, world_id: WorldId) -> QueueStats {
+        self.queues.get(&world_id).copied().unwrap_or_default()
+    }
+
+    /// Statistics for all messages received by `receiver`, regardless of sender
+    pub fn received(&self, receiver: WorldId) -> RouteStats {
+        self.routes
+            .iter()
+            .filter(|(route, _)| route.receiver == receiver)
+            .fold(RouteStats::default(), |acc, (_, stats)| RouteStats {
+                routed: acc.routed + stats.routed,
+                dead_letters: acc.dead_letters + stats.dead_letters,
+                handled: acc.handled + stats.handled,
+                failed: acc.failed + stats.failed,
+                total_latency: acc.total_latency + stats.total_latency,
+                max_latency: acc.max_latency.max(stats.max_latency),
+            })
+    }
+}
+
+impl Instrument for Mutex<ExchangeStats> {
+    fn message_routed(&self, route: &Route, queue_depth: usize) {
+        let mut stats = self.lock();
+        stats.routes.entry(*route).or_default().routed += 1;
+
+        let queue = stats.queues.entry(route.receiver).or_default();
+        queue.depth = queue_depth;
+        queue.max_depth = queue.max_depth.max(queue_depth);
+    }
+
+    fn message_dead_lettered(&self, route: &Route, _: &RoutingError) {
+        self.lock().routes.entry(*route).or_default().dead_letters += 1;
+    }
+
+    fn message_handled(&self, route: &Route, latency: Duration, ok: bool) {
+        let mut stats = self.lock();
+        let stats = stats.routes.entry(*route).or_default();
+        stats.handled += 1;
+        if !ok {
+            stats.failed += 1;
+        }
+        stats.total_latency += latency;
+        stats.max_latency = stats.max_latency.max(latency);
+    }
+}
+
+/// Built-in statistics and user-provided instruments, shared between an exchange and its channels
+#[derive(Default, Clone)]
+pub(crate) struct Instrumentation {
+    stats: Arc<Mutex<ExchangeStats>>,
+    instruments: Arc<RwLock<Vec<Arc<dyn Instrument>>>>,
+}
+
+impl Instrumentation {
+    fn each(&self, f: impl Fn(&dyn Instrument)) {
+        f(&*self.stats);
+        for instrument in self.instruments.read().iter() {
+            f(&**instrument);
+        }
+    }
+}
+
+impl Instrument for Instrumentation {
+    fn message_routed(&self, route: &Route, queue_depth: usize) {
+        tracing::trace!(%route, queue_depth, "Routed message");
+        self.each(|instrument| instrument.message_routed(route, queue_depth))
+    }
+
+    fn message_dead_lettered(&self, route: &Route, error: &RoutingError) {
+        tracing::warn!(%route, %error, "Failed to route message");
+        self.each(|instrument| instrument.message_dead_lettered(route, error))
+    }
+
+    fn message_handled(&self, route: &Route, latency: Duration, ok: bool) {
+        self.each(|instrument| instrument.message_handled(route, latency, ok))
+    }
+}
+
+impl WorldExchange {
+    /// Register an instrument to observe messages passing through this exchange
+    pub fn add_instrument(&mut self, instrument: Arc<dyn Instrument>) {
+        self.instrumentation.instruments.write().push(instrument);
+    }
+}
+
+impl WorldChannel {
+    /// Snapshot the message statistics gathered by this channel's exchange
+    pub fn stats(&self) -> ExchangeStats {
+        self.instrumentation.stats.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{receive_messages, Lift, SendTo};
+    use hecs::World;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    enum First {}
+    enum Second {}
+    enum Unregistered {}
+
+    #[derive(Default)]
+    struct Counter(AtomicUsize);
+
+    impl Instrument for Counter {
+        fn message_handled(&self, _: &Route, _: Duration, _: bool) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut exchange = WorldExchange::default();
+        let dead_letters = exchange.dead_letters();
+        let counter = Arc::new(Counter::default());
+        exchange.add_instrument(counter.clone());
+
+        let first = exchange.create_channel::<First>();
+        let second = exchange.create_channel::<Second>();
+        let _exchange = exchange.spawn();
+
+        for _ in 0..3 {
+            first.send_to::<Second>(|ctx| ctx.lift()).unwrap();
+        }
+        first.send_to::<Second>(|_| Err("failed".into())).unwrap();
+        first.send_to::<Unregistered>(|ctx| ctx.lift()).unwrap();
+        dead_letters.recv_timeout(Duration::from_secs(1)).unwrap();
+
+        let mut world = World::new();
+        for _ in 0..3 {
+            receive_messages(&mut world, &second).unwrap();
+
+            // Messages are counted as routed before they can be handled
+            let route = second
+                .stats()
+                .route(WorldId::of::<First>(), WorldId::of::<Second>());
+            assert!(route.routed >= route.handled);
+        }
+        assert!(receive_messages(&mut world, &second).is_err());
+
+        // Stats are shared between every channel on the exchange
+        let stats = first.stats();
+        let route = stats.route(WorldId::of::<First>(), WorldId::of::<Second>());
+        assert_eq!(route.routed, 4);
+        assert_eq!(route.handled, 4);
+        assert_eq!(route.failed, 1);
+        assert!(route.mean_latency().is_some());
+        assert_eq!(stats.queue(WorldId::of::<Second>()).max_depth, 4);
+
+        let route = stats.route(WorldId::of::<First>(), WorldId::of::<Unregistered>());
+        assert_eq!(route.dead_letters, 1);
+
+        assert_eq!(counter.0.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn test_mean_latency() {
+        assert_eq!(RouteStats::default().mean_latency(), None);
+
+        let stats = RouteStats {
+            handled: 4,
+            total_latency: Duration::from_millis(10),
+            ..Default::default()
+        };
+        assert_eq!(stats.mean_latency(), Some(Duration::from_micros(2500)));
+
+        // Counts beyond u32::MAX are not truncated
+        let handled = u64::from(u32::MAX) + 1;
+        let stats = RouteStats {
+            handled,
+            total_latency: Duration::from_nanos(handled * 3),
+            ..Default::default()
+        };
+        assert_eq!(stats.mean_latency(), Some(Duration::from_nanos(3)));
+    }
+}
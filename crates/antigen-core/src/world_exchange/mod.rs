@@ -0,0 +1,840 @@
+use crate::{Resources, TwoWayChannel};
+use crossbeam_channel::{
+    Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError, TrySendError,
+};
+use hecs::{Component, DynamicBundle, Entity, Query, World};
+use parking_lot::RwLock;
+use std::{
+    any::TypeId,
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+mod address;
+mod cloned_bundle;
+mod entity_map;
+mod instrument;
+mod multicast;
+mod pool;
+mod record;
+mod replay;
+mod replication;
+mod request;
+mod routing;
+mod shutdown;
+
+pub use address::*;
+pub use cloned_bundle::*;
+pub use entity_map::*;
+pub use instrument::*;
+pub use multicast::*;
+pub use record::*;
+pub use replay::*;
+pub use replication::*;
+pub use request::*;
+pub use routing::*;
+pub use shutdown::*;
+
+/// Struct for coordinating cross-thread communication between worlds
+#[derive(Default)]
+pub struct WorldExchange {
+    channels: Vec<(WorldId, WorldChannel)>,
+    connections: Arc<RwLock<Connections>>,
+    entity_map: Arc<RwLock<EntityMap>>,
+    dead_letters: Option<Sender<DeadLetter>>,
+    pool_cursors: BTreeMap<TypeId, usize>,
+    instrumentation: Instrumentation,
+    recorder: Option<Recorder>,
+}
+
+impl WorldExchange {
+    /// Create a channel for the next unused indexed instance of world U
+    ///
+    /// The first channel created for a given world is its default instance.
+    pub fn create_channel<U: 'static>(&mut self) -> WorldChannel {
+        let index = self
+            .channels
+            .iter()
+            .filter(|(world_id, _)| world_id.type_id() == TypeId::of::<U>())
+            .filter_map(|(world_id, _)| match world_id.instance_id() {
+                InstanceId::Index(index) => Some(index + 1),
+                InstanceId::Named(_) => None,
+            })
+            .max()
+            .unwrap_or_default();
+
+        self.create_instance_channel(WorldId::instance::<U, _>(index))
+    }
+
+    /// Create a channel for a named instance of world U
+    pub fn create_named_channel<U: 'static>(&mut self, name: &'static str) -> WorldChannel {
+        self.create_instance_channel(WorldId::instance::<U, _>(name))
+    }
+
+    fn create_instance_channel(&mut self, world_id: WorldId) -> WorldChannel {
+        if !self.connections.write().registered.insert(world_id) {
+            panic!("A channel for world {} already exists", world_id);
+        }
+
+        let (cl, cr) = TwoWayChannel::unbounded();
+        self.channels.push((
+            world_id,
+            WorldChannel::new(
+                cl,
+                world_id,
+                self.connections.clone(),
+                self.entity_map.clone(),
+                self.instrumentation.clone(),
+            ),
+        ));
+        WorldChannel::new(
+            cr,
+            world_id,
+            self.connections.clone(),
+            self.entity_map.clone(),
+            self.instrumentation.clone(),
+        )
+    }
+
+    /// Redirect undeliverable messages into the returned receiver
+    /// instead of bouncing them back to their sender
+    pub fn dead_letters(&mut self) -> Receiver<DeadLetter> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        self.dead_letters = Some(tx);
+        rx
+    }
+
+    /// Spawn the exchange into its own thread, returning a guard that stops it on drop
+    pub fn spawn(self) -> WorldExchangeHandle {
+        let (stop_tx, stop_rx) = crossbeam_channel::bounded(1);
+        let thread = std::thread::spawn(move || self.run(stop_rx));
+        WorldExchangeHandle::new(stop_tx, thread)
+    }
+
+    fn run(mut self, stop: Receiver<()>) {
+        // Build a channel selector over clones of each world's receiver,
+        // leaving the exchange free to be borrowed mutably while routing
+        let receivers = self
+            .channels
+            .iter()
+            .map(|(_, channel)| channel.rx().clone())
+            .collect::<Vec<_>>();
+
+        let mut sel = crossbeam_channel::Select::new();
+        for rx in &receivers {
+            sel.recv(rx);
+        }
+        let stop_index = sel.recv(&stop);
+
+        // Route until stopped, or until every world has disconnected
+        let mut connected = self.channels.len();
+        while connected > 0 {
+            // Block until a channel is ready
+            let oper = sel.select();
+
+            // Retrieve the ready channel
+            let index = oper.index();
+            if index == stop_index {
+                // Either a stop was requested or the handle was dropped
+                let _ = oper.recv(&stop);
+                self.broadcast_shutdown();
+                break;
+            }
+
+            let world_id = self.channels[index].0;
+
+            // Receive from the channel
+            match oper.recv(&receivers[index]) {
+                Ok(mut message) => {
+                    message.sender = Some(world_id);
+                    self.dispatch(message);
+                }
+                Err(_) => {
+                    // The sending world has dropped its channel
+                    sel.remove(index);
+                    self.connections.write().disconnected.insert(world_id);
+                    self.entity_map.write().remove_world(world_id);
+                    connected -= 1;
+                }
+            }
+        }
+    }
+
+    /// Send a shutdown control message to every connected world
+    fn broadcast_shutdown(&self) {
+        let connections = self.connections.read();
+        for (world_id, channel) in &self.channels {
+            if !connections.is_connected(world_id) {
+                continue;
+            }
+
+            // A world may disconnect between the check and the send, which is harmless
+            let _ = channel.tx().send(WorldMessage::shutdown(*world_id));
+        }
+    }
+
+    /// Expand a message into one message per receiving world and deliver each of them
+    fn dispatch(&mut self, message: WorldMessage) {
+        let WorldMessage {
+            sender,
+            receivers,
+            payload,
+        } = message;
+
+        let receivers = match *receivers {
+            Recipients::World(receiver) => {
+                let message = WorldMessage {
+                    sender,
+                    receivers: Box::new(Recipients::World(receiver)),
+                    payload,
+                };
+
+                if let Err(dead_letter) = self.route(receiver, message) {
+                    self.handle_dead_letter(*dead_letter);
+                }
+                return;
+            }
+            Recipients::Pool(pool) => {
+                let receiver = self.next_pool_instance(pool);
+                let message = WorldMessage {
+                    sender,
+                    receivers: Box::new(Recipients::World(receiver)),
+                    payload,
+                };
+
+                if let Err(dead_letter) = self.route(receiver, message) {
+                    self.handle_dead_letter(*dead_letter);
+                }
+                return;
+            }
+            Recipients::Multicast(receivers) => receivers,
+            Recipients::Broadcast => {
+                let connections = self.connections.read();
+                self.channels
+                    .iter()
+                    .map(|(world_id, _)| *world_id)
+                    .filter(|world_id| Some(*world_id) != sender)
+                    .filter(|world_id| connections.is_connected(world_id))
+                    .collect()
+            }
+        };
+
+        for receiver in receivers {
+            let message = WorldMessage {
+                sender,
+                receivers: Box::new(Recipients::World(receiver)),
+                payload: payload.instance(),
+            };
+
+            if let Err(dead_letter) = self.route(receiver, message) {
+                self.handle_dead_letter(*dead_letter);
+            }
+        }
+    }
+
+    /// Select the next connected instance of a pooled world type,
+    /// falling back to the pool's own ID if no instances are connected
+    fn next_pool_instance(&mut self, pool: WorldId) -> WorldId {
+        let connections = self.connections.read();
+        let instances = self
+            .channels
+            .iter()
+            .map(|(world_id, _)| *world_id)
+            .filter(|world_id| world_id.is_same_type(&pool))
+            .filter(|world_id| connections.is_connected(world_id))
+            .collect::<Vec<_>>();
+
+        if instances.is_empty() {
+            return pool;
+        }
+
+        let cursor = self.pool_cursors.entry(pool.type_id()).or_default();
+        let world_id = instances[*cursor % instances.len()];
+        *cursor = cursor.wrapping_add(1);
+        world_id
+    }
+
+    /// Deliver a message to `receiver`, which [`dispatch`](Self::dispatch)
+    /// has resolved from the message's recipients
+    fn route(&self, receiver: WorldId, message: WorldMessage) -> Result<(), Box<DeadLetter>> {
+        let route = Route {
+            sender: message.sender,
+            receiver,
+        };
+
+        let to_channel = if let Some((_, to_channel)) = self
+            .channels
+            .iter()
+            .find(|(candidate, _)| *candidate == receiver)
+        {
+            to_channel
+        } else {
+            return Err(Box::new(DeadLetter {
+                error: RoutingError::UnknownReceiver(receiver),
+                message,
+            }));
+        };
+
+        // Recorded ahead of sending, as delivery consumes the message
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&route, &message.payload);
+        }
+
+        // Also recorded ahead of sending, so the receiver can never report a message as handled
+        // before it has been counted as routed
+        self.instrumentation
+            .message_routed(&route, to_channel.tx().len() + 1);
+
+        // A failed send is reported as a dead letter by the caller
+        to_channel.tx().send(message).map_err(|SendError(message)| {
+            self.connections.write().disconnected.insert(receiver);
+            Box::new(DeadLetter {
+                error: RoutingError::Disconnected(receiver),
+                message,
+            })
+        })
+    }
+
+    /// Forward an undeliverable message to the dead letter queue if one exists,
+    /// or bounce its error back to the sending world
+    fn handle_dead_letter(&self, dead_letter: DeadLetter) {
+        let route = Route {
+            sender: dead_letter.message.sender,
+            receiver: dead_letter.error.receiver(),
+        };
+        self.instrumentation
+            .message_dead_lettered(&route, &dead_letter.error);
+
+        let dead_letter = match &self.dead_letters {
+            Some(dead_letters) => match dead_letters.send(dead_letter) {
+                Ok(()) => return,
+                Err(SendError(dead_letter)) => dead_letter,
+            },
+            None => dead_letter,
+        };
+
+        let DeadLetter { error, message } = dead_letter;
+        let sender = message.sender_id();
+        let bounce = message.reply(move |_| Err(Box::new(error)));
+
+        // If the sender has also disconnected, there is nobody left to notify
+        let _ = self.route(sender, bounce);
+    }
+}
+
+/// Two-way channel of world messages
+pub struct WorldChannel {
+    channel: TwoWayChannel<WorldMessage, WorldMessage>,
+    world_id: WorldId,
+    connections: Arc<RwLock<Connections>>,
+    entity_map: Arc<RwLock<EntityMap>>,
+    instrumentation: Instrumentation,
+    shutdown: AtomicBool,
+}
+
+impl WorldChannel {
+    fn new(
+        channel: TwoWayChannel<WorldMessage, WorldMessage>,
+        world_id: WorldId,
+        connections: Arc<RwLock<Connections>>,
+        entity_map: Arc<RwLock<EntityMap>>,
+        instrumentation: Instrumentation,
+    ) -> Self {
+        WorldChannel {
+            channel,
+            world_id,
+            connections,
+            entity_map,
+            instrumentation,
+            shutdown: AtomicBool::new(false),
+        }
+    }
+
+    /// The ID of the world this channel belongs to
+    pub fn world_id(&self) -> WorldId {
+        self.world_id
+    }
+
+    pub fn tx(&self) -> &Sender<WorldMessage> {
+        &self.channel.tx
+    }
+
+    pub fn rx(&self) -> &Receiver<WorldMessage> {
+        &self.channel.rx
+    }
+
+    pub fn send(&self, message: WorldMessage) -> Result<(), SendError<WorldMessage>> {
+        self.channel.tx.send(message)
+    }
+
+    pub fn try_send(&self, message: WorldMessage) -> Result<(), TrySendError<WorldMessage>> {
+        self.channel.tx.try_send(message)
+    }
+
+    pub fn recv(&self) -> Result<WorldMessage, RecvError> {
+        self.channel.rx.recv()
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<WorldMessage, RecvTimeoutError> {
+        self.channel.rx.recv_timeout(timeout)
+    }
+
+    pub fn try_recv(&self) -> Result<WorldMessage, TryRecvError> {
+        self.channel.rx.try_recv()
+    }
+
+    /// Returns true if the default instance of world U has a channel on the exchange
+    /// and has not dropped it
+    pub fn is_connected<U: 'static>(&self) -> bool {
+        self.is_world_connected(&WorldId::of::<U>())
+    }
+
+    /// Returns true if the given world has a channel on the exchange and has not dropped it
+    pub fn is_world_connected(&self, world_id: &WorldId) -> bool {
+        self.connections.read().is_connected(world_id)
+    }
+
+    /// Flag this channel's world for shutdown
+    pub fn request_shutdown(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if this channel's world has been asked to shut down
+    pub fn is_shutdown_requested(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+}
+
+/// Trait for sending a message to a given world while inferring its type via param F
+pub trait SendTo<Message, Value> {
+    fn send_to<T>(&self, f: Message) -> Result<(), SendError<Value>>
+    where
+        T: 'static;
+
+    fn try_send_to<T>(&self, f: Message) -> Result<(), TrySendError<Value>>
+    where
+        T: 'static;
+}
+
+impl<Message> SendTo<Message, WorldMessage> for WorldChannel
+where
+    Message: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+{
+    fn send_to<W>(&self, f: Message) -> Result<(), SendError<WorldMessage>>
+    where
+        W: 'static,
+    {
+        self.send(WorldMessage::to::<W, _>(f))
+    }
+
+    fn try_send_to<W>(&self, f: Message) -> Result<(), TrySendError<WorldMessage>>
+    where
+        W: 'static,
+    {
+        self.try_send(WorldMessage::to::<W, _>(f))
+    }
+}
+
+/// Boxed message closure, evaluated against the receiving world
+pub type MessageFn =
+    Box<dyn for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static>;
+
+/// Cross-thread message between worlds
+pub struct WorldMessage {
+    sender: Option<WorldId>,
+    receivers: Box<Recipients>,
+    payload: MessagePayload,
+}
+
+impl WorldMessage {
+    pub fn sender(&self) -> std::any::TypeId {
+        self.sender_id().type_id()
+    }
+
+    pub fn sender_name(&self) -> &'static str {
+        self.sender_id().name()
+    }
+
+    pub fn sender_id(&self) -> WorldId {
+        self.sender
+            .expect("Sender is not available until the message is sent")
+    }
+
+    /// Returns the receiving world of a single-receiver message,
+    /// or `None` if it is addressed to a pool or more than one world
+    ///
+    /// Messages delivered by the exchange always have a single receiver.
+    pub fn receiver(&self) -> Option<std::any::TypeId> {
+        self.receiver_id().map(|receiver| receiver.type_id())
+    }
+
+    pub fn receiver_name(&self) -> Option<&'static str> {
+        self.receiver_id().map(|receiver| receiver.name())
+    }
+
+    pub fn receiver_id(&self) -> Option<WorldId> {
+        match &*self.receivers {
+            Recipients::World(receiver) => Some(*receiver),
+            _ => None,
+        }
+    }
+
+    pub fn receivers(&self) -> &Recipients {
+        &self.receivers
+    }
+
+    pub fn message(self) -> MessageFn {
+        self.payload.into_message()
+    }
+}
+
+impl std::fmt::Debug for WorldMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorldMessage")
+            .field("from", &self.sender)
+            .field("to", &self.receivers)
+            .finish()
+    }
+}
+
+impl WorldMessage {
+    fn new<
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    >(
+        receiver: WorldId,
+        message: F,
+    ) -> Self {
+        WorldMessage::with_receivers(Recipients::World(receiver), message)
+    }
+
+    /// Construct a single-closure message for a single-receiver recipient set
+    fn with_receivers<
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    >(
+        receivers: Recipients,
+        message: F,
+    ) -> Self {
+        debug_assert!(matches!(
+            receivers,
+            Recipients::World(_) | Recipients::Pool(_)
+        ));
+
+        WorldMessage {
+            sender: None,
+            receivers: Box::new(receivers),
+            payload: MessagePayload::Once(Box::new(message)),
+        }
+    }
+
+    /// Construct a message to be sent to world U
+    pub fn to<
+        U: 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    >(
+        message: F,
+    ) -> Self {
+        WorldMessage::new(WorldId::of::<U>(), message)
+    }
+
+    /// Construct a message to be sent back to the world that sent this one
+    pub fn reply<
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    >(
+        &self,
+        message: F,
+    ) -> Self {
+        WorldMessage::new(self.sender_id(), message)
+    }
+}
+
+/// References to a world and its exchange channel
+pub type MessageContext<'a, 'b> = (&'a mut World, &'b WorldChannel);
+
+/// Result wrapper to enable compositional flow control
+pub type MessageResult<'a, 'b> = Result<MessageContext<'a, 'b>, Box<dyn std::error::Error>>;
+
+/// Lift a type into a MessageResult
+pub trait Lift {
+    type To;
+    fn lift(self) -> Self::To;
+}
+
+impl<'a, 'b> Lift for MessageContext<'a, 'b> {
+    type To = MessageResult<'a, 'b>;
+
+    fn lift(self) -> Self::To {
+        Ok(self)
+    }
+}
+
+fn insert_resources<C: DynamicBundle>(
+    bundle: C,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (world, _) = &mut ctx;
+        tracing::debug!(bundle = std::any::type_name::<C>(), "Inserting resources");
+        world.insert_resources(bundle);
+        Ok(ctx)
+    }
+}
+
+fn insert_component<C: DynamicBundle>(
+    entity: Entity,
+    component: C,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (world, _) = &mut ctx;
+        tracing::debug!(
+            bundle = std::any::type_name::<C>(),
+            ?entity,
+            "Inserting bundle"
+        );
+        world.insert(entity, component)?;
+        Ok(ctx)
+    }
+}
+
+/// Clone the components matched by query Q and send them to the mirror of `entity` in world U,
+/// spawning a mirror if none exists
+pub fn send_clone_query<Q, U>(
+    entity: Entity,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b>
+where
+    Q: Query,
+    for<'q> <<Q as Query>::Fetch as hecs::Fetch<'q>>::Item: ClonedBundle,
+    U: Send + 'static,
+{
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        tracing::debug!(
+            query = std::any::type_name::<Q>(),
+            receiver = std::any::type_name::<U>(),
+            "Sending cloned query"
+        );
+
+        let components = world.query_one_mut::<Q>(entity).unwrap();
+        let bundle = components.cloned_bundle();
+        drop(components);
+
+        channel.send(WorldMessage::to::<U, _>(insert_mirrored(
+            channel.world_id(),
+            entity,
+            bundle,
+        )))?;
+
+        Ok(ctx)
+    }
+}
+
+/// Clone the resources matched by query Q and send them to world U,
+/// replacing any it already holds
+pub fn send_clone_resources<Q, U>(
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b>
+where
+    Q: Query,
+    for<'q> <<Q as Query>::Fetch as hecs::Fetch<'q>>::Item: ClonedBundle,
+    U: Send + 'static,
+{
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        tracing::debug!(
+            query = std::any::type_name::<Q>(),
+            receiver = std::any::type_name::<U>(),
+            "Sending cloned resources"
+        );
+
+        let bundle = world.query_resources_mut::<Q>()?.cloned_bundle();
+
+        channel.send(WorldMessage::to::<U, _>(insert_resources(bundle)))?;
+
+        Ok(ctx)
+    }
+}
+
+/// Copy component C and send it to the mirror of `entity` in world U,
+/// spawning a mirror if none exists
+pub fn send_copy_component<C, U>(
+    entity: Entity,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b>
+where
+    C: Component + Copy,
+    U: Send + 'static,
+{
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        let component_name = std::any::type_name::<C>();
+        tracing::debug!(
+            component = component_name,
+            receiver = std::any::type_name::<U>(),
+            "Sending copied component"
+        );
+
+        let mut query = world.query_one::<&C>(entity).unwrap();
+        let component = *if let Some(component) = query.get() {
+            component
+        } else {
+            Err(format!("Error: No such {} component", component_name))?
+        };
+
+        channel.send(WorldMessage::to::<U, _>(insert_mirrored(
+            channel.world_id(),
+            entity,
+            (component,),
+        )))?;
+
+        drop(query);
+
+        Ok(ctx)
+    }
+}
+
+/// Move Send component C from entity with key component T to `entity` in world U,
+/// mapping the two as mirrors of one another
+pub fn send_component<C, U, T>(
+    key: T,
+    entity: Entity,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b>
+where
+    C: Component,
+    T: Component + PartialEq,
+    U: Send + 'static,
+{
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        let ids = world
+            .query_mut::<(&T, &C)>()
+            .into_iter()
+            .filter(|(_, (k, _))| **k == key)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+
+        for id in ids {
+            let value = world.remove::<(C,)>(id).unwrap();
+
+            tracing::debug!(
+                component = std::any::type_name::<C>(),
+                receiver = std::any::type_name::<U>(),
+                ?id,
+                "Sending component"
+            );
+
+            channel.send(WorldMessage::to::<U, _>(insert_component(entity, value)))?;
+            channel.map_entity(id, WorldId::of::<U>(), entity);
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Move Send component C from each entity with key component T to its mirror in world U,
+/// spawning a mirror if none exists
+pub fn send_mirrored_component<C, U, T>(
+    key: T,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b>
+where
+    C: Component,
+    T: Component + PartialEq,
+    U: Send + 'static,
+{
+    move |mut ctx| {
+        let (world, channel) = &mut ctx;
+
+        let ids = world
+            .query_mut::<(&T, &C)>()
+            .into_iter()
+            .filter(|(_, (k, _))| **k == key)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+
+        for id in ids {
+            let value = world.remove::<(C,)>(id).unwrap();
+
+            tracing::debug!(
+                component = std::any::type_name::<C>(),
+                receiver = std::any::type_name::<U>(),
+                ?id,
+                "Sending mirrored component"
+            );
+
+            channel.send(WorldMessage::to::<U, _>(insert_mirrored(
+                channel.world_id(),
+                id,
+                value,
+            )))?;
+        }
+
+        Ok(ctx)
+    }
+}
+
+/// Receive any pending messages from `channel` and handle them
+pub fn try_receive_messages(
+    world: &mut World,
+    channel: &WorldChannel,
+) -> Result<(), Box<dyn std::error::Error>> {
+    while let Ok(message) = channel.try_recv() {
+        handle_message(world, channel, message)?;
+    }
+    Ok(())
+}
+
+/// Block until a message is received from `channel` and handle it
+pub fn receive_messages(
+    world: &mut World,
+    channel: &WorldChannel,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let message = channel.recv()?;
+    handle_message(world, channel, message)
+}
+
+/// Wait up to `timeout` for a message from `channel` and handle it,
+/// returning without error if none arrives
+pub fn receive_messages_timeout(
+    world: &mut World,
+    channel: &WorldChannel,
+    timeout: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match channel.recv_timeout(timeout) {
+        Ok(message) => handle_message(world, channel, message),
+        Err(RecvTimeoutError::Timeout) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Handle a message received from `channel` within a tracing span,
+/// reporting its latency to the exchange's instruments
+pub fn handle_message(
+    world: &mut World,
+    channel: &WorldChannel,
+    message: WorldMessage,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let route = Route {
+        sender: message.sender,
+        receiver: channel.world_id(),
+    };
+
+    let span = tracing::debug_span!("world_message", %route);
+    let _enter = span.enter();
+
+    let start = Instant::now();
+    let result = (message.message())((world, channel)).map(|_| ());
+    let latency = start.elapsed();
+
+    if let Err(e) = &result {
+        tracing::debug!(error = %e, "Message handler failed");
+    }
+
+    channel
+        .instrumentation
+        .message_handled(&route, latency, result.is_ok());
+
+    result
+}
@@ -0,0 +1,197 @@
+use super::{
+    ErasedCommand, MessageContext, MessageFn, MessageResult, Recipients, WorldChannel, WorldId,
+    WorldMessage,
+};
+use crossbeam_channel::SendError;
+use std::sync::Arc;
+
+/// Factory producing a fresh message closure for each receiving world
+pub type MessageFactory = Arc<dyn Fn() -> MessageFn + Send + Sync + 'static>;
+
+/// Message closure, or a factory for creating one per receiver
+pub(crate) enum MessagePayload {
+    Once(MessageFn),
+    Factory(MessageFactory),
+    Command(Box<dyn ErasedCommand>),
+}
+
+impl MessagePayload {
+    /// Create a single-receiver payload from a factory
+    pub fn instance(&self) -> Self {
+        match self {
+            MessagePayload::Once(_) | MessagePayload::Command(_) => {
+                panic!("Single-receiver payloads cannot be instanced")
+            }
+            MessagePayload::Factory(factory) => MessagePayload::Once(factory()),
+        }
+    }
+
+    pub fn into_message(self) -> MessageFn {
+        match self {
+            MessagePayload::Once(message) => message,
+            MessagePayload::Factory(factory) => factory(),
+            MessagePayload::Command(command) => command.into_message(),
+        }
+    }
+}
+
+fn message_factory<F, M>(factory: F) -> MessageFactory
+where
+    F: Fn() -> M + Send + Sync + 'static,
+    M: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+{
+    Arc::new(move || Box::new(factory()) as MessageFn)
+}
+
+impl WorldMessage {
+    /// Construct a message to be sent to every connected world except its sender
+    ///
+    /// `factory` is invoked once per receiving world.
+    pub fn broadcast<F, M>(factory: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+        M: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        WorldMessage {
+            sender: None,
+            receivers: Box::new(Recipients::Broadcast),
+            payload: MessagePayload::Factory(message_factory(factory)),
+        }
+    }
+
+    /// Construct a message to be sent to each world in `receivers`
+    ///
+    /// `factory` is invoked once per receiving world.
+    pub fn multicast<F, M>(receivers: Vec<WorldId>, factory: F) -> Self
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+        M: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        WorldMessage {
+            sender: None,
+            receivers: Box::new(Recipients::Multicast(receivers)),
+            payload: MessagePayload::Factory(message_factory(factory)),
+        }
+    }
+}
+
+impl WorldChannel {
+    /// Send a message to every other connected world
+    pub fn broadcast<F, M>(&self, factory: F) -> Result<(), SendError<WorldMessage>>
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+        M: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        self.send(WorldMessage::broadcast(factory))
+    }
+
+    /// Send a message to each world in `receivers`
+    pub fn multicast<F, M>(
+        &self,
+        receivers: Vec<WorldId>,
+        factory: F,
+    ) -> Result<(), SendError<WorldMessage>>
+    where
+        F: Fn() -> M + Send + Sync + 'static,
+        M: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        self.send(WorldMessage::multicast(receivers, factory))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{receive_messages, RoutingError, WorldExchange};
+    use hecs::World;
+    use std::time::Duration;
+
+    enum First {}
+    enum Second {}
+    enum Third {}
+    enum Unregistered {}
+
+    struct Reloaded;
+
+    fn mark_reloaded() -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+        |mut ctx| {
+            let (world, _) = &mut ctx;
+            world.spawn((Reloaded,));
+            Ok(ctx)
+        }
+    }
+
+    fn reloaded_count(world: &World) -> usize {
+        world.query::<&Reloaded>().iter().count()
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let mut exchange = WorldExchange::default();
+        let first = exchange.create_channel::<First>();
+        let second = exchange.create_channel::<Second>();
+        let third = exchange.create_channel::<Third>();
+        let _exchange = exchange.spawn();
+
+        first.broadcast(mark_reloaded).unwrap();
+
+        for channel in [&second, &third] {
+            let mut world = World::new();
+            receive_messages(&mut world, channel).unwrap();
+            assert_eq!(reloaded_count(&world), 1);
+        }
+
+        // The sender does not receive its own broadcast
+        assert!(first.recv_timeout(Duration::from_millis(50)).is_err());
+    }
+
+    #[test]
+    fn test_multicast() {
+        let mut exchange = WorldExchange::default();
+        let dead_letters = exchange.dead_letters();
+        let first = exchange.create_channel::<First>();
+        let second = exchange.create_channel::<Second>();
+        let third = exchange.create_channel::<Third>();
+        let _exchange = exchange.spawn();
+
+        first
+            .multicast(
+                vec![
+                    WorldId::of::<First>(),
+                    WorldId::of::<Third>(),
+                    WorldId::of::<Unregistered>(),
+                ],
+                mark_reloaded,
+            )
+            .unwrap();
+
+        for channel in [&first, &third] {
+            let mut world = World::new();
+            receive_messages(&mut world, channel).unwrap();
+            assert_eq!(reloaded_count(&world), 1);
+        }
+
+        assert!(second.recv_timeout(Duration::from_millis(50)).is_err());
+
+        let dead_letter = dead_letters.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(
+            dead_letter.error,
+            RoutingError::UnknownReceiver(WorldId::of::<Unregistered>())
+        );
+    }
+
+    #[test]
+    fn test_multi_receiver_accessors() {
+        let broadcast = WorldMessage::broadcast(mark_reloaded);
+        assert_eq!(broadcast.receiver_id(), None);
+        assert_eq!(broadcast.receivers(), &Recipients::Broadcast);
+
+        let receivers = vec![WorldId::of::<First>(), WorldId::of::<Second>()];
+        let multicast = WorldMessage::multicast(receivers.clone(), mark_reloaded);
+        assert_eq!(multicast.receiver_name(), None);
+        assert_eq!(multicast.receivers(), &Recipients::Multicast(receivers));
+
+        let single = WorldMessage::to::<Third, _>(mark_reloaded());
+        assert_eq!(single.receiver_id(), Some(WorldId::of::<Third>()));
+    }
+}
@@ -0,0 +1,132 @@
+use super::{MessageContext, MessageResult, Recipients, WorldChannel, WorldId, WorldMessage};
+use crossbeam_channel::SendError;
+
+impl WorldMessage {
+    /// Construct a message to be sent to a specific world instance
+    pub fn to_instance<
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    >(
+        receiver: WorldId,
+        message: F,
+    ) -> Self {
+        WorldMessage::with_receivers(Recipients::World(receiver), message)
+    }
+
+    /// Construct a message to be sent to one instance of world U, chosen round-robin
+    pub fn to_pool<
+        U: 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    >(
+        message: F,
+    ) -> Self {
+        WorldMessage::with_receivers(Recipients::Pool(WorldId::of::<U>()), message)
+    }
+}
+
+impl WorldChannel {
+    /// Send a message to a specific world instance
+    pub fn send_to_instance<F>(
+        &self,
+        receiver: WorldId,
+        f: F,
+    ) -> Result<(), SendError<WorldMessage>>
+    where
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        self.send(WorldMessage::to_instance(receiver, f))
+    }
+
+    /// Send a message to one instance of world U, chosen round-robin
+    pub fn send_to_pool<U, F>(&self, f: F) -> Result<(), SendError<WorldMessage>>
+    where
+        U: 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        self.send(WorldMessage::to_pool::<U, _>(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{receive_messages, InstanceId, WorldExchange};
+    use hecs::World;
+    use std::time::Duration;
+
+    enum Client {}
+    enum Server {}
+    enum Worker {}
+
+    struct Handled;
+
+    fn handled<'a, 'b>(mut ctx: MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+        let (world, _) = &mut ctx;
+        world.spawn((Handled,));
+        Ok(ctx)
+    }
+
+    #[test]
+    fn test_instances() {
+        let mut exchange = WorldExchange::default();
+        let client = exchange.create_channel::<Client>();
+        let server = exchange.create_named_channel::<Server>("server");
+        let predicted = exchange.create_named_channel::<Server>("predicted");
+        let _exchange = exchange.spawn();
+
+        assert_eq!(server.world_id().instance_id(), InstanceId::Named("server"));
+        assert!(client.is_world_connected(&WorldId::instance::<Server, _>("predicted")));
+        assert!(!client.is_connected::<Server>());
+
+        client
+            .send_to_instance(WorldId::instance::<Server, _>("predicted"), handled)
+            .unwrap();
+
+        let mut world = World::new();
+        receive_messages(&mut world, &predicted).unwrap();
+        assert_eq!(world.query::<&Handled>().iter().count(), 1);
+        assert!(server.recv_timeout(Duration::from_millis(50)).is_err());
+    }
+
+    #[test]
+    fn test_pool() {
+        let mut exchange = WorldExchange::default();
+        let client = exchange.create_channel::<Client>();
+        let workers = (0..3)
+            .map(|_| exchange.create_channel::<Worker>())
+            .collect::<Vec<_>>();
+        let _exchange = exchange.spawn();
+
+        assert_eq!(workers[0].world_id(), WorldId::of::<Worker>());
+        assert_eq!(workers[2].world_id(), WorldId::instance::<Worker, _>(2));
+
+        for _ in 0..6 {
+            client.send_to_pool::<Worker, _>(handled).unwrap();
+        }
+
+        // Each worker receives an equal share of the jobs
+        for worker in &workers {
+            let mut world = World::new();
+            for _ in 0..2 {
+                receive_messages(&mut world, worker).unwrap();
+            }
+            assert_eq!(world.query::<&Handled>().iter().count(), 2);
+            assert!(worker.recv_timeout(Duration::from_millis(50)).is_err());
+        }
+
+        let handle = client
+            .request_pool::<Worker, _, _>(|(_, channel)| Ok(channel.world_id()))
+            .unwrap();
+
+        let mut world = World::new();
+        for worker in &workers {
+            if let Ok(message) = worker.recv_timeout(Duration::from_millis(50)) {
+                (message.message())((&mut world, worker)).unwrap();
+            }
+        }
+
+        assert_eq!(
+            handle.wait_timeout(Duration::from_secs(1)),
+            Ok(WorldId::of::<Worker>())
+        );
+    }
+}
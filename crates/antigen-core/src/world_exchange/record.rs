@@ -0,0 +1,209 @@
+use super::{
+    InstanceId, MessageContext, MessageFn, MessagePayload, MessageResult, Recipients, Route,
+    WorldChannel, WorldExchange, WorldId, WorldMessage,
+};
+use crossbeam_channel::SendError;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{sync::Arc, time::Duration, time::Instant};
+
+/// Serializable message that can be recorded by an exchange and replayed later
+///
+/// Typically implemented by an enum covering the operations a world accepts.
+pub trait Command: Serialize + DeserializeOwned + Send + 'static {
+    /// Name under which this command is recorded and registered for replay
+    fn name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Apply this command to the receiving world
+    fn apply<'a, 'b>(self, ctx: MessageContext<'a, 'b>) -> MessageResult<'a, 'b>;
+}
+
+/// Type-erased command carried by a message payload
+pub(crate) trait ErasedCommand: Send {
+    fn name(&self) -> &'static str;
+    fn to_value(&self) -> Result<serde_json::Value, serde_json::Error>;
+    fn into_message(self: Box<Self>) -> MessageFn;
+}
+
+impl<C: Command> ErasedCommand for C {
+    fn name(&self) -> &'static str {
+        C::name()
+    }
+
+    fn to_value(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+
+    fn into_message(self: Box<Self>) -> MessageFn {
+        let command = *self;
+        Box::new(move |ctx| command.apply(ctx))
+    }
+}
+
+/// Serializable counterpart to InstanceId
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RecordedInstance {
+    Index(usize),
+    Named(String),
+}
+
+/// Serializable counterpart to WorldId, identifying a world by its type name and instance
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RecordedWorld {
+    pub name: String,
+    pub instance: RecordedInstance,
+}
+
+impl RecordedWorld {
+    /// Returns true if this recorded world refers to `world_id`
+    pub fn matches(&self, world_id: &WorldId) -> bool {
+        *self == RecordedWorld::from(*world_id)
+    }
+}
+
+impl From<WorldId> for RecordedWorld {
+    fn from(world_id: WorldId) -> Self {
+        RecordedWorld {
+            name: world_id.name().to_string(),
+            instance: match world_id.instance_id() {
+                InstanceId::Index(index) => RecordedInstance::Index(index),
+                InstanceId::Named(name) => RecordedInstance::Named(name.to_string()),
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for RecordedWorld {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.instance {
+            RecordedInstance::Index(0) => f.write_str(&self.name),
+            RecordedInstance::Index(index) => write!(f, "{}#{}", self.name, index),
+            RecordedInstance::Named(name) => write!(f, "{}({})", self.name, name),
+        }
+    }
+}
+
+/// A command message as delivered by the exchange
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    /// Position of this message in the exchange's delivery order
+    pub sequence: u64,
+    /// Time since recording started
+    pub elapsed: Duration,
+    pub sender: Option<RecordedWorld>,
+    pub receiver: RecordedWorld,
+    /// Registered name of the command type
+    pub command: String,
+    pub payload: serde_json::Value,
+}
+
+/// Ordered stream of command messages captured from an exchange
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub messages: Vec<RecordedMessage>,
+}
+
+struct RecorderState {
+    start: Instant,
+    sequence: u64,
+    recording: Recording,
+}
+
+/// Handle to the command messages recorded by an exchange
+#[derive(Clone)]
+pub struct Recorder(Arc<Mutex<RecorderState>>);
+
+impl Recorder {
+    fn new() -> Self {
+        Recorder(Arc::new(Mutex::new(RecorderState {
+            start: Instant::now(),
+            sequence: 0,
+            recording: Recording::default(),
+        })))
+    }
+
+    /// Snapshot the messages recorded so far
+    pub fn recording(&self) -> Recording {
+        self.0.lock().recording.clone()
+    }
+
+    /// Take the messages recorded so far, leaving the recorder empty
+    pub fn take(&self) -> Recording {
+        std::mem::take(&mut self.0.lock().recording)
+    }
+
+    /// Record a command message routed by the exchange
+    ///
+    /// Closure messages are opaque and cannot be recorded, so are skipped.
+    pub(crate) fn record(&self, route: &Route, payload: &MessagePayload) {
+        let command = match payload {
+            MessagePayload::Command(command) => command,
+            _ => return,
+        };
+
+        let value = match command.to_value() {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!(
+                    %route,
+                    command = command.name(),
+                    error = %e,
+                    "Failed to record command"
+                );
+                return;
+            }
+        };
+
+        let mut state = self.0.lock();
+        let message = RecordedMessage {
+            sequence: state.sequence,
+            elapsed: state.start.elapsed(),
+            sender: route.sender.map(Into::into),
+            receiver: route.receiver.into(),
+            command: command.name().to_string(),
+            payload: value,
+        };
+        state.sequence += 1;
+        state.recording.messages.push(message);
+    }
+}
+
+impl WorldExchange {
+    /// Record every command message delivered by this exchange
+    pub fn record(&mut self) -> Recorder {
+        self.recorder.get_or_insert_with(Recorder::new).clone()
+    }
+}
+
+impl WorldMessage {
+    /// Construct a recordable command message to be sent to world U
+    pub fn command<U: 'static, C: Command>(command: C) -> Self {
+        WorldMessage::command_to(Recipients::World(WorldId::of::<U>()), command)
+    }
+
+    /// Construct a recordable command message to be sent to one instance of world U,
+    /// chosen round-robin
+    pub fn command_pool<U: 'static, C: Command>(command: C) -> Self {
+        WorldMessage::command_to(Recipients::Pool(WorldId::of::<U>()), command)
+    }
+
+    fn command_to<C: Command>(receivers: Recipients, command: C) -> Self {
+        WorldMessage {
+            sender: None,
+            receivers: Box::new(receivers),
+            payload: MessagePayload::Command(Box::new(command)),
+        }
+    }
+}
+
+impl WorldChannel {
+    /// Send a recordable command to world U
+    pub fn send_command<U: 'static, C: Command>(
+        &self,
+        command: C,
+    ) -> Result<(), SendError<WorldMessage>> {
+        self.send(WorldMessage::command::<U, C>(command))
+    }
+}
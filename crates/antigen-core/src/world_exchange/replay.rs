@@ -0,0 +1,307 @@
+use super::{
+    Command, ErasedCommand, MessageFn, RecordedMessage, RecordedWorld, Recording, WorldChannel,
+    WorldExchange, WorldId,
+};
+use hecs::World;
+use std::collections::BTreeMap;
+
+type CommandDeserializer = fn(serde_json::Value) -> Result<MessageFn, serde_json::Error>;
+
+fn deserialize_command<C: Command>(
+    value: serde_json::Value,
+) -> Result<MessageFn, serde_json::Error> {
+    let command = serde_json::from_value::<C>(value)?;
+    Ok(Box::new(command).into_message())
+}
+
+/// Set of command types that can be reconstructed from a recording
+#[derive(Default, Clone)]
+pub struct CommandRegistry {
+    commands: BTreeMap<&'static str, CommandDeserializer>,
+}
+
+impl CommandRegistry {
+    pub fn register<C: Command>(&mut self) -> &mut Self {
+        self.commands.insert(C::name(), deserialize_command::<C>);
+        self
+    }
+
+    fn message(&self, message: &RecordedMessage) -> Result<MessageFn, ReplayError> {
+        let deserialize = self.commands.get(message.command.as_str()).ok_or_else(|| {
+            ReplayError::UnknownCommand {
+                sequence: message.sequence,
+                command: message.command.clone(),
+            }
+        })?;
+
+        deserialize(message.payload.clone()).map_err(|e| ReplayError::Deserialize {
+            sequence: message.sequence,
+            error: e.to_string(),
+        })
+    }
+}
+
+/// Error produced when a recorded message cannot be replayed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The message's command type has not been registered
+    UnknownCommand { sequence: u64, command: String },
+    /// No world has been added for the message's receiver
+    UnknownWorld { sequence: u64, world: RecordedWorld },
+    /// The message's payload does not match its registered command type
+    Deserialize { sequence: u64, error: String },
+    /// The command returned an error when applied to its world
+    Command { sequence: u64, error: String },
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::UnknownCommand { sequence, command } => {
+                write!(f, "Message {}: Unregistered command {}", sequence, command)
+            }
+            ReplayError::UnknownWorld { sequence, world } => {
+                write!(f, "Message {}: No world added for {}", sequence, world)
+            }
+            ReplayError::Deserialize { sequence, error } => {
+                write!(f, "Message {}: Failed to deserialize: {}", sequence, error)
+            }
+            ReplayError::Command { sequence, error } => {
+                write!(f, "Message {}: Command failed: {}", sequence, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Single-threaded driver for feeding a recording into a fresh set of worlds
+///
+/// Messages are applied in recorded order, making the result independent of thread timing.
+/// Any messages sent by commands during replay are discarded,
+/// as the ones that were commands will already be present in the recording.
+pub struct Replay {
+    registry: CommandRegistry,
+    exchange: WorldExchange,
+    worlds: Vec<(World, WorldChannel)>,
+}
+
+impl Replay {
+    pub fn new(registry: CommandRegistry) -> Self {
+        Replay {
+            registry,
+            exchange: WorldExchange::default(),
+            worlds: Default::default(),
+        }
+    }
+
+    /// Add a world to receive messages recorded for the default instance of world U
+    pub fn add_world<U: 'static>(&mut self, world: World) -> &mut Self {
+        self.add_instance(WorldId::of::<U>(), world)
+    }
+
+    /// Add a world to receive messages recorded for `world_id`
+    pub fn add_instance(&mut self, world_id: WorldId, world: World) -> &mut Self {
+        let channel = self.exchange.create_instance_channel(world_id);
+        self.worlds.push((world, channel));
+        self
+    }
+
+    pub fn world(&self, world_id: &WorldId) -> Option<&World> {
+        self.worlds
+            .iter()
+            .find(|(_, channel)| channel.world_id() == *world_id)
+            .map(|(world, _)| world)
+    }
+
+    pub fn world_mut(&mut self, world_id: &WorldId) -> Option<&mut World> {
+        self.worlds
+            .iter_mut()
+            .find(|(_, channel)| channel.world_id() == *world_id)
+            .map(|(world, _)| world)
+    }
+
+    /// Apply every message in `recording` in sequence order
+    pub fn run(&mut self, recording: &Recording) -> Result<(), ReplayError> {
+        let mut messages = recording.messages.iter().collect::<Vec<_>>();
+        messages.sort_by_key(|message| message.sequence);
+
+        for message in messages {
+            self.step(message)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a single recorded message to its receiving world
+    pub fn step(&mut self, message: &RecordedMessage) -> Result<(), ReplayError> {
+        let (world, channel) = self
+            .worlds
+            .iter_mut()
+            .find(|(_, channel)| message.receiver.matches(&channel.world_id()))
+            .ok_or_else(|| ReplayError::UnknownWorld {
+                sequence: message.sequence,
+                world: message.receiver.clone(),
+            })?;
+
+        let f = self.registry.message(message)?;
+        let result = f((world, channel))
+            .map(|_| ())
+            .map_err(|e| ReplayError::Command {
+                sequence: message.sequence,
+                error: e.to_string(),
+            });
+
+        // Discard anything sent to the exchange while handling the message
+        for (_, channel) in &self.exchange.channels {
+            while channel.try_recv().is_ok() {}
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{receive_messages, run_until_shutdown, MessageContext, MessageResult};
+    use serde::{Deserialize, Serialize};
+
+    enum Game {}
+    enum Render {}
+
+    #[derive(Debug, Default, Copy, Clone, PartialEq)]
+    struct Score(i64);
+
+    #[derive(Serialize, Deserialize)]
+    enum ScoreCommand {
+        Add(i64),
+        Multiply(i64),
+        Fail,
+    }
+
+    impl Command for ScoreCommand {
+        fn apply<'a, 'b>(self, mut ctx: MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+            let (world, _) = &mut ctx;
+            let (_, score) = world
+                .query_mut::<&mut Score>()
+                .into_iter()
+                .next()
+                .ok_or("No score")?;
+
+            match self {
+                ScoreCommand::Add(value) => score.0 += value,
+                ScoreCommand::Multiply(value) => score.0 *= value,
+                ScoreCommand::Fail => Err("Command failed")?,
+            }
+
+            Ok(ctx)
+        }
+    }
+
+    fn score(world: &World) -> Score {
+        *world.query::<&Score>().iter().next().unwrap().1
+    }
+
+    fn score_world() -> World {
+        let mut world = World::new();
+        world.spawn((Score::default(),));
+        world
+    }
+
+    #[test]
+    fn test_record_replay() {
+        let mut exchange = WorldExchange::default();
+        let recorder = exchange.record();
+        let render = exchange.create_channel::<Render>();
+        let game = exchange.create_channel::<Game>();
+        let handle = exchange.spawn();
+
+        let game_thread = std::thread::spawn(move || {
+            let mut world = score_world();
+            run_until_shutdown(&mut world, &game, receive_messages).unwrap();
+            score(&world)
+        });
+
+        render
+            .send_command::<Game, _>(ScoreCommand::Add(3))
+            .unwrap();
+        render
+            .send_command::<Game, _>(ScoreCommand::Multiply(4))
+            .unwrap();
+        render
+            .send_command::<Game, _>(ScoreCommand::Add(-2))
+            .unwrap();
+
+        // Closure messages are delivered, but not recorded
+        render
+            .request::<Game, _, _>(|(_, _)| Ok(()))
+            .unwrap()
+            .wait()
+            .unwrap();
+
+        handle.shutdown().unwrap();
+        let recorded_score = game_thread.join().unwrap();
+        assert_eq!(recorded_score, Score(10));
+
+        let recording = recorder.take();
+        assert_eq!(recording.messages.len(), 3);
+        assert!(recording
+            .messages
+            .iter()
+            .all(|message| message.receiver.matches(&WorldId::of::<Game>())
+                && message.sender == Some(WorldId::of::<Render>().into())));
+
+        // Round-trip through a serialized form
+        let json = serde_json::to_string(&recording).unwrap();
+        let recording = serde_json::from_str::<Recording>(&json).unwrap();
+
+        let mut registry = CommandRegistry::default();
+        registry.register::<ScoreCommand>();
+
+        let mut replay = Replay::new(registry);
+        replay.add_world::<Game>(score_world());
+        replay.run(&recording).unwrap();
+
+        assert_eq!(
+            score(replay.world(&WorldId::of::<Game>()).unwrap()),
+            recorded_score
+        );
+    }
+
+    #[test]
+    fn test_replay_errors() {
+        let mut exchange = WorldExchange::default();
+        let recorder = exchange.record();
+        let render = exchange.create_channel::<Render>();
+        let game = exchange.create_channel::<Game>();
+        let _exchange = exchange.spawn();
+
+        render.send_command::<Game, _>(ScoreCommand::Fail).unwrap();
+        receive_messages(&mut score_world(), &game).unwrap_err();
+        let recording = recorder.recording();
+
+        let mut replay = Replay::new(CommandRegistry::default());
+        replay.add_world::<Game>(score_world());
+        assert!(matches!(
+            replay.run(&recording),
+            Err(ReplayError::UnknownCommand { sequence: 0, .. })
+        ));
+
+        let mut registry = CommandRegistry::default();
+        registry.register::<ScoreCommand>();
+
+        let mut replay = Replay::new(registry.clone());
+        assert!(matches!(
+            replay.run(&recording),
+            Err(ReplayError::UnknownWorld { sequence: 0, .. })
+        ));
+
+        let mut replay = Replay::new(registry);
+        replay.add_world::<Game>(score_world());
+        assert!(matches!(
+            replay.run(&recording),
+            Err(ReplayError::Command { sequence: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+use super::{insert_mirrored, remove_mirrored, WorldChannel, WorldId, WorldMessage};
+use crate::{ChangeTick, Changed, ChangedTrait};
+use crossbeam_channel::SendError;
+use hecs::{Component, Entity, World};
+use std::{collections::BTreeSet, marker::PhantomData};
+
+/// Declares that `Changed<C>` components in this world should be replicated to another world
+///
+/// Tracks which entities have been shipped and when,
+/// so that only components which have changed since are sent.
+pub struct Replicate<C> {
+    target: WorldId,
+    shipped: BTreeSet<Entity>,
+    observed: ChangeTick,
+    _phantom: PhantomData<fn() -> C>,
+}
+
+impl<C> Replicate<C> {
+    /// Replicate to the default instance of world U
+    pub fn to<U: 'static>() -> Self {
+        Replicate::to_instance(WorldId::of::<U>())
+    }
+
+    /// Replicate to a specific world instance
+    pub fn to_instance(target: WorldId) -> Self {
+        Replicate {
+            target,
+            shipped: Default::default(),
+            observed: Default::default(),
+            _phantom: Default::default(),
+        }
+    }
+
+    pub fn target(&self) -> WorldId {
+        self.target
+    }
+}
+
+/// Ship inserted, changed and removed `Changed<C>` components to the mirrors of their entities
+/// in each world declared by a `Replicate<C>` component
+///
+/// Replicated components arrive marked as changed.
+/// Mirrors left without any components by a removal are despawned.
+pub fn replicate_system<C>(
+    world: &mut World,
+    channel: &WorldChannel,
+) -> Result<(), SendError<WorldMessage>>
+where
+    C: Component + Clone,
+{
+    let sender = channel.world_id();
+
+    let mut query = world.query::<&mut Replicate<C>>();
+    for (_, replicate) in query.iter() {
+        let since = std::mem::replace(&mut replicate.observed, ChangeTick::advance());
+        let mut present = BTreeSet::new();
+
+        for (entity, component) in world.query::<&Changed<C>>().iter() {
+            present.insert(entity);
+
+            if replicate.shipped.contains(&entity) && !component.is_changed_since(since) {
+                continue;
+            }
+
+            let value = (**component).clone();
+            replicate.shipped.insert(entity);
+
+            tracing::trace!(
+                component = std::any::type_name::<C>(),
+                target = %replicate.target,
+                ?entity,
+                "Replicating component"
+            );
+
+            channel.send_to_instance(
+                replicate.target,
+                insert_mirrored(sender, entity, (Changed::new(value, true),)),
+            )?;
+        }
+
+        let removed = replicate
+            .shipped
+            .iter()
+            .filter(|entity| !present.contains(entity))
+            .copied()
+            .collect::<Vec<_>>();
+
+        for entity in removed {
+            replicate.shipped.remove(&entity);
+
+            tracing::trace!(
+                component = std::any::type_name::<C>(),
+                target = %replicate.target,
+                ?entity,
+                "Replicating component removal"
+            );
+
+            channel.send_to_instance(
+                replicate.target,
+                remove_mirrored::<Changed<C>>(sender, entity),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{try_receive_messages, WorldExchange};
+    use crossbeam_channel::RecvTimeoutError;
+    use std::time::Duration;
+
+    enum Game {}
+    enum Render {}
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Position(i32);
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Tag;
+
+    /// Wait for the exchange to deliver everything sent so far, then apply it
+    fn sync(world: &mut World, channel: &WorldChannel, expected: usize) {
+        for _ in 0..expected {
+            let message = channel.recv_timeout(Duration::from_secs(1)).unwrap();
+            crate::handle_message(world, channel, message).unwrap();
+        }
+        try_receive_messages(world, channel).unwrap();
+    }
+
+    /// Wait long enough for the exchange to deliver anything sent so far, failing if it does
+    fn assert_nothing_sent(channel: &WorldChannel) {
+        assert!(matches!(
+            channel.recv_timeout(Duration::from_millis(100)),
+            Err(RecvTimeoutError::Timeout)
+        ));
+    }
+
+    fn position(world: &World, entity: Entity) -> Position {
+        **world.get::<Changed<Position>>(entity).unwrap()
+    }
+
+    #[test]
+    fn test_replication() {
+        let mut exchange = WorldExchange::default();
+        let game = exchange.create_channel::<Game>();
+        let render = exchange.create_channel::<Render>();
+        let _exchange = exchange.spawn();
+
+        let mut game_world = World::new();
+        let mut render_world = World::new();
+
+        game_world.spawn((Replicate::<Position>::to::<Render>(),));
+        let a = game_world.spawn((Changed::new(Position(1), false),));
+        let b = game_world.spawn((Changed::new(Position(2), false), Tag));
+
+        // Inserts are always shipped
+        replicate_system::<Position>(&mut game_world, &game).unwrap();
+        sync(&mut render_world, &render, 2);
+
+        let mirror_a = game.mirror_entity(a, render.world_id()).unwrap();
+        let mirror_b = game.mirror_entity(b, render.world_id()).unwrap();
+        assert_eq!(position(&render_world, mirror_a), Position(1));
+        assert_eq!(position(&render_world, mirror_b), Position(2));
+        assert!(render_world
+            .get::<Changed<Position>>(mirror_a)
+            .unwrap()
+            .is_changed_since(ChangeTick::default()));
+
+        // Unchanged components are not shipped again
+        replicate_system::<Position>(&mut game_world, &game).unwrap();
+        assert_nothing_sent(&render);
+        assert_eq!(game.stats().received(render.world_id()).routed, 2);
+
+        // Changes are shipped to the existing mirror
+        {
+            let mut position = game_world.get_mut::<Changed<Position>>(a).unwrap();
+            **position = Position(3);
+            position.mark_changed();
+        }
+        replicate_system::<Position>(&mut game_world, &game).unwrap();
+        sync(&mut render_world, &render, 1);
+        assert_eq!(position(&render_world, mirror_a), Position(3));
+        assert_eq!(render_world.len(), 2);
+
+        // Changes are only shipped once
+        replicate_system::<Position>(&mut game_world, &game).unwrap();
+        assert_nothing_sent(&render);
+        assert_eq!(game.stats().received(render.world_id()).routed, 3);
+
+        // Removals are shipped, despawning mirrors left empty
+        game_world.despawn(a).unwrap();
+        game_world.remove_one::<Changed<Position>>(b).unwrap();
+        render_world.insert_one(mirror_b, Tag).unwrap();
+
+        replicate_system::<Position>(&mut game_world, &game).unwrap();
+        sync(&mut render_world, &render, 2);
+
+        assert!(!render_world.contains(mirror_a));
+        assert!(render_world.get::<Changed<Position>>(mirror_b).is_err());
+        assert!(render_world.get::<Tag>(mirror_b).is_ok());
+        assert_eq!(game.mirror_entity(a, render.world_id()), None);
+    }
+}
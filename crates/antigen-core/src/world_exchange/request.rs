@@ -0,0 +1,203 @@
+use super::{MessageContext, Recipients, WorldChannel, WorldId, WorldMessage};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, SendError, TryRecvError};
+use std::time::Duration;
+
+/// Error produced when a request fails to resolve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestError {
+    /// The remote world returned an error while handling the request
+    Remote(String),
+    /// No response arrived within the given duration
+    Timeout,
+    /// The request was dropped without producing a response
+    Disconnected,
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::Remote(e) => write!(f, "Remote world returned an error: {}", e),
+            RequestError::Timeout => write!(f, "Timed out waiting for response"),
+            RequestError::Disconnected => write!(f, "Request dropped without a response"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+/// Result of a request as delivered back to the sending world
+pub type RequestResult<R> = Result<R, RequestError>;
+
+/// Handle to the pending response of a request sent to another world
+#[derive(Debug)]
+pub struct RequestHandle<R> {
+    rx: Receiver<RequestResult<R>>,
+}
+
+impl<R> RequestHandle<R> {
+    /// Block until the remote world responds
+    pub fn wait(self) -> RequestResult<R> {
+        self.rx.recv().unwrap_or(Err(RequestError::Disconnected))
+    }
+
+    /// Block until the remote world responds, or `timeout` elapses
+    pub fn wait_timeout(self, timeout: Duration) -> RequestResult<R> {
+        match self.rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(RequestError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(RequestError::Disconnected),
+        }
+    }
+
+    /// Poll for a response without blocking, returning `None` if it has not arrived yet
+    pub fn try_wait(&self) -> Option<RequestResult<R>> {
+        match self.rx.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(RequestError::Disconnected)),
+        }
+    }
+}
+
+impl WorldMessage {
+    /// Construct a request to be evaluated in world U, along with a handle to its response
+    pub fn request<
+        U: 'static,
+        R: Send + 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> Result<R, Box<dyn std::error::Error>>
+            + Send
+            + 'static,
+    >(
+        request: F,
+    ) -> (Self, RequestHandle<R>) {
+        WorldMessage::request_to(Recipients::World(WorldId::of::<U>()), request)
+    }
+
+    /// Construct a request to be evaluated by one instance of pooled world U,
+    /// along with a handle to its response
+    pub fn request_pool<
+        U: 'static,
+        R: Send + 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> Result<R, Box<dyn std::error::Error>>
+            + Send
+            + 'static,
+    >(
+        request: F,
+    ) -> (Self, RequestHandle<R>) {
+        WorldMessage::request_to(Recipients::Pool(WorldId::of::<U>()), request)
+    }
+
+    fn request_to<
+        R: Send + 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> Result<R, Box<dyn std::error::Error>>
+            + Send
+            + 'static,
+    >(
+        receivers: Recipients,
+        request: F,
+    ) -> (Self, RequestHandle<R>) {
+        let (tx, rx) = bounded(1);
+
+        let message = WorldMessage::with_receivers(receivers, move |mut ctx| {
+            let (world, channel) = &mut ctx;
+            let result = request((world, channel)).map_err(|e| RequestError::Remote(e.to_string()));
+
+            // The requester may have dropped its handle, in which case the result is discarded
+            let _ = tx.send(result);
+
+            Ok(ctx)
+        });
+
+        (message, RequestHandle { rx })
+    }
+}
+
+impl WorldChannel {
+    /// Send a request to world U, returning a handle to its typed response
+    pub fn request<U, R, F>(&self, f: F) -> Result<RequestHandle<R>, SendError<WorldMessage>>
+    where
+        U: 'static,
+        R: Send + 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> Result<R, Box<dyn std::error::Error>>
+            + Send
+            + 'static,
+    {
+        let (message, handle) = WorldMessage::request::<U, R, F>(f);
+        self.send(message)?;
+        Ok(handle)
+    }
+
+    /// Send a request to one instance of pooled world U, returning a handle to its typed response
+    pub fn request_pool<U, R, F>(&self, f: F) -> Result<RequestHandle<R>, SendError<WorldMessage>>
+    where
+        U: 'static,
+        R: Send + 'static,
+        F: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> Result<R, Box<dyn std::error::Error>>
+            + Send
+            + 'static,
+    {
+        let (message, handle) = WorldMessage::request_pool::<U, R, F>(f);
+        self.send(message)?;
+        Ok(handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorldExchange;
+    use hecs::World;
+
+    enum Local {}
+    enum Remote {}
+
+    #[test]
+    fn test_request() {
+        let mut exchange = WorldExchange::default();
+        let local = exchange.create_channel::<Local>();
+        let remote = exchange.create_channel::<Remote>();
+        let _exchange = exchange.spawn();
+
+        let mut remote_world = World::new();
+        remote_world.spawn((42u32,));
+
+        let ok = local
+            .request::<Remote, _, _>(|(world, _)| {
+                let mut query = world.query::<&u32>();
+                let (_, value) = query.into_iter().next().ok_or("No u32 component")?;
+                Ok(*value)
+            })
+            .unwrap();
+
+        let err = local
+            .request::<Remote, u32, _>(|_| Err("Failed".into()))
+            .unwrap();
+
+        for _ in 0..2 {
+            let message = remote.recv().unwrap();
+            assert!(message.sender() == std::any::TypeId::of::<Local>());
+            (message.message())((&mut remote_world, &remote)).unwrap();
+        }
+
+        assert_eq!(ok.wait_timeout(Duration::from_secs(1)), Ok(42));
+        assert_eq!(
+            err.wait_timeout(Duration::from_secs(1)),
+            Err(RequestError::Remote("Failed".into()))
+        );
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let mut exchange = WorldExchange::default();
+        let local = exchange.create_channel::<Local>();
+        let _remote = exchange.create_channel::<Remote>();
+        let _exchange = exchange.spawn();
+
+        let handle = local.request::<Remote, (), _>(|_| Ok(())).unwrap();
+        assert_eq!(handle.try_wait(), None);
+        assert_eq!(
+            handle.wait_timeout(Duration::from_millis(10)),
+            Err(RequestError::Timeout)
+        );
+    }
+}
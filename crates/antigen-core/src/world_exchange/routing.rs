@@ -0,0 +1,132 @@
+use super::{WorldId, WorldMessage};
+use std::collections::BTreeSet;
+
+/// Error produced when the exchange is unable to deliver a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoutingError {
+    /// No channel has been created for the receiving world
+    UnknownReceiver(WorldId),
+    /// The receiving world has dropped its channel
+    Disconnected(WorldId),
+}
+
+impl RoutingError {
+    pub fn receiver(&self) -> WorldId {
+        match self {
+            RoutingError::UnknownReceiver(receiver) => *receiver,
+            RoutingError::Disconnected(receiver) => *receiver,
+        }
+    }
+}
+
+impl std::fmt::Display for RoutingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoutingError::UnknownReceiver(receiver) => {
+                write!(f, "No channel registered for world {}", receiver)
+            }
+            RoutingError::Disconnected(receiver) => {
+                write!(f, "World {} has disconnected", receiver)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RoutingError {}
+
+/// An undeliverable message, along with the reason it could not be routed
+#[derive(Debug)]
+pub struct DeadLetter {
+    pub error: RoutingError,
+    pub message: WorldMessage,
+}
+
+/// Connection state shared between an exchange and its channels
+#[derive(Debug, Default)]
+pub(crate) struct Connections {
+    pub registered: BTreeSet<WorldId>,
+    pub disconnected: BTreeSet<WorldId>,
+}
+
+impl Connections {
+    pub fn is_connected(&self, world_id: &WorldId) -> bool {
+        self.registered.contains(world_id) && !self.disconnected.contains(world_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{try_receive_messages, SendTo, WorldExchange};
+    use hecs::World;
+    use std::time::Duration;
+
+    enum Local {}
+    enum Remote {}
+    enum Unregistered {}
+
+    #[test]
+    fn test_unknown_receiver_bounce() {
+        let mut exchange = WorldExchange::default();
+        let local = exchange.create_channel::<Local>();
+        let _exchange = exchange.spawn();
+
+        local.send_to::<Unregistered>(|ctx| Ok(ctx)).unwrap();
+
+        let bounce = local.recv_timeout(Duration::from_secs(1)).unwrap();
+        let mut world = World::new();
+        let err = (bounce.message())((&mut world, &local)).err().unwrap();
+        let err = err.downcast_ref::<RoutingError>().unwrap();
+        assert_eq!(err.receiver(), WorldId::of::<Unregistered>());
+    }
+
+    #[test]
+    fn test_dead_letters() {
+        let mut exchange = WorldExchange::default();
+        let dead_letters = exchange.dead_letters();
+        let local = exchange.create_channel::<Local>();
+        let _exchange = exchange.spawn();
+
+        local.send_to::<Unregistered>(|ctx| Ok(ctx)).unwrap();
+
+        let dead_letter = dead_letters.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert!(matches!(
+            dead_letter.error,
+            RoutingError::UnknownReceiver(_)
+        ));
+        assert_eq!(dead_letter.message.sender_id(), WorldId::of::<Local>());
+        assert_eq!(
+            dead_letter.message.receiver_id(),
+            Some(WorldId::of::<Unregistered>())
+        );
+    }
+
+    #[test]
+    fn test_disconnected_receiver() {
+        let mut exchange = WorldExchange::default();
+        let local = exchange.create_channel::<Local>();
+        let remote = exchange.create_channel::<Remote>();
+        let _exchange = exchange.spawn();
+
+        assert!(local.is_connected::<Remote>());
+        assert!(!local.is_connected::<Unregistered>());
+
+        drop(remote);
+
+        local.send_to::<Remote>(|ctx| Ok(ctx)).unwrap();
+
+        let bounce = local.recv_timeout(Duration::from_secs(1)).unwrap();
+        let mut world = World::new();
+        let err = (bounce.message())((&mut world, &local)).err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<RoutingError>(),
+            Some(RoutingError::Disconnected(_))
+        ));
+        assert!(!local.is_connected::<Remote>());
+
+        // Routing errors are surfaced by the regular message handling functions
+        local.send_to::<Remote>(|ctx| Ok(ctx)).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(try_receive_messages(&mut world, &local).is_err());
+    }
+}
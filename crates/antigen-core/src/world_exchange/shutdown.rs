@@ -0,0 +1,125 @@
+use super::{WorldChannel, WorldId, WorldMessage};
+use crossbeam_channel::Sender;
+use hecs::World;
+use std::thread::JoinHandle;
+
+/// Guard over a running exchange thread
+///
+/// Dropping the guard will broadcast a shutdown message to all worlds,
+/// stop routing, and wait for the exchange thread to exit.
+#[must_use = "Dropping a WorldExchangeHandle shuts down its exchange"]
+pub struct WorldExchangeHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WorldExchangeHandle {
+    pub(crate) fn new(stop: Sender<()>, thread: JoinHandle<()>) -> Self {
+        WorldExchangeHandle {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns true if the exchange thread has stopped routing
+    pub fn is_finished(&self) -> bool {
+        self.thread
+            .as_ref()
+            .map(JoinHandle::is_finished)
+            .unwrap_or(true)
+    }
+
+    /// Broadcast a shutdown message to all worlds and wait for the exchange thread to exit
+    pub fn shutdown(mut self) -> std::thread::Result<()> {
+        self.stop_and_join()
+    }
+
+    fn stop_and_join(&mut self) -> std::thread::Result<()> {
+        if let Some(stop) = self.stop.take() {
+            // The exchange may already have stopped if every world disconnected
+            let _ = stop.send(());
+        }
+
+        match self.thread.take() {
+            Some(thread) => thread.join(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for WorldExchangeHandle {
+    fn drop(&mut self) {
+        let _ = self.stop_and_join();
+    }
+}
+
+impl WorldMessage {
+    /// Construct a control message that flags the receiving world's channel for shutdown
+    pub(crate) fn shutdown(receiver: WorldId) -> Self {
+        WorldMessage::new(receiver, |ctx| {
+            let (_, channel) = &ctx;
+            channel.request_shutdown();
+            Ok(ctx)
+        })
+    }
+}
+
+/// Repeatedly run `f` against a world until its channel receives a shutdown request
+pub fn run_until_shutdown<F>(
+    world: &mut World,
+    channel: &WorldChannel,
+    mut f: F,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnMut(&mut World, &WorldChannel) -> Result<(), Box<dyn std::error::Error>>,
+{
+    while !channel.is_shutdown_requested() {
+        f(world, channel)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{receive_messages, WorldExchange};
+
+    enum First {}
+    enum Second {}
+
+    #[test]
+    fn test_shutdown() {
+        let mut exchange = WorldExchange::default();
+        let first = exchange.create_channel::<First>();
+        let second = exchange.create_channel::<Second>();
+        let handle = exchange.spawn();
+
+        let threads = [first, second]
+            .into_iter()
+            .map(|channel| {
+                std::thread::spawn(move || {
+                    let mut world = World::new();
+                    run_until_shutdown(&mut world, &channel, receive_messages).is_ok()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert!(!handle.is_finished());
+        handle.shutdown().unwrap();
+
+        for thread in threads {
+            assert!(thread.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_shutdown_on_drop() {
+        let mut exchange = WorldExchange::default();
+        let first = exchange.create_channel::<First>();
+        drop(exchange.spawn());
+
+        let mut world = World::new();
+        receive_messages(&mut world, &first).unwrap();
+        assert!(first.is_shutdown_requested());
+    }
+}
@@ -1,6 +1,13 @@
-use antigen_core::{Construct, MessageContext, MessageResult, Usage};
+use antigen_core::{
+    Changed, Construct, MessageContext, MessageResult, PrefabLibrary, Resources, Usage,
+};
+use hecs::EntityBuilder;
 use std::path::PathBuf;
 
+mod watch;
+
+pub use watch::*;
+
 pub enum FilePath {}
 pub enum FileBytes {}
 pub enum FileString {}
@@ -13,13 +20,13 @@ pub type FileStringComponent = Usage<FileString, String>;
 #[derive(hecs::Bundle)]
 pub struct FileBytesBundle {
     path: FilePathComponent,
-    bytes: FileBytesComponent,
+    bytes: Changed<FileBytesComponent>,
 }
 
 impl FileBytesBundle {
     pub fn new<P: Into<PathBuf>, B: Into<Vec<u8>>>(path: P, bytes: B) -> Self {
         let path = FilePathComponent::construct(path.into());
-        let bytes = FileBytesComponent::construct(bytes.into());
+        let bytes = Changed::new(FileBytesComponent::construct(bytes.into()), false);
 
         FileBytesBundle { path, bytes }
     }
@@ -28,13 +35,13 @@ impl FileBytesBundle {
 #[derive(hecs::Bundle)]
 pub struct FileStringBundle {
     path: FilePathComponent,
-    string: FileStringComponent,
+    string: Changed<FileStringComponent>,
 }
 
 impl FileStringBundle {
     pub fn new<P: Into<PathBuf>, S: Into<String>>(path: P, string: S) -> Self {
         let path = FilePathComponent::construct(path.into());
-        let string = FileStringComponent::construct(string.into());
+        let string = Changed::new(FileStringComponent::construct(string.into()), false);
 
         FileStringBundle { path, string }
     }
@@ -43,13 +50,13 @@ impl FileStringBundle {
 #[derive(hecs::Query)]
 pub struct FileStringQuery<'a> {
     pub path: &'a FilePathComponent,
-    pub string: &'a FileStringComponent,
+    pub string: &'a Changed<FileStringComponent>,
 }
 
 #[derive(hecs::Query)]
 pub struct FileBytesQuery<'a> {
     pub path: &'a FilePathComponent,
-    pub string: &'a FileBytesComponent,
+    pub string: &'a Changed<FileBytesComponent>,
 }
 
 /// Load a file and store it in the World with a FileStringBundle
@@ -60,21 +67,22 @@ pub fn load_file_string<'a, 'b, P: Into<PathBuf>>(
         let (world, _) = &mut ctx;
         let path = path.into();
 
-        println!(
-            "Thread {} loading file {:?}...",
-            std::thread::current().name().unwrap(),
-            path,
-        );
+        tracing::debug!(?path, "Loading file");
+        let modified = std::fs::metadata(&path)?.modified()?;
         let file = std::fs::read_to_string(&path)?;
 
-        println!("Loaded file, spawning into world...");
-        world.spawn(FileStringBundle::new(path, file));
-        
+        tracing::debug!(?path, "Loaded file, spawning into world");
+        let mut builder = EntityBuilder::new();
+        builder
+            .add_bundle(FileStringBundle::new(path, file))
+            .add(FileModifiedComponent::construct(modified));
+        world.spawn(builder.build());
+
         Ok(ctx)
     }
 }
 
-/// Load a file and store it in the World with a FileStringBundle
+/// Load a file and store it in the World with a FileBytesBundle
 pub fn load_file_bytes<'a, 'b, P: Into<PathBuf>>(
     path: P,
 ) -> impl FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
@@ -82,15 +90,37 @@ pub fn load_file_bytes<'a, 'b, P: Into<PathBuf>>(
         let (world, _) = &mut ctx;
         let path = path.into();
 
-        println!(
-            "Thread {} loading file {:?}...",
-            std::thread::current().name().unwrap(),
-            path,
-        );
-        let file = std::fs::read_to_string(&path)?;
+        tracing::debug!(?path, "Loading file");
+        let modified = std::fs::metadata(&path)?.modified()?;
+        let file = std::fs::read(&path)?;
+
+        tracing::debug!(?path, "Loaded file, spawning into world");
+        let mut builder = EntityBuilder::new();
+        builder
+            .add_bundle(FileBytesBundle::new(path, file))
+            .add(FileModifiedComponent::construct(modified));
+        world.spawn(builder.build());
+
+        Ok(ctx)
+    }
+}
+
+/// Load a JSON prefab library and merge it into the World's PrefabLibrary resource,
+/// replacing any existing prefabs with the same names
+pub fn load_prefabs<'a, 'b, P: Into<PathBuf>>(
+    path: P,
+) -> impl FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |mut ctx| {
+        let (world, _) = &mut ctx;
+        let path = path.into();
+
+        tracing::debug!(?path, "Loading prefabs");
+        let prefabs = PrefabLibrary::from_json(&std::fs::read_to_string(&path)?)?;
 
-        println!("Loaded file, spawning into world...");
-        world.spawn(FileStringBundle::new(path, file));
+        tracing::debug!(?path, "Loaded prefabs, merging into library");
+        let mut library = world.remove_resource::<PrefabLibrary>().unwrap_or_default();
+        library.extend(prefabs);
+        world.insert_resource(library);
 
         Ok(ctx)
     }
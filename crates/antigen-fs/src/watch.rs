@@ -0,0 +1,324 @@
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use antigen_core::{
+    Changed, ChangedTrait, MessageContext, MessageResult, Resources, Usage, WorldChannel,
+    WorldMessage,
+};
+use hecs::World;
+
+use crate::{FileBytesComponent, FilePathComponent, FileStringComponent};
+
+pub enum FileModified {}
+
+/// Modification time of a loaded file as of its last read
+pub type FileModifiedComponent = Usage<FileModified, SystemTime>;
+
+type FileReloadFactory = Arc<dyn Fn(PathBuf) -> WorldMessage + Send + Sync>;
+
+struct FileSubscription {
+    world: &'static str,
+    factory: FileReloadFactory,
+}
+
+/// Resource tracking how often loaded files are polled for changes,
+/// and which worlds to notify when they are reloaded
+pub struct FileWatcher {
+    interval: Duration,
+    last_poll: Option<Instant>,
+    subscriptions: BTreeMap<PathBuf, Vec<FileSubscription>>,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        FileWatcher::new(Duration::from_millis(500))
+    }
+}
+
+impl FileWatcher {
+    pub fn new(interval: Duration) -> Self {
+        FileWatcher {
+            interval,
+            last_poll: None,
+            subscriptions: Default::default(),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Send a message built by `f` to world U each time the file at `path` is reloaded
+    pub fn subscribe<U, F, M>(&mut self, path: PathBuf, f: F)
+    where
+        U: 'static,
+        F: Fn(PathBuf) -> M + Send + Sync + 'static,
+        M: for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> + Send + 'static,
+    {
+        self.subscriptions
+            .entry(path)
+            .or_default()
+            .push(FileSubscription {
+                world: std::any::type_name::<U>(),
+                factory: Arc::new(move |path| WorldMessage::to::<U, _>(f(path))),
+            });
+    }
+
+    /// Returns true if at least `interval` has passed since the last poll, recording a poll if so
+    fn should_poll(&mut self, now: Instant) -> bool {
+        match self.last_poll {
+            Some(last_poll) if now.saturating_duration_since(last_poll) < self.interval => false,
+            _ => {
+                self.last_poll = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// Subscribe world U to reloads of the file at `path`,
+/// inserting a default [`FileWatcher`] resource if none exists
+///
+/// The message built by `f` is sent to U after the reloaded contents have been written
+/// to the file's entity, so it can re-run anything derived from them.
+pub fn subscribe_file_reload<'a, 'b, U, P, F, M>(
+    path: P,
+    f: F,
+) -> impl FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b>
+where
+    U: 'static,
+    P: Into<PathBuf>,
+    F: Fn(PathBuf) -> M + Send + Sync + 'static,
+    M: for<'c, 'd> FnOnce(MessageContext<'c, 'd>) -> MessageResult<'c, 'd> + Send + 'static,
+{
+    move |mut ctx| {
+        let (world, _) = &mut ctx;
+        let path = path.into();
+
+        tracing::debug!(
+            ?path,
+            world = std::any::type_name::<U>(),
+            "Subscribing to file reloads"
+        );
+        if !world.contains_resource::<FileWatcher>() {
+            world.insert_resource(FileWatcher::default());
+        }
+        world
+            .get_resource_mut::<FileWatcher>()
+            .unwrap()
+            .subscribe::<U, _, _>(path, f);
+
+        Ok(ctx)
+    }
+}
+
+/// Poll the modification times of loaded files, re-reading any that have changed on disk
+///
+/// Reloaded contents are marked as changed,
+/// and a notification is sent to each world subscribed to the file.
+/// Does nothing if there is no [`FileWatcher`] resource, or its poll interval has not elapsed.
+pub fn file_watch_system(world: &mut World, channel: &WorldChannel) {
+    match world.get_resource_mut::<FileWatcher>() {
+        Ok(mut watcher) => {
+            if !watcher.should_poll(Instant::now()) {
+                return;
+            }
+        }
+        Err(_) => return,
+    }
+
+    let mut reloaded = vec![];
+    for (_, (path, modified, string, bytes)) in world.query_mut::<(
+        &FilePathComponent,
+        &mut FileModifiedComponent,
+        Option<&mut Changed<FileStringComponent>>,
+        Option<&mut Changed<FileBytesComponent>>,
+    )>() {
+        let path: &PathBuf = path;
+        let current = match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+            Ok(current) => current,
+            Err(e) => {
+                tracing::warn!(?path, %e, "Failed to read file modification time");
+                continue;
+            }
+        };
+
+        // Any difference counts, as restoring an older version moves the time backwards
+        if current == **modified {
+            continue;
+        }
+
+        tracing::debug!(?path, "File changed on disk, reloading");
+        if let Some(string) = string {
+            match std::fs::read_to_string(path) {
+                Ok(s) => {
+                    ***string = s;
+                    string.mark_changed();
+                }
+                Err(e) => {
+                    tracing::warn!(?path, %e, "Failed to reload file");
+                    continue;
+                }
+            }
+        }
+
+        if let Some(bytes) = bytes {
+            match std::fs::read(path) {
+                Ok(b) => {
+                    ***bytes = b;
+                    bytes.mark_changed();
+                }
+                Err(e) => {
+                    tracing::warn!(?path, %e, "Failed to reload file");
+                    continue;
+                }
+            }
+        }
+
+        **modified = current;
+        if !reloaded.contains(path) {
+            reloaded.push(path.clone());
+        }
+    }
+
+    let watcher = world.get_resource::<FileWatcher>().unwrap();
+    for path in reloaded {
+        for subscription in watcher.subscriptions.get(&path).into_iter().flatten() {
+            tracing::debug!(
+                ?path,
+                world = subscription.world,
+                "Sending file reload notification"
+            );
+            if let Err(e) = channel.send((subscription.factory)(path.clone())) {
+                tracing::warn!(
+                    ?path,
+                    world = subscription.world,
+                    %e,
+                    "Failed to send file reload notification"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_file_bytes, load_file_string};
+    use antigen_core::{receive_messages, ChangeTick, WorldExchange};
+
+    enum Files {}
+    enum Game {}
+
+    /// Resource recording the path of the last reload notification
+    struct Reloaded(PathBuf);
+
+    #[test]
+    fn test_file_watcher_interval() {
+        let start = Instant::now();
+        let mut watcher = FileWatcher::new(Duration::from_millis(100));
+        assert!(watcher.should_poll(start));
+        assert!(!watcher.should_poll(start + Duration::from_millis(50)));
+        assert!(watcher.should_poll(start + Duration::from_millis(100)));
+        assert!(!watcher.should_poll(start + Duration::from_millis(150)));
+    }
+
+    #[test]
+    fn test_file_watch_system() {
+        let mut exchange = WorldExchange::default();
+        let files = exchange.create_channel::<Files>();
+        let game = exchange.create_channel::<Game>();
+        let _exchange = exchange.spawn();
+
+        let path = std::env::temp_dir().join(format!(
+            "antigen-fs-test-file-watch-system-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, "before").unwrap();
+
+        let mut files_world = World::new();
+        let mut game_world = World::new();
+
+        load_file_string(path.clone())((&mut files_world, &files)).unwrap();
+        load_file_bytes(path.clone())((&mut files_world, &files)).unwrap();
+        subscribe_file_reload::<Game, _, _, _>(path.clone(), |path| {
+            move |mut ctx: MessageContext| {
+                let (world, _) = &mut ctx;
+                world.insert_resource(Reloaded(path));
+                Ok(ctx)
+            }
+        })((&mut files_world, &files))
+        .unwrap();
+        files_world
+            .get_resource_mut::<FileWatcher>()
+            .unwrap()
+            .set_interval(Duration::ZERO);
+
+        // Unchanged files are left alone
+        file_watch_system(&mut files_world, &files);
+        for (_, string) in files_world.query_mut::<&Changed<FileStringComponent>>() {
+            assert_eq!(string.changed_tick(), ChangeTick::default());
+        }
+
+        let write = |contents: &str, modified: SystemTime| {
+            std::fs::write(&path, contents).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+
+        let modified = SystemTime::now() + Duration::from_secs(60);
+        write("after", modified);
+        file_watch_system(&mut files_world, &files);
+
+        let (_, (string, file_modified)) = files_world
+            .query_mut::<(&Changed<FileStringComponent>, &FileModifiedComponent)>()
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(***string, "after");
+        assert!(string.changed_tick() > ChangeTick::default());
+        assert_eq!(**file_modified, modified);
+
+        let (_, (bytes, file_modified)) = files_world
+            .query_mut::<(&Changed<FileBytesComponent>, &FileModifiedComponent)>()
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(***bytes, b"after");
+        assert!(bytes.changed_tick() > ChangeTick::default());
+        assert_eq!(**file_modified, modified);
+
+        receive_messages(&mut game_world, &game).unwrap();
+        assert_eq!(game_world.get_resource::<Reloaded>().unwrap().0, path);
+        game_world.remove_resource::<Reloaded>().unwrap();
+
+        // Files whose modification time moves backwards are reloaded too
+        let modified = modified - Duration::from_secs(3600);
+        write("restored", modified);
+        file_watch_system(&mut files_world, &files);
+        std::fs::remove_file(&path).unwrap();
+
+        let (_, (string, file_modified)) = files_world
+            .query_mut::<(&Changed<FileStringComponent>, &FileModifiedComponent)>()
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(***string, "restored");
+        assert_eq!(**file_modified, modified);
+
+        receive_messages(&mut game_world, &game).unwrap();
+        assert_eq!(game_world.get_resource::<Reloaded>().unwrap().0, path);
+    }
+}
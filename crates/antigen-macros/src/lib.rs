@@ -0,0 +1,258 @@
+//! Derive macros for `antigen-core` traits
+//!
+//! Generated code refers to items via the `::antigen_core` path,
+//! so `antigen-core` must be a dependency of the deriving crate.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, Index, Member, Result, Type,
+};
+
+/// Largest tuple hecs implements `DynamicBundle` for
+const MAX_TUPLE_LEN: usize = 15;
+
+/// Derive `Construct` for a struct
+///
+/// Single-field structs can be constructed from their field,
+/// or from anything their field can be constructed from, mirroring `Usage` and `Changed`.
+/// Structs with several fields are constructed from a tuple of their fields in declaration order.
+#[proc_macro_derive(Construct)]
+pub fn derive_construct(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    construct(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derive `With` for a struct
+///
+/// Single-field structs can replace their field,
+/// or forward to its own `With` implementation, mirroring `Usage` and `Changed`.
+/// Structs with several fields can replace each field,
+/// indexed by its position so that fields of the same type remain distinct.
+#[proc_macro_derive(With)]
+pub fn derive_with(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    with(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+/// Derive `ClonedBundle` for a struct of component references, such as a `hecs::Query` struct
+///
+/// Fields beyond the hecs tuple limit are chained into further bundles,
+/// so there is no limit on the number of fields.
+#[proc_macro_derive(ClonedBundle)]
+pub fn derive_cloned_bundle(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    cloned_bundle(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn struct_fields<'a>(input: &'a DeriveInput, derive: &str) -> Result<&'a Fields> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            format!("{} can only be derived for structs", derive),
+        )),
+    }
+}
+
+fn members(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        })
+        .collect()
+}
+
+/// Peano-encoded type-level index
+fn peano(index: usize) -> TokenStream2 {
+    (0..index).fold(
+        quote!(::antigen_core::peano::Z),
+        |acc, _| quote!(::antigen_core::peano::S<#acc>),
+    )
+}
+
+fn construct(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = struct_fields(&input, "Construct")?;
+    let members = members(fields);
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    if let ([ty], [member]) = (types.as_slice(), members.as_slice()) {
+        let mut generics = input.generics.clone();
+        generics.params.push(parse_quote!(__T));
+        generics.params.push(parse_quote!(__I));
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: ::antigen_core::Construct<__T, __I>));
+        let (nested_impl_generics, _, nested_where_clause) = generics.split_for_impl();
+
+        return Ok(quote! {
+            impl #impl_generics ::antigen_core::Construct<#ty, ::antigen_core::peano::Z>
+                for #name #ty_generics #where_clause
+            {
+                fn construct(t: #ty) -> Self {
+                    Self { #member: t }
+                }
+            }
+
+            impl #nested_impl_generics
+                ::antigen_core::Construct<__T, ::antigen_core::peano::S<__I>>
+                for #name #ty_generics #nested_where_clause
+            {
+                fn construct(t: __T) -> Self {
+                    Self {
+                        #member: <#ty as ::antigen_core::Construct<__T, __I>>::construct(t),
+                    }
+                }
+            }
+        });
+    }
+
+    let vars = (0..types.len())
+        .map(|i| format_ident!("t{}", i))
+        .collect::<Vec<_>>();
+
+    Ok(quote! {
+        impl #impl_generics ::antigen_core::Construct<(#(#types,)*), ::antigen_core::peano::Z>
+            for #name #ty_generics #where_clause
+        {
+            fn construct((#(#vars,)*): (#(#types,)*)) -> Self {
+                Self { #(#members: #vars),* }
+            }
+        }
+    })
+}
+
+fn with(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = struct_fields(&input, "With")?;
+    let members = members(fields);
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    if let ([ty], [member]) = (types.as_slice(), members.as_slice()) {
+        let mut generics = input.generics.clone();
+        generics.params.push(parse_quote!(__T));
+        generics.params.push(parse_quote!(__I));
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: ::antigen_core::With<__T, __I>));
+        let (nested_impl_generics, _, nested_where_clause) = generics.split_for_impl();
+
+        return Ok(quote! {
+            impl #impl_generics ::antigen_core::With<#ty, ::antigen_core::peano::Z>
+                for #name #ty_generics #where_clause
+            {
+                fn with(mut self, t: #ty) -> Self {
+                    self.#member = t;
+                    self
+                }
+            }
+
+            impl #nested_impl_generics
+                ::antigen_core::With<__T, ::antigen_core::peano::S<__I>>
+                for #name #ty_generics #nested_where_clause
+            {
+                fn with(self, t: __T) -> Self {
+                    Self {
+                        #member: <#ty as ::antigen_core::With<__T, __I>>::with(self.#member, t),
+                    }
+                }
+            }
+        });
+    }
+
+    let impls = types
+        .iter()
+        .zip(members.iter())
+        .enumerate()
+        .map(|(i, (ty, member))| {
+            let index = peano(i);
+            quote! {
+                impl #impl_generics ::antigen_core::With<#ty, #index>
+                    for #name #ty_generics #where_clause
+                {
+                    fn with(mut self, t: #ty) -> Self {
+                        self.#member = t;
+                        self
+                    }
+                }
+            }
+        });
+
+    Ok(quote!(#(#impls)*))
+}
+
+fn cloned_bundle(input: DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = struct_fields(&input, "ClonedBundle")?;
+    let members = members(fields);
+    let types = fields
+        .iter()
+        .map(|field| match &field.ty {
+            Type::Reference(reference) => Ok(&*reference.elem),
+            ty => Err(Error::new_spanned(
+                ty,
+                "ClonedBundle fields must be component references",
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for ty in &types {
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::core::clone::Clone + Send + Sync + 'static));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let values = types
+        .iter()
+        .zip(members.iter())
+        .map(|(ty, member)| quote!(<#ty as ::core::clone::Clone>::clone(&*self.#member)))
+        .collect::<Vec<_>>();
+
+    let bundle_type = chain(
+        types
+            .chunks(MAX_TUPLE_LEN)
+            .map(|chunk| quote!((#(#chunk,)*))),
+        |a, b| quote!(::antigen_core::ChainedBundle<#a, #b>),
+    );
+    let bundle = chain(
+        values
+            .chunks(MAX_TUPLE_LEN)
+            .map(|chunk| quote!((#(#chunk,)*))),
+        |a, b| quote!(::antigen_core::ChainedBundle(#a, #b)),
+    );
+
+    Ok(quote! {
+        impl #impl_generics ::antigen_core::ClonedBundle for #name #ty_generics #where_clause {
+            type Bundle = #bundle_type;
+
+            fn cloned_bundle(&self) -> Self::Bundle {
+                #bundle
+            }
+        }
+    })
+}
+
+/// Right-fold tuple chunks into nested chained bundles
+fn chain(
+    chunks: impl DoubleEndedIterator<Item = TokenStream2>,
+    f: impl Fn(TokenStream2, TokenStream2) -> TokenStream2,
+) -> TokenStream2 {
+    let mut chunks = chunks.rev();
+    let last = chunks.next().unwrap_or_else(|| quote!(()));
+    chunks.fold(last, |acc, chunk| f(chunk, acc))
+}
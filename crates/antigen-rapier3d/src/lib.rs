@@ -3,7 +3,8 @@ use parking_lot::{RwLock, RwLockReadGuard};
 pub use rapier3d;
 
 use antigen_core::{
-    Construct, Indirect, LazyComponent, PositionComponent, RotationComponent, Usage,
+    Construct, FixedTime, Indirect, LazyComponent, PositionComponent, Resources, RotationComponent,
+    Usage,
 };
 use hecs::{EntityBuilder, Query, World};
 use rapier3d::{
@@ -65,7 +66,7 @@ impl EventCollector {
     }
 }
 
-// Physics backend
+// Physics backend, held as resources
 #[derive(Query)]
 pub struct PhysicsQuery<'a> {
     pub gravity: &'a GravityComponent,
@@ -101,43 +102,56 @@ pub fn physics_backend_builder(gravity: nalgebra::Vector3<f32>) -> EntityBuilder
     builder
 }
 
+/// Advance the simulation by one step of [`IntegrationParameters::dt`]
+///
+/// If a [`FixedTime`] resource is present, `dt` is kept in sync with its timestep,
+/// and this system should be run via [`antigen_core::run_fixed_steps`].
 pub fn step_physics_system(world: &mut World) {
-    for (
-        _,
-        PhysicsQuery {
-            gravity,
-            integration_parameters,
-            physics_pipeline,
-            island_manager,
-            broad_phase,
-            narrow_phase,
-            rigid_body_set,
-            collider_set,
-            joint_set,
-            ccd_solver,
-            event_collector,
-        },
-    ) in world.query_mut::<PhysicsQuery>().into_iter()
-    {
-        physics_pipeline.step(
-            &gravity,
-            integration_parameters,
-            island_manager,
-            broad_phase,
-            narrow_phase,
-            rigid_body_set,
-            collider_set,
-            joint_set,
-            ccd_solver,
-            &(),
-            event_collector,
-        );
+    if let Ok(fixed_time) = world.get_resource::<FixedTime>() {
+        if let Ok(mut integration_parameters) = world.get_resource_mut::<IntegrationParameters>() {
+            integration_parameters.dt = fixed_time.timestep_seconds();
+        }
     }
+
+    let PhysicsQuery {
+        gravity,
+        integration_parameters,
+        physics_pipeline,
+        island_manager,
+        broad_phase,
+        narrow_phase,
+        rigid_body_set,
+        collider_set,
+        joint_set,
+        ccd_solver,
+        event_collector,
+    } = match world.query_resources_mut::<PhysicsQuery>() {
+        Ok(physics) => physics,
+        Err(e) => {
+            tracing::warn!("Skipping physics step: {}", e);
+            return;
+        }
+    };
+
+    physics_pipeline.step(
+        gravity,
+        integration_parameters,
+        island_manager,
+        broad_phase,
+        narrow_phase,
+        rigid_body_set,
+        collider_set,
+        joint_set,
+        ccd_solver,
+        &(),
+        event_collector,
+    );
 }
 
 pub fn clear_physics_event_collector_system(world: &mut World) {
-    for (_, event_collector) in world.query_mut::<&EventCollector>().into_iter() {
-        event_collector.clear()
+    match world.get_resource::<EventCollector>() {
+        Ok(event_collector) => event_collector.clear(),
+        Err(e) => tracing::warn!("Skipping physics event collector clear: {}", e),
     }
 }
 
@@ -147,8 +161,20 @@ pub enum ColliderParent {}
 pub type ColliderParentComponent<'a> = Usage<ColliderParent, Indirect<&'a RigidBodyComponent>>;
 
 pub fn insert_colliders_system(world: &mut World) {
-    let mut query = world.query::<(&mut ColliderSet, &mut RigidBodySet)>();
-    let (_, (collider_set, rigid_body_set)) = query.into_iter().next().unwrap();
+    let mut collider_set = match world.get_resource_mut::<ColliderSet>() {
+        Ok(collider_set) => collider_set,
+        Err(e) => {
+            tracing::warn!("Skipping collider insertion: {}", e);
+            return;
+        }
+    };
+    let mut rigid_body_set = match world.get_resource_mut::<RigidBodySet>() {
+        Ok(rigid_body_set) => rigid_body_set,
+        Err(e) => {
+            tracing::warn!("Skipping collider insertion: {}", e);
+            return;
+        }
+    };
 
     for (_, (collider_component, position, rotation, rigid_body, collider_parent)) in world
         .query::<(
@@ -192,12 +218,16 @@ pub fn insert_colliders_system(world: &mut World) {
                         } else {
                             panic!("No collider component")
                         };
-                        let handle = collider_set.insert_with_parent(c, rb, rigid_body_set);
+                        let handle = collider_set.insert_with_parent(c, rb, &mut rigid_body_set);
                         *collider_component = ColliderComponent::Ready(handle);
                     }
                 }
                 (None, Some(parent)) => {
-                    let mut query = parent.get(world);
+                    // Colliders with a despawned parent are left pending
+                    let mut query = match parent.get(world) {
+                        Ok(query) => query,
+                        Err(_) => continue,
+                    };
                     let parent = query.get().unwrap();
                     if let LazyComponent::Ready(parent) = **parent {
                         let c = if let LazyComponent::Pending(c) = collider_component.take() {
@@ -205,7 +235,8 @@ pub fn insert_colliders_system(world: &mut World) {
                         } else {
                             panic!("No collider component")
                         };
-                        let handle = collider_set.insert_with_parent(c, parent, rigid_body_set);
+                        let handle =
+                            collider_set.insert_with_parent(c, parent, &mut rigid_body_set);
                         *collider_component = ColliderComponent::Ready(handle);
                     }
                 }
@@ -218,8 +249,13 @@ pub enum RigidBodyTag {}
 pub type RigidBodyComponent = Usage<RigidBodyTag, LazyComponent<RigidBodyHandle, RigidBody>>;
 
 pub fn insert_rigid_bodies_system(world: &mut World) {
-    let mut query = world.query::<&mut RigidBodySet>();
-    let (_, rigid_body_set) = query.into_iter().next().unwrap();
+    let mut rigid_body_set = match world.get_resource_mut::<RigidBodySet>() {
+        Ok(rigid_body_set) => rigid_body_set,
+        Err(e) => {
+            tracing::warn!("Skipping rigid body insertion: {}", e);
+            return;
+        }
+    };
 
     for (_, (rigid_body, position, rotation, linear_velocity, angular_velocity)) in world
         .query::<(
@@ -274,8 +310,13 @@ pub fn insert_rigid_bodies_system(world: &mut World) {
 }
 
 pub fn write_rigid_body_isometries_system(world: &mut World) {
-    let mut query = world.query::<&mut RigidBodySet>();
-    let (_, rigid_body_set) = query.into_iter().next().unwrap();
+    let mut rigid_body_set = match world.get_resource_mut::<RigidBodySet>() {
+        Ok(rigid_body_set) => rigid_body_set,
+        Err(e) => {
+            tracing::warn!("Skipping rigid body isometry write: {}", e);
+            return;
+        }
+    };
 
     for (_, (rigid_body, position, rotation, linear_velocity, angular_velocity)) in world
         .query::<(
@@ -332,8 +373,13 @@ pub fn write_rigid_body_isometries_system(world: &mut World) {
 }
 
 pub fn read_back_rigid_body_isometries_system(world: &mut World) {
-    let mut query = world.query::<&mut RigidBodySet>();
-    let (_, rigid_body_set) = query.into_iter().next().unwrap();
+    let rigid_body_set = match world.get_resource::<RigidBodySet>() {
+        Ok(rigid_body_set) => rigid_body_set,
+        Err(e) => {
+            tracing::warn!("Skipping rigid body isometry read back: {}", e);
+            return;
+        }
+    };
 
     for (_, (rigid_body, position, rotation, linear_velocity, angular_velocity)) in world
         .query::<(
@@ -2,7 +2,7 @@ pub use shambler;
 
 use std::path::PathBuf;
 
-use antigen_core::{Construct, MessageContext, MessageResult, Usage};
+use antigen_core::{Commands, Construct, MessageContext, MessageResult, Usage};
 use antigen_fs::{FilePathComponent, FileStringQuery};
 use shambler::GeoMap;
 
@@ -23,29 +23,20 @@ pub fn parse_map_file_string<'a, 'b, P: Into<PathBuf>>(
         let (world, _) = &mut ctx;
 
         let map_path = path.into();
-        println!(
-            "Thread {} Looking for file string entities with path {:?}..",
-            std::thread::current().name().unwrap(),
-            map_path
-        );
-
-        let components = world
-            .query_mut::<FileStringQuery>()
-            .into_iter()
-            .filter(|(_, FileStringQuery { path, .. })| ***path == *map_path)
-            .map(|(entity, FileStringQuery { string, .. })| {
-                println!("Parsing map file for entity {:?}", entity);
-                let map = string.parse::<shambler::shalrath::repr::Map>().unwrap();
-                let map = GeoMap::from(map);
-                (entity, MapFileComponent::construct(map))
-            })
-            .collect::<Vec<_>>();
-
-        for (entity, map) in components {
-            world
-                .insert(entity, (map,))
-                .expect("Failed to add map to entity");
+        tracing::debug!(path = ?map_path, "Looking for file string entities");
+
+        let commands = Commands::default();
+        for (entity, FileStringQuery { path, string }) in world.query_mut::<FileStringQuery>() {
+            if ***path != *map_path {
+                continue;
+            }
+
+            tracing::debug!(?entity, "Parsing map file");
+            let map = string.parse::<shambler::shalrath::repr::Map>().unwrap();
+            let map = GeoMap::from(map);
+            commands.insert(entity, (MapFileComponent::construct(map),));
         }
+        commands.apply(world);
 
         Ok(ctx)
     }
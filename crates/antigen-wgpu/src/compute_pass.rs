@@ -1,4 +1,7 @@
-use antigen_core::{AsUsage, Construct, Indirect, Usage};
+use antigen_core::{
+    validate_indirect_system, AsUsage, Construct, Indirect, IndirectError, IndirectReferences,
+    Usage,
+};
 use hecs::{Entity, EntityBuilder, World};
 use wgpu::{BufferAddress, ComputePassDescriptor, DynamicOffset};
 
@@ -22,6 +25,12 @@ pub struct ComputePassDispatchIndirectComponent {
     offset: BufferAddress,
 }
 
+impl IndirectReferences for ComputePassDispatchIndirectComponent {
+    fn check_indirect(&self, world: &World, include_weak: bool, errors: &mut Vec<IndirectError>) {
+        self.buffer.check_indirect(world, include_weak, errors)
+    }
+}
+
 pub enum ComputePassBundle {}
 
 fn compute_pass_bundle_impl(
@@ -130,6 +139,14 @@ pub struct ComputePassQuery<'a> {
     encoder: &'a mut CommandEncoderComponent,
 }
 
+/// Warn about dangling references held by compute pass components
+pub fn validate_compute_passes_system(world: &World) {
+    validate_indirect_system::<ComputePassPipelineComponent>(world);
+    validate_indirect_system::<ComputePassBindGroupsComponent>(world);
+    validate_indirect_system::<ComputePassPushConstantsComponent>(world);
+    validate_indirect_system::<ComputePassDispatchIndirectComponent>(world);
+}
+
 pub fn dispatch_compute_passes_system(world: &mut World) -> Option<()> {
     let mut query = world.query::<ComputePassQuery>();
 
@@ -149,17 +166,35 @@ pub fn dispatch_compute_passes_system(world: &mut World) -> Option<()> {
         },
     ) in components.into_iter()
     {
+        // Skip passes with unresolvable references, including weak ones,
+        // rather than panicking mid-pass
+        let mut errors = vec![];
+        pipeline.validate_indirect_access(world, &mut errors);
+        bind_groups.validate_indirect_access(world, &mut errors);
+        push_constants.validate_indirect_access(world, &mut errors);
+        dispatch
+            .right()
+            .validate_indirect_access(world, &mut errors);
+
+        if !errors.is_empty() {
+            for error in errors {
+                tracing::warn!(?entity, "Skipping compute pass: {}", error);
+            }
+            continue;
+        }
+
         let encoder = encoder.get_mut()?;
 
         // Collect pipeline
-        let mut query = pipeline.get(world);
+        // References are known to resolve from here on
+        let mut query = pipeline.get(world).unwrap();
         let pipeline = query.get()?;
         let pipeline = pipeline.get()?;
 
         // Collect bind group queries
         let (mut bind_group_queries, bind_group_offsets): (Vec<_>, Vec<_>) = bind_groups
             .iter()
-            .map(|(bind_group, offsets)| (bind_group.get(world), offsets))
+            .map(|(bind_group, offsets)| (bind_group.get(world).unwrap(), offsets))
             .unzip();
 
         let bind_groups = bind_group_queries
@@ -174,7 +209,7 @@ pub fn dispatch_compute_passes_system(world: &mut World) -> Option<()> {
         let mut push_constant_queries = if let Some(push_constants) = push_constants {
             let push_constant_queries = push_constants
                 .iter()
-                .map(|push_constant| push_constant.get(world))
+                .map(|push_constant| push_constant.get(world).unwrap())
                 .collect::<Vec<_>>();
 
             push_constant_queries
@@ -188,8 +223,8 @@ pub fn dispatch_compute_passes_system(world: &mut World) -> Option<()> {
             .collect::<Vec<_>>();
 
         let dispatch_ind = dispatch.right();
-        let mut dispatch_ind_query =
-            dispatch_ind.map(|dispatch_ind| (dispatch_ind.buffer.get(world), dispatch_ind.offset));
+        let mut dispatch_ind_query = dispatch_ind
+            .map(|dispatch_ind| (dispatch_ind.buffer.get(world).unwrap(), dispatch_ind.offset));
         let dispatch_ind_buffer = dispatch_ind_query
             .as_mut()
             .map(|(query, offset)| (query.get().unwrap(), *offset));
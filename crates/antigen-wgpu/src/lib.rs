@@ -5,6 +5,8 @@ mod compute_pass;
 mod render_pass;
 mod systems;
 
+pub mod schedule;
+
 use std::path::PathBuf;
 
 use antigen_core::{MessageContext, MessageResult, WorldChannel};
@@ -79,7 +81,6 @@ pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl Ev
         match event {
             Event::MainEventsCleared => {
                 //staging_belt_flush_thread_local(&world.read(), &mut staging_belt_manager);
-                reset_surface_config_changed_system(world);
             }
             Event::RedrawEventsCleared => {
                 submit_and_present_schedule(world);
@@ -97,23 +98,19 @@ pub fn spawn_shader_from_file_string<'a, 'b, P: Into<PathBuf>>(
         let (world, _) = &mut ctx;
 
         let map_path = path.into();
-        println!(
-            "Thread {} Looking for file string entities with path {:?}..",
-            std::thread::current().name().unwrap(),
-            map_path
-        );
+        tracing::debug!(path = ?map_path, "Looking for file string entities");
 
         let components = world
             .query_mut::<FileStringQuery>()
             .into_iter()
             .filter(|(_, FileStringQuery { path, .. })| ***path == *map_path)
             .map(|(entity, FileStringQuery { string, .. })| {
-                println!("Creating shader for entity {:?}", entity);
+                tracing::debug!(?entity, "Creating shader");
                 (
                     entity,
                     ShaderModuleBundle::new(ShaderModuleDescriptor {
                         label: None,
-                        source: ShaderSource::Wgsl(std::borrow::Cow::Owned((**string).clone())),
+                        source: ShaderSource::Wgsl(std::borrow::Cow::Owned((***string).clone())),
                     }),
                 )
             })
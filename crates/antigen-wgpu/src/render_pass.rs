@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-use antigen_core::{Construct, Indirect, Usage};
+use antigen_core::{validate_indirect_system, Construct, Indirect, IndirectReferences, Usage};
 use hecs::{Entity, EntityBuilder, World};
 use wgpu::{
     BufferAddress, Color, DynamicOffset, IndexFormat, Operations, RenderPassColorAttachment,
@@ -362,6 +362,20 @@ pub struct RenderPassQuery<'a> {
     encoder: &'a RenderPassEncoderComponent,
 }
 
+/// Warn about dangling references held by render pass components
+pub fn validate_render_passes_system(world: &World) {
+    validate_indirect_system::<RenderPassColorAttachmentsComponent>(world);
+    validate_indirect_system::<RenderPassDepthAttachmentComponent>(world);
+    validate_indirect_system::<RenderPassPipelineComponent>(world);
+    validate_indirect_system::<RenderPassVertexBuffersComponent>(world);
+    validate_indirect_system::<RenderPassIndexBufferComponent>(world);
+    validate_indirect_system::<RenderPassBindGroupsComponent>(world);
+    validate_indirect_system::<RenderPassPushConstantsComponent>(world);
+    validate_indirect_system::<RenderPassEncoderComponent>(world);
+    validate_indirect_system::<RenderPassDrawIndirectComponent>(world);
+    validate_indirect_system::<RenderPassDrawIndexedIndirectComponent>(world);
+}
+
 pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
     let mut query = world.query::<RenderPassQuery>();
     let mut components = query.into_iter().collect::<Vec<_>>();
@@ -422,7 +436,32 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
             .map(|query| query.get())
             .flatten();
 
-        let mut query = encoder.get(world);
+        // Skip passes with unresolvable references, including weak ones,
+        // rather than panicking mid-pass
+        let mut errors = vec![];
+        color_attachments.validate_indirect_access(world, &mut errors);
+        for (_, resolve_target, _) in color_attachments.iter() {
+            resolve_target.validate_indirect_access(world, &mut errors);
+        }
+        depth_attachment.validate_indirect_access(world, &mut errors);
+        pipeline.validate_indirect_access(world, &mut errors);
+        vertex_buffers.validate_indirect_access(world, &mut errors);
+        index_buffer.validate_indirect_access(world, &mut errors);
+        bind_groups.validate_indirect_access(world, &mut errors);
+        push_constants.validate_indirect_access(world, &mut errors);
+        draw_indirect.validate_indirect_access(world, &mut errors);
+        draw_indexed_indirect.validate_indirect_access(world, &mut errors);
+        encoder.validate_indirect_access(world, &mut errors);
+
+        if !errors.is_empty() {
+            for error in errors {
+                tracing::warn!(?entity, "Skipping render pass: {}", error);
+            }
+            continue;
+        }
+
+        // References are known to resolve from here on
+        let mut query = encoder.get(world).unwrap();
         let encoder = query.get().unwrap().get_mut().unwrap();
 
         // Collect label
@@ -434,10 +473,10 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
             .iter()
             .map(|(view, resolve_target, ops)| {
                 (
-                    view.get(world),
+                    view.get(world).unwrap(),
                     resolve_target
                         .as_ref()
-                        .map(|resolve_target| resolve_target.get(world)),
+                        .map(|resolve_target| resolve_target.get(world).unwrap()),
                     ops,
                 )
             })
@@ -467,9 +506,12 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
             .collect::<Vec<_>>();
 
         // Collect depth stencil attachment
-        let mut depth_stencil_query = depth_attachment
-            .as_ref()
-            .map(|(view, depth_ops, stencil_ops)| (view.get(world), depth_ops, stencil_ops));
+        let mut depth_stencil_query =
+            depth_attachment
+                .as_ref()
+                .map(|(view, depth_ops, stencil_ops)| {
+                    (view.get(world).unwrap(), depth_ops, stencil_ops)
+                });
 
         let depth_stencil = depth_stencil_query
             .as_mut()
@@ -489,14 +531,14 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
         });
 
         // Collect pipeline
-        let mut query = pipeline.get(world);
+        let mut query = pipeline.get(world).unwrap();
         let pipeline = query.get()?;
         let pipeline = pipeline.get()?;
 
         // Collect vertex buffer queries
         let mut vertex_buffer_queries = vertex_buffers
             .iter()
-            .map(|(vertex_buffer, range)| (vertex_buffer.get(world), range))
+            .map(|(vertex_buffer, range)| (vertex_buffer.get(world).unwrap(), range))
             .collect::<Vec<_>>();
 
         let vertex_buffer_locks = vertex_buffer_queries
@@ -515,7 +557,7 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
         // Collect index buffer query
         let mut index_buffer_query = index_buffer
             .as_ref()
-            .map(|(index_buffer, range, format)| (index_buffer.get(world), range, format));
+            .map(|(index_buffer, range, format)| (index_buffer.get(world).unwrap(), range, format));
 
         let index_buffer_lock = index_buffer_query.as_mut().map(|(query, range, format)| {
             let bind_group = query.get().unwrap().read();
@@ -529,7 +571,7 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
         // Collect bind group queries
         let mut bind_group_queries = bind_groups
             .iter()
-            .map(|(bind_group, offsets)| (bind_group.get(world), offsets))
+            .map(|(bind_group, offsets)| (bind_group.get(world).unwrap(), offsets))
             .collect::<Vec<_>>();
 
         let bind_groups = bind_group_queries
@@ -544,7 +586,9 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
         let mut push_constant_queries = if let Some(push_constants) = push_constants {
             let push_constant_queries = push_constants
                 .iter()
-                .map(|(push_constant, shader_stages)| (push_constant.get(world), shader_stages))
+                .map(|(push_constant, shader_stages)| {
+                    (push_constant.get(world).unwrap(), shader_stages)
+                })
                 .collect::<Vec<_>>();
 
             push_constant_queries
@@ -560,7 +604,7 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
         // Collect draw indirect query
         let mut indirect_query = draw_indirect.map(|draw_indirect| {
             let (indirect_query, indirect_offset) = &**draw_indirect;
-            (indirect_query.get(world), *indirect_offset)
+            (indirect_query.get(world).unwrap(), *indirect_offset)
         });
 
         let draw_indirect_lock =
@@ -577,7 +621,7 @@ pub fn draw_render_passes_system(world: &mut World) -> Option<()> {
         // Collect draw indexed indirect query
         let mut indexed_indirect_query = draw_indexed_indirect.map(|draw_indexed_indirect| {
             let (indirect_query, indirect_offset) = &**draw_indexed_indirect;
-            (indirect_query.get(world), *indirect_offset)
+            (indirect_query.get(world).unwrap(), *indirect_offset)
         });
 
         let draw_indexed_indirect_lock =
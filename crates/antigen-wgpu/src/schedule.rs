@@ -0,0 +1,164 @@
+//! Schedulable descriptors for the shared wgpu systems,
+//! declaring the components each accesses.
+//!
+//! Descriptors are labelled but unordered, so that schedules can order them as required.
+//! Conflicts such as writing a buffer before it has been created will be reported when building.
+
+use std::ops::Deref;
+
+use antigen_core::{Changed, Indirect, System, Usage};
+
+use crate::{
+    buffer_write_slice_system, buffer_write_system, create_buffers_init_system,
+    create_buffers_system, create_command_encoders_system, create_samplers_system,
+    create_shader_modules_spirv_system, create_shader_modules_system, create_texture_views_system,
+    create_textures_system, texture_write_slice_system, texture_write_system, BufferComponent,
+    BufferDescriptorComponent, BufferInitDescriptorComponent, BufferWriteComponent,
+    CommandEncoderComponent, CommandEncoderDescriptorComponent, DeviceComponent, QueueComponent,
+    SamplerComponent, SamplerDescriptorComponent, ShaderModuleComponent,
+    ShaderModuleDescriptorComponent, ShaderModuleDescriptorSpirVComponent, TextureComponent,
+    TextureDescriptorComponent, TextureViewComponent, TextureViewDescriptorComponent,
+    TextureWriteComponent,
+};
+
+pub const CREATE_SHADER_MODULES: &str = "create_shader_modules";
+pub const CREATE_BUFFERS: &str = "create_buffers";
+pub const CREATE_TEXTURES: &str = "create_textures";
+pub const CREATE_TEXTURE_VIEWS: &str = "create_texture_views";
+pub const CREATE_SAMPLERS: &str = "create_samplers";
+pub const CREATE_COMMAND_ENCODERS: &str = "create_command_encoders";
+pub const BUFFER_WRITES: &str = "buffer_writes";
+pub const TEXTURE_WRITES: &str = "texture_writes";
+
+pub fn create_shader_modules() -> System {
+    System::shared(create_shader_modules_system)
+        .named(CREATE_SHADER_MODULES)
+        .label(CREATE_SHADER_MODULES)
+        .reads::<DeviceComponent>()
+        .reads::<ShaderModuleDescriptorComponent<'static>>()
+        .writes::<ShaderModuleComponent>()
+}
+
+pub fn create_shader_modules_spirv<T: Send + Sync + 'static>() -> System {
+    System::shared(create_shader_modules_spirv_system::<T>)
+        .label(CREATE_SHADER_MODULES)
+        .reads::<DeviceComponent>()
+        .reads::<Usage<T, ShaderModuleDescriptorSpirVComponent<'static>>>()
+        .writes::<Usage<T, ShaderModuleComponent>>()
+}
+
+pub fn create_buffers() -> System {
+    System::shared(create_buffers_system)
+        .named(CREATE_BUFFERS)
+        .label(CREATE_BUFFERS)
+        .reads::<DeviceComponent>()
+        .reads::<BufferDescriptorComponent<'static>>()
+        .writes::<BufferComponent>()
+}
+
+pub fn create_buffers_init() -> System {
+    System::shared(create_buffers_init_system)
+        .named("create_buffers_init")
+        .label(CREATE_BUFFERS)
+        .reads::<DeviceComponent>()
+        .reads::<BufferInitDescriptorComponent<'static>>()
+        .writes::<BufferComponent>()
+}
+
+pub fn create_textures() -> System {
+    System::shared(create_textures_system)
+        .named(CREATE_TEXTURES)
+        .label(CREATE_TEXTURES)
+        .reads::<DeviceComponent>()
+        .reads::<TextureDescriptorComponent<'static>>()
+        .writes::<TextureComponent>()
+}
+
+pub fn create_texture_views() -> System {
+    System::shared(create_texture_views_system)
+        .named(CREATE_TEXTURE_VIEWS)
+        .label(CREATE_TEXTURE_VIEWS)
+        .reads::<TextureComponent>()
+        .reads::<TextureViewDescriptorComponent<'static>>()
+        .writes::<TextureViewComponent>()
+}
+
+pub fn create_samplers() -> System {
+    System::shared(create_samplers_system)
+        .named(CREATE_SAMPLERS)
+        .label(CREATE_SAMPLERS)
+        .reads::<DeviceComponent>()
+        .reads::<SamplerDescriptorComponent<'static>>()
+        .writes::<SamplerComponent>()
+}
+
+pub fn create_command_encoders() -> System {
+    System::shared(create_command_encoders_system)
+        .named(CREATE_COMMAND_ENCODERS)
+        .label(CREATE_COMMAND_ENCODERS)
+        .reads::<DeviceComponent>()
+        .reads::<CommandEncoderDescriptorComponent>()
+        .writes::<CommandEncoderComponent>()
+}
+
+pub fn buffer_write<T: bytemuck::Pod + Send + Sync + 'static>() -> System {
+    System::shared(buffer_write_system::<T>)
+        .label(BUFFER_WRITES)
+        .reads::<QueueComponent>()
+        .reads::<BufferWriteComponent<T>>()
+        .reads::<Changed<T>>()
+        .reads::<Usage<BufferWriteComponent<T>, Indirect<&'static BufferComponent>>>()
+        .reads::<BufferComponent>()
+}
+
+pub fn buffer_write_slice<T, V>() -> System
+where
+    T: Deref<Target = [V]> + Send + Sync + 'static,
+    V: bytemuck::Pod + 'static,
+{
+    System::shared(buffer_write_slice_system::<T, V>)
+        .label(BUFFER_WRITES)
+        .reads::<QueueComponent>()
+        .reads::<BufferWriteComponent<T>>()
+        .reads::<Changed<T>>()
+        .reads::<Usage<BufferWriteComponent<T>, Indirect<&'static BufferComponent>>>()
+        .reads::<BufferComponent>()
+}
+
+pub fn texture_write<T: bytemuck::Pod + Send + Sync + 'static>() -> System {
+    System::shared(texture_write_system::<T>)
+        .label(TEXTURE_WRITES)
+        .reads::<QueueComponent>()
+        .reads::<TextureWriteComponent<T>>()
+        .reads::<Changed<T>>()
+        .reads::<Usage<
+            TextureWriteComponent<T>,
+            Indirect<(
+                &'static TextureDescriptorComponent<'static>,
+                &'static TextureComponent,
+            )>,
+        >>()
+        .reads::<TextureDescriptorComponent<'static>>()
+        .reads::<TextureComponent>()
+}
+
+pub fn texture_write_slice<T, V>() -> System
+where
+    T: Deref<Target = [V]> + Send + Sync + 'static,
+    V: bytemuck::Pod + 'static,
+{
+    System::shared(texture_write_slice_system::<T, V>)
+        .label(TEXTURE_WRITES)
+        .reads::<QueueComponent>()
+        .reads::<TextureWriteComponent<T>>()
+        .reads::<Changed<T>>()
+        .reads::<Usage<
+            TextureWriteComponent<T>,
+            Indirect<(
+                &'static TextureDescriptorComponent<'static>,
+                &'static TextureComponent,
+            )>,
+        >>()
+        .reads::<TextureDescriptorComponent<'static>>()
+        .reads::<TextureComponent>()
+}
@@ -12,24 +12,65 @@ use crate::{
     ShaderModuleDescriptorSpirVComponent, SurfaceConfigurationComponent, TextureComponent,
 };
 
-use antigen_core::{Changed, ChangedTrait, Indirect, LazyComponent, Usage};
+use antigen_core::{
+    defer_changes, observe_changes_or_warn, observe_entity_changes_or_warn, take_deferred_changes,
+    Changed, ChangedTrait, Indirect, LazyComponent, Resources, Usage,
+};
 use antigen_winit::{WindowComponent, WindowEntityMap, WindowEventComponent, WindowSizeComponent};
 
 use hecs::{Entity, World};
 
 use wgpu::{util::DeviceExt, Maintain};
 
+// Change observation markers
+enum ReconfigureSurfaces {}
+enum SurfaceTextureView {}
+enum SurfaceSize {}
+enum SurfaceTextureViewDrop {}
+enum CreateShaderModules {}
+enum CreateBuffers {}
+enum CreateBuffersInit {}
+enum CreateTextures {}
+enum CreateTextureViews {}
+enum CreateSamplers {}
+enum CreateCommandEncoders {}
+enum BufferWrite {}
+enum BufferWriteSlice {}
+enum TextureWrite {}
+enum TextureWriteSlice {}
+
 pub fn device_poll_system(maintain: &Maintain) -> impl FnMut(&mut World) {
     let maintain = *maintain;
-    move |world| {
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
-        device.poll(maintain);
+    move |world| match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device.poll(maintain),
+        Err(e) => tracing::warn!("Skipping device poll: {}", e),
     }
 }
 
 // Initialize pending surfaces that share an entity with a window
 pub fn create_window_surfaces_system(world: &mut World) {
+    let adapter = match world.get_resource::<AdapterComponent>() {
+        Ok(adapter) => adapter,
+        Err(e) => {
+            tracing::warn!("Skipping window surface creation: {}", e);
+            return;
+        }
+    };
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping window surface creation: {}", e);
+            return;
+        }
+    };
+    let instance = match world.get_resource::<InstanceComponent>() {
+        Ok(instance) => instance,
+        Err(e) => {
+            tracing::warn!("Skipping window surface creation: {}", e);
+            return;
+        }
+    };
+
     let mut query = world.query::<(
         &WindowComponent,
         &mut SurfaceConfigurationComponent,
@@ -39,16 +80,7 @@ pub fn create_window_surfaces_system(world: &mut World) {
         query.into_iter()
     {
         if let LazyComponent::Ready(window) = &*window_component {
-            let mut query = world.query::<&AdapterComponent>();
-            let (_, adapter) = query.into_iter().next().unwrap();
-
-            let mut query = world.query::<&DeviceComponent>();
-            let (_, device) = query.into_iter().next().unwrap();
-
             if surface_component.is_pending() {
-                let mut query = world.query::<&InstanceComponent>();
-                let (_, instance) = query.into_iter().next().unwrap();
-
                 let surface = unsafe { instance.create_surface(window) };
 
                 let window_size = window.inner_size();
@@ -56,10 +88,10 @@ pub fn create_window_surfaces_system(world: &mut World) {
                 surface_configuration_component.height = window_size.height;
 
                 surface_configuration_component.format = surface
-                    .get_preferred_format(adapter)
+                    .get_preferred_format(&adapter)
                     .expect("Surface is incompatible with adapter");
 
-                surface.configure(device, &*surface_configuration_component);
+                surface.configure(&device, &*surface_configuration_component);
 
                 surface_component.set_ready_with(surface);
             }
@@ -69,37 +101,39 @@ pub fn create_window_surfaces_system(world: &mut World) {
 
 // Initialize pending surfaces that share an entity with a window
 pub fn reconfigure_surfaces_system(world: &mut World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping surface reconfiguration: {}", e);
+            return;
+        }
+    };
+
+    let since =
+        match observe_changes_or_warn::<ReconfigureSurfaces>(world, "surface reconfiguration") {
+            Some(since) => since,
+            None => return,
+        };
+
     let mut query = world.query::<(&SurfaceConfigurationComponent, &SurfaceComponent)>();
     for (_, (surface_config, surface)) in query.into_iter() {
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
-
         let surface = if let LazyComponent::Ready(surface) = &*surface {
             surface
         } else {
             continue;
         };
 
-        if !surface_config.get_changed() {
+        if !surface_config.is_changed_since(since) {
             continue;
         }
 
         if surface_config.width > 0 && surface_config.height > 0 {
-            surface.configure(device, &surface_config);
-        }
-    }
-}
-
-pub fn reset_surface_config_changed_system(world: &mut World) {
-    let mut query = world.query::<&SurfaceConfigurationComponent>();
-    for (_, surface_config) in query.into_iter() {
-        if surface_config.get_changed() {
-            surface_config.set_changed(false);
+            surface.configure(&device, &surface_config);
         }
     }
 }
 
-// Fetch the current surface texture for a given surface, and set its dirty flag
+// Fetch the current surface texture for a given surface, and mark it changed
 pub fn surface_texture_query(world: &mut World, entity: Entity) {
     let mut query = world
         .query_one::<(&SurfaceComponent, &mut SurfaceTextureComponent)>(entity)
@@ -119,17 +153,26 @@ pub fn surface_texture_query(world: &mut World, entity: Entity) {
 
     if let Ok(current) = surface.get_current_texture() {
         **surface_texture = Some(current);
-        surface_texture.set_changed(true);
+        surface_texture.mark_changed();
     } else {
         if surface_texture.is_some() {
-            surface_texture.set_changed(true);
+            surface_texture.mark_changed();
             **surface_texture = None;
         }
     }
 }
 
-// Create a texture view for a surface texture, unsetting its dirty flag
+// Create a texture view for a surface texture if it has changed
 pub fn surface_texture_view_query(world: &mut World, entity: Entity) {
+    let since = match observe_entity_changes_or_warn::<SurfaceTextureView>(
+        world,
+        entity,
+        "surface texture view creation",
+    ) {
+        Some(since) => since,
+        None => return,
+    };
+
     let mut query = world
         .query_one::<(
             &SurfaceTextureComponent,
@@ -145,46 +188,56 @@ pub fn surface_texture_view_query(world: &mut World, entity: Entity) {
             return;
         };
 
-    if surface_texture_component.get_changed() {
+    if surface_texture_component.is_changed_since(since) {
         if let Some(surface_texture) = &**surface_texture_component {
             let view = surface_texture.texture.create_view(&texture_view_desc);
             texture_view.set_ready_with(view);
-            surface_texture_component.set_changed(false);
         } else {
             texture_view.set_dropped();
-            surface_texture_component.set_changed(false);
         }
     }
 }
 
 pub fn surface_size_system(world: &mut World) {
+    let since = match observe_changes_or_warn::<SurfaceSize>(world, "surface resize") {
+        Some(since) => since,
+        None => return,
+    };
+
     let mut query = world.query::<(&WindowSizeComponent, &mut SurfaceConfigurationComponent)>();
     for (_, (window_size, surface_configuration)) in query.into_iter() {
-        if window_size.get_changed() {
+        if window_size.is_changed_since(since) {
             surface_configuration.width = window_size.width;
             surface_configuration.height = window_size.height;
-            surface_configuration.set_changed(true);
+            surface_configuration.mark_changed();
         }
     }
 }
 
-// Present valid surface textures, setting their dirty flag
+// Present valid surface textures, marking them changed
 pub fn surface_texture_present_system(world: &mut World) {
     let mut query = world.query::<&mut SurfaceTextureComponent>();
     for (_, surface_texture_component) in query.into_iter() {
         if let Some(surface_texture) = surface_texture_component.take() {
             println!("Presenting surface texture {:?}", surface_texture);
             surface_texture.present();
-            surface_texture_component.set_changed(true);
+            surface_texture_component.mark_changed();
         }
     }
 }
 
-// Drop texture views whose surface textures have been invalidated, unsetting their dirty flag
+// Drop texture views whose surface textures have been invalidated
 pub fn surface_texture_view_drop_system(world: &mut World) {
+    let since =
+        match observe_changes_or_warn::<SurfaceTextureViewDrop>(world, "surface texture view drop")
+        {
+            Some(since) => since,
+            None => return,
+        };
+
     let mut query = world.query::<(&mut SurfaceTextureComponent, &mut TextureViewComponent)>();
     for (_, (surface_texture, texture_view)) in query.into_iter() {
-        if !surface_texture.get_changed() {
+        if !surface_texture.is_changed_since(since) {
             continue;
         }
 
@@ -197,26 +250,35 @@ pub fn surface_texture_view_drop_system(world: &mut World) {
             surface_texture
         );
         texture_view.set_dropped();
-        surface_texture.set_changed(false);
     }
 }
 
-/// Create pending usage-tagged shader modules, recreating them if a Changed flag is set
-pub fn create_shader_modules_system(world: &mut World) {
+/// Create pending usage-tagged shader modules, recreating them if their descriptor has changed
+pub fn create_shader_modules_system(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping shader module creation: {}", e);
+            return;
+        }
+    };
+
+    let since =
+        match observe_changes_or_warn::<CreateShaderModules>(world, "shader module creation") {
+            Some(since) => since,
+            None => return,
+        };
+
     println!("Create shader modules system");
     let mut query = world.query::<(&ShaderModuleDescriptorComponent, &mut ShaderModuleComponent)>();
 
     for (entity, (shader_module_desc, shader_module)) in query.into_iter() {
         println!("Checking shader for entity {:?}", entity);
-        if !shader_module.is_pending() && !shader_module_desc.get_changed() {
+        if !shader_module.is_pending() && !shader_module_desc.is_changed_since(since) {
             continue;
         }
 
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
         shader_module.set_ready_with(device.create_shader_module(&shader_module_desc));
-
-        shader_module_desc.set_changed(false);
         println!(
             "Created shader module with label {:?}",
             shader_module_desc.label
@@ -224,22 +286,34 @@ pub fn create_shader_modules_system(world: &mut World) {
     }
 }
 
-/// Create pending usage-tagged shader modules, recreating them if a Changed flag is set
-pub fn create_shader_modules_spirv_system<T: Send + Sync + 'static>(world: &mut World) {
+/// Create pending usage-tagged shader modules, recreating them if their descriptor has changed
+pub fn create_shader_modules_spirv_system<T: Send + Sync + 'static>(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping spir-v shader module creation: {}", e);
+            return;
+        }
+    };
+
+    let since = match observe_changes_or_warn::<(CreateShaderModules, T)>(
+        world,
+        "spir-v shader module creation",
+    ) {
+        Some(since) => since,
+        None => return,
+    };
+
     let mut query = world.query::<(
         &Usage<T, ShaderModuleDescriptorSpirVComponent>,
         &mut Usage<T, ShaderModuleComponent>,
     )>();
     for (_, (shader_module_desc, shader_module)) in query.into_iter() {
-        if !shader_module.is_pending() && !shader_module_desc.get_changed() {
+        if !shader_module.is_pending() && !shader_module_desc.is_changed_since(since) {
             continue;
         }
 
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
         shader_module.set_ready_with(unsafe { device.create_shader_module_spirv(&shader_module_desc) });
-
-        shader_module_desc.set_changed(false);
         println!(
             "Created {} spir-v shader module",
             std::any::type_name::<T>()
@@ -247,21 +321,29 @@ pub fn create_shader_modules_spirv_system<T: Send + Sync + 'static>(world: &mut
     }
 }
 
-/// Create pending usage-tagged buffers, recreating them if a Changed flag is set
-pub fn create_buffers_system(world: &mut World) {
-    let mut query = world.query::<&DeviceComponent>();
-    let (_, device) = query.iter().next().unwrap();
+/// Create pending usage-tagged buffers, recreating them if their descriptor has changed
+pub fn create_buffers_system(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping buffer creation: {}", e);
+            return;
+        }
+    };
+
+    let since = match observe_changes_or_warn::<CreateBuffers>(world, "buffer creation") {
+        Some(since) => since,
+        None => return,
+    };
 
     let mut query = world.query::<(&BufferDescriptorComponent, &mut BufferComponent)>();
     for (entity, (buffer_descriptor, buffer)) in query.into_iter() {
-        if !buffer.read().is_pending() && !buffer_descriptor.get_changed() {
+        if !buffer.read().is_pending() && !buffer_descriptor.is_changed_since(since) {
             continue;
         }
 
         buffer.write().set_ready_with(device.create_buffer(&buffer_descriptor).into());
 
-        buffer_descriptor.set_changed(false);
-
         println!(
             "Created buffer for entity {:?} with label {:?}",
             entity, buffer_descriptor.label
@@ -269,21 +351,31 @@ pub fn create_buffers_system(world: &mut World) {
     }
 }
 
-/// Create-initialize pending usage-tagged buffers, recreating them if a Changed flag is set
-pub fn create_buffers_init_system(world: &mut World) {
+/// Create-initialize pending usage-tagged buffers, recreating them if their descriptor has changed
+pub fn create_buffers_init_system(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping buffer create-initialization: {}", e);
+            return;
+        }
+    };
+
+    let since =
+        match observe_changes_or_warn::<CreateBuffersInit>(world, "buffer create-initialization") {
+            Some(since) => since,
+            None => return,
+        };
+
     let mut query = world.query::<(&BufferInitDescriptorComponent, &mut BufferComponent)>();
 
     for (_, (buffer_init_descriptor, buffer)) in query.into_iter() {
-        if !buffer.read().is_pending() && !buffer_init_descriptor.get_changed() {
+        if !buffer.read().is_pending() && !buffer_init_descriptor.is_changed_since(since) {
             continue;
         }
 
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
         buffer.write().set_ready_with(device.create_buffer_init(&buffer_init_descriptor).into());
 
-        buffer_init_descriptor.set_changed(false);
-
         println!(
             "Create-initialized buffer with label {:?}",
             buffer_init_descriptor.label
@@ -291,12 +383,25 @@ pub fn create_buffers_init_system(world: &mut World) {
     }
 }
 
-/// Create pending usage-tagged textures, recreating them if a Changed flag is set
-pub fn create_textures_system(world: &mut World) {
+/// Create pending usage-tagged textures, recreating them if their descriptor has changed
+pub fn create_textures_system(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping texture creation: {}", e);
+            return;
+        }
+    };
+
+    let since = match observe_changes_or_warn::<CreateTextures>(world, "texture creation") {
+        Some(since) => since,
+        None => return,
+    };
+
     let mut query = world.query::<(&TextureDescriptorComponent, &mut TextureComponent)>();
 
     for (_, (texture_descriptor_component, texture)) in query.into_iter() {
-        if !texture.is_pending() && !texture_descriptor_component.get_changed() {
+        if !texture.is_pending() && !texture_descriptor_component.is_changed_since(since) {
             continue;
         }
 
@@ -308,71 +413,92 @@ pub fn create_textures_system(world: &mut World) {
             continue;
         }
 
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
-
         texture.set_ready_with(device.create_texture(&*texture_descriptor).into());
 
-        texture_descriptor_component.set_changed(false);
-
         println!("Created texture: {:#?}", **texture_descriptor);
     }
 }
 
-/// Create pending usage-tagged texture views, recreating them if a Changed flag is set
-pub fn create_texture_views_system(world: &mut World) {
+/// Create pending usage-tagged texture views, recreating them if their descriptor has changed
+pub fn create_texture_views_system(world: &World) {
+    let since = match observe_changes_or_warn::<CreateTextureViews>(world, "texture view creation")
+    {
+        Some(since) => since,
+        None => return,
+    };
+    let deferred = take_deferred_changes::<CreateTextureViews>(world).unwrap_or_default();
+
     let mut query = world.query::<(
         &TextureComponent,
         &TextureViewDescriptorComponent<'static>,
         &mut TextureViewComponent,
     )>();
 
-    for (_, (texture, texture_view_descriptor, texture_view)) in query.into_iter() {
-        if !texture_view.is_pending() && !texture_view_descriptor.get_changed() {
+    for (entity, (texture, texture_view_descriptor, texture_view)) in query.into_iter() {
+        if !texture_view.is_pending()
+            && !texture_view_descriptor.is_changed_since(since)
+            && !deferred.contains(&entity)
+        {
             continue;
         }
 
         let texture = if let LazyComponent::Ready(texture) = &*texture {
             texture
         } else {
+            // Retry once the texture is ready
+            defer_changes::<CreateTextureViews>(world, entity).ok();
             continue;
         };
 
         texture_view.set_ready_with(texture.create_view(&texture_view_descriptor));
 
-        texture_view_descriptor.set_changed(false);
-
         println!("Created texture view: {:#?}", **texture_view_descriptor);
     }
 }
 
-/// Create pending usage-tagged samplers, recreating them if a Changed flag is set
-pub fn create_samplers_system(world: &mut World) {
+/// Create pending usage-tagged samplers, recreating them if their descriptor has changed
+pub fn create_samplers_system(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping sampler creation: {}", e);
+            return;
+        }
+    };
+
+    let since = match observe_changes_or_warn::<CreateSamplers>(world, "sampler creation") {
+        Some(since) => since,
+        None => return,
+    };
+
     let mut query = world.query::<(&SamplerDescriptorComponent, &mut SamplerComponent)>();
 
     for (_, (sampler_descriptor, sampler)) in query.into_iter() {
-        if !sampler.is_pending() && !sampler_descriptor.get_changed() {
+        if !sampler.is_pending() && !sampler_descriptor.is_changed_since(since) {
             continue;
         }
 
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
         sampler.set_ready_with(device.create_sampler(&sampler_descriptor));
 
-        sampler_descriptor.set_changed(false);
-
         println!("Created sampler: {:#?}", **sampler_descriptor);
     }
 }
 
 // Write data to buffer
-pub fn buffer_write_system<T: bytemuck::Pod + Send + Sync + 'static>(world: &mut World) {
-    let mut query = world.query::<&QueueComponent>();
-    let (_, queue) = if let Some(components) = query.into_iter().next() {
-        components
-    } else {
-        return;
+pub fn buffer_write_system<T: bytemuck::Pod + Send + Sync + 'static>(world: &World) {
+    let queue = match world.get_resource::<QueueComponent>() {
+        Ok(queue) => queue,
+        Err(e) => {
+            tracing::warn!("Skipping buffer writes: {}", e);
+            return;
+        }
+    };
+
+    let since = match observe_changes_or_warn::<(BufferWrite, T)>(world, "buffer writes") {
+        Some(since) => since,
+        None => return,
     };
+    let deferred = take_deferred_changes::<(BufferWrite, T)>(world).unwrap_or_default();
 
     let mut query = world.query::<(
         &BufferWriteComponent<T>,
@@ -380,21 +506,38 @@ pub fn buffer_write_system<T: bytemuck::Pod + Send + Sync + 'static>(world: &mut
         &Usage<BufferWriteComponent<T>, Indirect<&BufferComponent>>,
     )>();
 
-    for (_, (buffer_write, data_component, buffer)) in query.into_iter() {
+    for (entity, (buffer_write, data_component, buffer)) in query.into_iter() {
         let buffer_entity = buffer.entity();
-        let mut query = buffer.get(world);
-        let buffer = query.get().unwrap_or_else(|| {
-            panic!(
-                "No buffer component for data {}",
-                std::any::type_name::<T>()
-            )
-        });
-
-        if data_component.get_changed() {
+        let mut query = match buffer.get(world) {
+            Ok(query) => query,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping buffer write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    e
+                );
+                continue;
+            }
+        };
+        let buffer = match query.get() {
+            Some(buffer_component) => buffer_component,
+            None => {
+                tracing::warn!(
+                    "Skipping buffer write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    buffer.unsatisfied()
+                );
+                continue;
+            }
+        };
+
+        if data_component.is_changed_since(since) || deferred.contains(&entity) {
             let buffer = buffer.read();
             let buffer = if let LazyComponent::Ready(buffer) = &*buffer {
                 buffer
             } else {
+                // Retry once the buffer is ready
+                defer_changes::<(BufferWrite, T)>(world, entity).ok();
                 continue;
             };
 
@@ -410,8 +553,6 @@ pub fn buffer_write_system<T: bytemuck::Pod + Send + Sync + 'static>(world: &mut
             );
             */
             queue.write_buffer(buffer, buffer_write.offset(), bytes);
-
-            data_component.set_changed(false);
         }
     }
 }
@@ -420,36 +561,61 @@ pub fn buffer_write_slice_system<
     T: Deref<Target = [V]> + Send + Sync + 'static,
     V: bytemuck::Pod + 'static,
 >(
-    world: &mut World,
+    world: &World,
 ) {
-    let mut query = world.query::<&QueueComponent>();
-    let (_, queue) = if let Some(components) = query.into_iter().next() {
-        components
-    } else {
-        return;
+    let queue = match world.get_resource::<QueueComponent>() {
+        Ok(queue) => queue,
+        Err(e) => {
+            tracing::warn!("Skipping buffer slice writes: {}", e);
+            return;
+        }
     };
 
+    let since = match observe_changes_or_warn::<(BufferWriteSlice, T)>(world, "buffer slice writes")
+    {
+        Some(since) => since,
+        None => return,
+    };
+    let deferred = take_deferred_changes::<(BufferWriteSlice, T)>(world).unwrap_or_default();
+
     let mut query = world.query::<(
         &BufferWriteComponent<T>,
         &Changed<T>,
         &Usage<BufferWriteComponent<T>, Indirect<&BufferComponent>>,
     )>();
 
-    for (_, (buffer_write, data_component, buffer)) in query.into_iter() {
+    for (entity, (buffer_write, data_component, buffer)) in query.into_iter() {
         let buffer_entity = buffer.entity();
-        let mut query = buffer.get(world);
-        let buffer = query.get().unwrap_or_else(|| {
-            panic!(
-                "No buffer component for data {}",
-                std::any::type_name::<T>()
-            )
-        });
-
-        if data_component.get_changed() {
+        let mut query = match buffer.get(world) {
+            Ok(query) => query,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping buffer write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    e
+                );
+                continue;
+            }
+        };
+        let buffer = match query.get() {
+            Some(buffer_component) => buffer_component,
+            None => {
+                tracing::warn!(
+                    "Skipping buffer write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    buffer.unsatisfied()
+                );
+                continue;
+            }
+        };
+
+        if data_component.is_changed_since(since) || deferred.contains(&entity) {
             let buffer = buffer.read();
             let buffer = if let LazyComponent::Ready(buffer) = &*buffer {
                 buffer
             } else {
+                // Retry once the buffer is ready
+                defer_changes::<(BufferWriteSlice, T)>(world, entity).ok();
                 continue;
             };
 
@@ -465,24 +631,29 @@ pub fn buffer_write_slice_system<
             );
             */
             queue.write_buffer(buffer, buffer_write.offset(), bytes);
-
-            data_component.set_changed(false);
         }
     }
 }
 
 // Write data to texture
-pub fn texture_write_system<T>(world: &mut World)
+pub fn texture_write_system<T>(world: &World)
 where
     T: bytemuck::Pod + Send + Sync + 'static,
 {
-    let mut query = world.query::<&QueueComponent>();
-    let (_, queue) = if let Some(queue) = query.into_iter().next() {
-        queue
-    } else {
-        return;
+    let queue = match world.get_resource::<QueueComponent>() {
+        Ok(queue) => queue,
+        Err(e) => {
+            tracing::warn!("Skipping texture writes: {}", e);
+            return;
+        }
     };
 
+    let since = match observe_changes_or_warn::<(TextureWrite, T)>(world, "texture writes") {
+        Some(since) => since,
+        None => return,
+    };
+    let deferred = take_deferred_changes::<(TextureWrite, T)>(world).unwrap_or_default();
+
     let mut query = world.query::<(
         &TextureWriteComponent<T>,
         &Changed<T>,
@@ -492,19 +663,36 @@ where
         >,
     )>();
 
-    for (_, (texture_write, texels_component, texture)) in query.into_iter() {
-        let mut query = texture.get(world);
-        let (texture_desc, texture) = query.get().unwrap_or_else(|| {
-            panic!(
-                "No buffer component for data {}",
-                std::any::type_name::<T>()
-            )
-        });
+    for (entity, (texture_write, texels_component, texture)) in query.into_iter() {
+        let mut query = match texture.get(world) {
+            Ok(query) => query,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping texture write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    e
+                );
+                continue;
+            }
+        };
+        let (texture_desc, texture) = match query.get() {
+            Some(components) => components,
+            None => {
+                tracing::warn!(
+                    "Skipping texture write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    texture.unsatisfied()
+                );
+                continue;
+            }
+        };
 
-        if texels_component.get_changed() {
+        if texels_component.is_changed_since(since) || deferred.contains(&entity) {
             let texture = if let LazyComponent::Ready(texture) = texture {
                 texture
             } else {
+                // Retry once the texture is ready
+                defer_changes::<(TextureWrite, T)>(world, entity).ok();
                 continue;
             };
 
@@ -529,24 +717,30 @@ where
                 *image_data_layout,
                 texture_desc.size,
             );
-
-            texels_component.set_changed(false);
         }
     }
 }
 
-pub fn texture_write_slice_system<T, V>(world: &mut World)
+pub fn texture_write_slice_system<T, V>(world: &World)
 where
     T: Deref<Target = [V]> + Send + Sync + 'static,
     V: bytemuck::Pod,
 {
-    let mut query = world.query::<&QueueComponent>();
-    let (_, queue) = if let Some(queue) = query.into_iter().next() {
-        queue
-    } else {
-        return;
+    let queue = match world.get_resource::<QueueComponent>() {
+        Ok(queue) => queue,
+        Err(e) => {
+            tracing::warn!("Skipping texture slice writes: {}", e);
+            return;
+        }
     };
 
+    let since =
+        match observe_changes_or_warn::<(TextureWriteSlice, T)>(world, "texture slice writes") {
+            Some(since) => since,
+            None => return,
+        };
+    let deferred = take_deferred_changes::<(TextureWriteSlice, T)>(world).unwrap_or_default();
+
     let mut query = world.query::<(
         &TextureWriteComponent<T>,
         &Changed<T>,
@@ -556,19 +750,36 @@ where
         >,
     )>();
 
-    for (_, (texture_write, texels_component, texture)) in query.into_iter() {
-        let mut query = texture.get(world);
-        let (texture_desc, texture) = query.get().unwrap_or_else(|| {
-            panic!(
-                "No buffer component for data {}",
-                std::any::type_name::<T>()
-            )
-        });
+    for (entity, (texture_write, texels_component, texture)) in query.into_iter() {
+        let mut query = match texture.get(world) {
+            Ok(query) => query,
+            Err(e) => {
+                tracing::warn!(
+                    "Skipping texture write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    e
+                );
+                continue;
+            }
+        };
+        let (texture_desc, texture) = match query.get() {
+            Some(components) => components,
+            None => {
+                tracing::warn!(
+                    "Skipping texture write for data {}: {}",
+                    std::any::type_name::<T>(),
+                    texture.unsatisfied()
+                );
+                continue;
+            }
+        };
 
-        if texels_component.get_changed() {
+        if texels_component.is_changed_since(since) || deferred.contains(&entity) {
             let texture = if let LazyComponent::Ready(texture) = texture {
                 texture
             } else {
+                // Retry once the texture is ready
+                defer_changes::<(TextureWriteSlice, T)>(world, entity).ok();
                 continue;
             };
 
@@ -593,24 +804,22 @@ where
                 *image_data_layout,
                 texture_desc.size,
             );
-
-            texels_component.set_changed(false);
         }
     }
 }
 
 // Flush command buffers to the WGPU queue
 pub fn submit_command_buffers_system(world: &mut World) {
-    let mut query = world.query::<&mut CommandBuffersComponent>();
+    let queue = match world.get_resource::<QueueComponent>() {
+        Ok(queue) => queue,
+        Err(e) => {
+            tracing::warn!("Skipping command buffer submission: {}", e);
+            return;
+        }
+    };
 
+    let mut query = world.query::<&mut CommandBuffersComponent>();
     for (_, command_buffers) in query.into_iter() {
-        let mut query = world.query::<&QueueComponent>();
-        let (_, queue) = if let Some(queue) = query.into_iter().next() {
-            queue
-        } else {
-            continue;
-        };
-
         println!("Submitting command buffers: {:?}", command_buffers);
         queue.submit(command_buffers.drain(..));
     }
@@ -618,22 +827,29 @@ pub fn submit_command_buffers_system(world: &mut World) {
 
 // Create textures and corresponding texture views for surfaces
 pub fn surfaces_textures_views_system(world: &mut World) {
-    let mut query = world.query::<&WindowEventComponent>();
-    let (_, window_event) = query.into_iter().next().unwrap();
-    let window_event = window_event.0;
-    drop(query);
+    let window_event = match world.get_resource::<WindowEventComponent>() {
+        Ok(window_event) => window_event.0,
+        Err(e) => {
+            tracing::warn!("Skipping surface texture creation: {}", e);
+            return;
+        }
+    };
 
     let window_event = window_event.expect("No window for current event");
 
-    let mut query = world.query::<&WindowEntityMap>();
-
-    let (_, window_entity_map) = query.into_iter().next().unwrap();
+    let window_entity_map = match world.get_resource::<WindowEntityMap>() {
+        Ok(window_entity_map) => window_entity_map,
+        Err(e) => {
+            tracing::warn!("Skipping surface texture creation: {}", e);
+            return;
+        }
+    };
 
     let entity = *window_entity_map
         .get(&window_event)
         .expect("Redraw requested for window without entity");
 
-    drop(query);
+    drop(window_entity_map);
 
     // Create surface textures and views
     // These will be rendered to and presented during RedrawEventsCleared
@@ -641,24 +857,34 @@ pub fn surfaces_textures_views_system(world: &mut World) {
     surface_texture_view_query(world, entity);
 }
 
-/// Create pending CommandEncoders, recreating them if a Changed flag is set
-pub fn create_command_encoders_system(world: &mut World) {
+/// Create pending CommandEncoders, recreating them if their descriptor has changed
+pub fn create_command_encoders_system(world: &World) {
+    let device = match world.get_resource::<DeviceComponent>() {
+        Ok(device) => device,
+        Err(e) => {
+            tracing::warn!("Skipping command encoder creation: {}", e);
+            return;
+        }
+    };
+
+    let since =
+        match observe_changes_or_warn::<CreateCommandEncoders>(world, "command encoder creation") {
+            Some(since) => since,
+            None => return,
+        };
+
     let mut query = world.query::<(
         &crate::CommandEncoderDescriptorComponent,
         &mut crate::CommandEncoderComponent,
     )>();
 
     for (entity, (command_encoder_desc, command_encoder)) in query.into_iter() {
-        if !command_encoder.is_pending() && !command_encoder_desc.get_changed() {
+        if !command_encoder.is_pending() && !command_encoder_desc.is_changed_since(since) {
             continue;
         }
 
-        let mut query = world.query::<&DeviceComponent>();
-        let (_, device) = query.into_iter().next().unwrap();
         command_encoder.set_ready_with(device.create_command_encoder(&command_encoder_desc));
 
-        command_encoder_desc.set_changed(false);
-
         println!(
             "Created command encoder {:#?} for entity {:?}",
             **command_encoder_desc, entity
@@ -673,8 +899,24 @@ pub fn flush_command_encoders_system(world: &mut World) {
         &Usage<CommandEncoderComponent, Indirect<&mut CommandBuffersComponent>>,
     )>();
     for (entity, (command_encoder, command_buffers)) in query.into_iter() {
-        let mut query = command_buffers.get(world);
-        let command_buffers = query.get().unwrap();
+        let mut query = match command_buffers.get(world) {
+            Ok(query) => query,
+            Err(e) => {
+                tracing::warn!(?entity, "Skipping command encoder flush: {}", e);
+                continue;
+            }
+        };
+        let command_buffers = match query.get() {
+            Some(command_buffers_component) => command_buffers_component,
+            None => {
+                tracing::warn!(
+                    ?entity,
+                    "Skipping command encoder flush: {}",
+                    command_buffers.unsatisfied()
+                );
+                continue;
+            }
+        };
 
         if let LazyComponent::Ready(encoder) = command_encoder.take() {
             println!("Flushing command encoder for entity {:?}", entity);
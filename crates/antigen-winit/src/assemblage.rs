@@ -6,6 +6,7 @@ use crate::{
     WindowSizeComponent, WindowTitleComponent,
 };
 
+/// Window resources, to be inserted via [`antigen_core::Resources::insert_resources`]
 #[derive(Default, hecs::Bundle)]
 pub struct BackendBundle {
     window_entity_map: WindowEntityMap,
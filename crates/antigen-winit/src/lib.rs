@@ -9,14 +9,13 @@ pub use systems::*;
 pub use winit;
 
 use winit::{
-    event::{DeviceEvent, DeviceId, Event, WindowEvent},
+    event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoopWindowTarget},
-    window::WindowId,
 };
 
-use hecs::World;
+use hecs::{RefMut, World};
 
-use antigen_core::WorldChannel;
+use antigen_core::{init_observed_ticks, MissingResource, Resources, WorldChannel};
 
 /// A winit-compatible event loop closure
 pub trait WinitEventLoopHandler<T>:
@@ -46,11 +45,15 @@ impl<T, U> EventLoopHandler<U> for T where
 }
 
 /// Wrap [`EventLoopHandler`] into a [`WinitEventLoopHandler`]
+///
+/// Initializes change observation for `world`, which is required by the window systems.
 pub fn wrap_event_loop<T>(
     mut world: World,
     channel: WorldChannel,
     mut f: impl EventLoopHandler<T>,
 ) -> impl WinitEventLoopHandler<T> {
+    init_observed_ticks(&mut world);
+
     move |event: Event<T>,
           event_loop_window_target: &EventLoopWindowTarget<T>,
           control_flow: &mut winit::event_loop::ControlFlow| {
@@ -71,23 +74,15 @@ pub fn wrap_event_loop<T>(
 }
 
 fn get_window_event_component(
-    world: &mut World,
-) -> &mut (Option<WindowId>, Option<WindowEvent<'static>>) {
-    let (_, window_event) = world
-        .query_mut::<&mut WindowEventComponent>()
-        .into_iter()
-        .next()
-        .unwrap();
-    window_event
+    world: &World,
+) -> Result<RefMut<'_, WindowEventComponent>, MissingResource> {
+    world.get_resource_mut::<WindowEventComponent>()
 }
 
-fn get_device_event_component(world: &mut World) -> &mut (Option<DeviceId>, Option<DeviceEvent>) {
-    let (_, device_event) = world
-        .query_mut::<&mut DeviceEventComponent>()
-        .into_iter()
-        .next()
-        .unwrap();
-    device_event
+fn get_device_event_component(
+    world: &World,
+) -> Result<RefMut<'_, DeviceEventComponent>, MissingResource> {
+    world.get_resource_mut::<DeviceEventComponent>()
 }
 
 /// Extend an event loop closure with ECS event loop handling and window functionality
@@ -97,14 +92,27 @@ pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl Ev
           event: Event<'static, T>,
           event_loop_window_target: &EventLoopWindowTarget<T>,
           control_flow: &mut ControlFlow| {
-        {
-            let window_event = get_window_event_component(world);
-            *window_event = (None, None);
+        let (window_event, device_event) = match &event {
+            winit::event::Event::RedrawRequested(window_id) => {
+                ((Some(*window_id), None), (None, None))
+            }
+            winit::event::Event::WindowEvent { window_id, event } => {
+                ((Some(*window_id), Some(event.clone())), (None, None))
+            }
+            winit::event::Event::DeviceEvent { device_id, event } => {
+                ((None, None), (Some(*device_id), Some(event.clone())))
+            }
+            _ => ((None, None), (None, None)),
+        };
+
+        match get_window_event_component(world) {
+            Ok(mut window_event_component) => *window_event_component = window_event,
+            Err(e) => tracing::warn!("Skipping window event tracking: {}", e),
         }
 
-        {
-            let device_event = get_device_event_component(world);
-            *device_event = (None, None);
+        match get_device_event_component(world) {
+            Ok(mut device_event_component) => *device_event_component = device_event,
+            Err(e) => tracing::warn!("Skipping device event tracking: {}", e),
         }
 
         match &event {
@@ -113,24 +121,15 @@ pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl Ev
                 window_title_system(world);
                 redraw_unconditionally_system(world);
             }
-            winit::event::Event::RedrawRequested(window_id) => {
-                get_window_event_component(world).0 = Some(*window_id);
-            }
-            winit::event::Event::WindowEvent { window_id, event } => {
-                *get_window_event_component(world) = (Some(*window_id), Some(event.clone()));
-                match event {
-                    WindowEvent::Resized(_) => {
-                        resize_window_system(world);
-                    }
-                    WindowEvent::CloseRequested => {
-                        close_window_system(world);
-                    }
-                    _ => (),
+            winit::event::Event::WindowEvent { event, .. } => match event {
+                WindowEvent::Resized(_) => {
+                    resize_window_system(world);
                 }
-            }
-            winit::event::Event::DeviceEvent { device_id, event } => {
-                *get_device_event_component(world) = (Some(*device_id), Some(event.clone()))
-            }
+                WindowEvent::CloseRequested => {
+                    close_window_system(world);
+                }
+                _ => (),
+            },
             _ => (),
         }
 
@@ -141,13 +140,6 @@ pub fn winit_event_handler<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl Ev
             event_loop_window_target,
             control_flow,
         );
-
-        match &event {
-            winit::event::Event::MainEventsCleared => {
-                reset_window_size_changed_system(world);
-            }
-            _ => (),
-        }
     }
 }
 
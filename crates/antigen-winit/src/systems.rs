@@ -2,14 +2,19 @@ use super::{RedrawUnconditionally, WindowComponent};
 use crate::{WindowEntityMap, WindowEventComponent, WindowSizeComponent, WindowTitleComponent};
 use hecs::World;
 
-use antigen_core::{ChangedTrait, LazyComponent};
+use antigen_core::{observe_entity_changes_or_warn, ChangedTrait, LazyComponent, Resources};
 
 use winit::event_loop::EventLoopWindowTarget;
 
 // Create winit::Window for WindowComponent
 pub fn create_windows_system<T>(world: &mut World, event_loop_proxy: &EventLoopWindowTarget<T>) {
-    let mut query = world.query::<&mut WindowEntityMap>();
-    let (_, window_entity_map) = query.into_iter().next().unwrap();
+    let mut window_entity_map = match world.get_resource_mut::<WindowEntityMap>() {
+        Ok(window_entity_map) => window_entity_map,
+        Err(e) => {
+            tracing::warn!("Skipping window creation: {}", e);
+            return;
+        }
+    };
 
     let mut query = world.query::<&WindowComponent>();
     let pending_entities = query
@@ -36,7 +41,7 @@ pub fn create_windows_system<T>(world: &mut World, event_loop_proxy: &EventLoopW
 
         if let Some(window_size) = size_component {
             ***window_size = size;
-            window_size.set_changed(true);
+            window_size.mark_changed();
         }
     }
 }
@@ -56,13 +61,23 @@ pub fn redraw_unconditionally_system(world: &mut World) {
 }
 
 pub fn resize_window_system(world: &mut World) {
-    let mut query = world.query::<&WindowEventComponent>();
-    let (_, event_window) = query.into_iter().next().unwrap();
+    let event_window = match world.get_resource::<WindowEventComponent>() {
+        Ok(event_window) => event_window,
+        Err(e) => {
+            tracing::warn!("Skipping window resize: {}", e);
+            return;
+        }
+    };
 
     let window_id = event_window.0.expect("No window for current event");
 
-    let mut query = world.query::<&WindowEntityMap>();
-    let (_, window_entity_map) = query.into_iter().next().unwrap();
+    let window_entity_map = match world.get_resource::<WindowEntityMap>() {
+        Ok(window_entity_map) => window_entity_map,
+        Err(e) => {
+            tracing::warn!("Skipping window resize: {}", e);
+            return;
+        }
+    };
 
     let entity = window_entity_map
         .get(&window_id)
@@ -80,36 +95,52 @@ pub fn resize_window_system(world: &mut World) {
 
     if let LazyComponent::Ready(window) = &*window_component {
         ***size_component = window.inner_size();
-        size_component.set_changed(true);
+        size_component.mark_changed();
     }
 }
 
-pub fn reset_window_size_changed_system(world: &mut World) {
-    for (_, window_size) in world.query_mut::<&mut WindowSizeComponent>() {
-        if window_size.get_changed() {
-            println!("Resetting window size changed flag");
-            window_size.set_changed(false);
-        }
-    }
-}
+enum WindowTitleSystem {}
 
 pub fn window_title_system(world: &mut World) {
-    world
+    let entities = world
         .query_mut::<(&WindowComponent, &WindowTitleComponent)>()
         .into_iter()
-        .for_each(|(_, (window, title))| {
-            if let LazyComponent::Ready(window) = &*window {
-                if title.get_changed() {
-                    window.set_title(&title);
-                    title.set_changed(false);
-                }
+        .filter(|(_, (window, _))| matches!(&**window, LazyComponent::Ready(_)))
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+
+    // Observe per window, so titles set before their window is created are not missed
+    for entity in entities {
+        let since = match observe_entity_changes_or_warn::<WindowTitleSystem>(
+            world,
+            entity,
+            "window title updates",
+        ) {
+            Some(since) => since,
+            None => return,
+        };
+
+        let mut query = world
+            .query_one::<(&WindowComponent, &WindowTitleComponent)>(entity)
+            .unwrap();
+        let (window, title) = query.get().unwrap();
+
+        if let LazyComponent::Ready(window) = &*window {
+            if title.is_changed_since(since) {
+                window.set_title(&title);
             }
-        });
+        }
+    }
 }
 
 pub fn close_window_system(world: &mut World) {
-    let mut query = world.query::<&WindowEventComponent>();
-    let (_, window_event) = query.into_iter().next().unwrap();
+    let window_event = match world.get_resource::<WindowEventComponent>() {
+        Ok(window_event) => window_event,
+        Err(e) => {
+            tracing::warn!("Skipping window close: {}", e);
+            return;
+        }
+    };
 
     let window_id = if let (Some(window_id), _) = &*window_event {
         window_id
@@ -117,8 +148,13 @@ pub fn close_window_system(world: &mut World) {
         return;
     };
 
-    let mut query = world.query::<&WindowEntityMap>();
-    let (_, window_entity_map) = query.into_iter().next().unwrap();
+    let window_entity_map = match world.get_resource::<WindowEntityMap>() {
+        Ok(window_entity_map) => window_entity_map,
+        Err(e) => {
+            tracing::warn!("Skipping window close: {}", e);
+            return;
+        }
+    };
 
     let entity = window_entity_map
         .get(&window_id)
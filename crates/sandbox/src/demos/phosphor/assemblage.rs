@@ -157,8 +157,8 @@ pub fn line_mesh_instance_builder(
     let (_, mesh_ids) = query.into_iter().next()?;
     let (line_mesh, line_count) = *mesh_ids.read().get(mesh)?;
 
-    let line_mesh_instance_entity = get_tagged_entity::<LineMeshInstances>(world)?;
-    let line_instance_entity = get_tagged_entity::<LineInstances>(world)?;
+    let line_mesh_instance_entity = get_tagged_entity::<LineMeshInstances>(world).ok()?;
+    let line_instance_entity = get_tagged_entity::<LineInstances>(world).ok()?;
 
     let line_mesh_instance_head = world
         .query_one_mut::<&mut antigen_wgpu::BufferLengthComponent>(line_mesh_instance_entity)
@@ -448,7 +448,7 @@ pub fn triangle_mesh_instance_builder(
     let (_, mesh_ids) = query.into_iter().next()?;
     let triangle_mesh = *mesh_ids.read().get(mesh)?;
 
-    let triangle_mesh_instance_entity = get_tagged_entity::<TriangleMeshInstances>(world)?;
+    let triangle_mesh_instance_entity = get_tagged_entity::<TriangleMeshInstances>(world).ok()?;
 
     let triangle_mesh_instance_heads = world
         .query_one_mut::<&mut antigen_wgpu::BufferLengthsComponent>(triangle_mesh_instance_entity)
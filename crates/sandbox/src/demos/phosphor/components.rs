@@ -1,16 +1,14 @@
 use bytemuck::{Pod, Zeroable};
 use parking_lot::RwLock;
 use rapier3d::prelude::IntersectionEvent;
-use std::{borrow::Cow, collections::{BTreeMap, BTreeSet}, sync::Arc, time::Instant, marker::PhantomData};
+use std::{borrow::Cow, collections::{BTreeMap, BTreeSet}, sync::Arc};
 
-use antigen_core::{Changed, LazyComponent, Usage};
+use antigen_core::{Changed, EventInputComponent, EventOutputComponent, LazyComponent, Usage};
 
 // Phosphor renderer tag
 pub struct PhosphorRenderer;
 
 // Usage tags
-pub enum StartTime {}
-pub enum Timestamp {}
 pub enum TotalTime {}
 pub enum DeltaTime {}
 
@@ -59,8 +57,6 @@ pub struct Tonemap;
 pub enum MapFile {}
 
 // Usage-tagged components
-pub type StartTimeComponent = Usage<StartTime, Instant>;
-pub type TimestampComponent = Usage<Timestamp, Instant>;
 pub type TotalTimeComponent = Usage<TotalTime, f32>;
 pub type DeltaTimeComponent = Usage<DeltaTime, f32>;
 
@@ -268,36 +264,6 @@ pub type SharedShapesComponent = Usage<
     >,
 >;
 
-pub struct EventInput;
-pub type EventInputComponent<T> = Usage<EventInput, Vec<T>>;
-
-pub struct EventOutput;
-pub type EventOutputComponent<T> = Usage<EventOutput, Vec<T>>;
-
-pub struct EventTransformComponent<I, O>(PhantomData<(I, O)>);
-
-impl<I, O> Default for EventTransformComponent<I, O> {
-    fn default() -> Self {
-        EventTransformComponent(PhantomData)
-    }
-}
-
-impl EventTransformComponent<(), ()> {
-    pub fn unit() -> Self {
-        Default::default()
-    }
-}
-
-impl<I, O> EventTransformComponent<I, O> {
-    pub fn with_input_type<T>(self) -> EventTransformComponent<T, O> {
-        Default::default()
-    }
-
-    pub fn with_output_type<T>(self) -> EventTransformComponent<I, T> {
-        Default::default()
-    }
-}
-
 pub struct EulerAngles;
 pub type EulerAnglesComponent = Usage<EulerAngles, nalgebra::Vector3<f32>>;
 
@@ -333,5 +299,3 @@ pub type EventInComponent = Usage<EventIn, Cow<'static, str>>;
 pub struct EventOut;
 pub type EventOutComponent = Usage<EventOut, Cow<'static, str>>;
 
-pub struct EventTarget<T>(PhantomData<T>);
-pub type EventTargetComponent<T> = Usage<EventTarget<T>, Cow<'static, str>>;
@@ -103,7 +103,7 @@ mod render_passes;
 mod svg_lines;
 mod systems;
 
-use antigen_fs::{load_file_string, FilePathComponent, FileStringQuery};
+use antigen_fs::{load_file_string, subscribe_file_reload, FilePathComponent, FileStringQuery};
 use antigen_rapier3d::{
     AngularVelocityComponent, ColliderComponent, LinearVelocityComponent, RigidBodyComponent,
 };
@@ -119,7 +119,6 @@ pub use systems::*;
 use expression::{EvalTrait, Expression};
 use std::{
     borrow::Cow, collections::BTreeMap, error::Error, path::PathBuf, sync::atomic::Ordering,
-    time::Instant,
 };
 use winit::event::DeviceEvent;
 
@@ -133,8 +132,9 @@ use antigen_winit::{
 
 use antigen_core::{
     get_tagged_entity, insert_tagged_entity, insert_tagged_entity_by_query, send_clone_query,
-    send_component, Construct, Indirect, Lift, MessageContext, MessageResult, NamedEntityComponent,
-    PositionComponent, RotationComponent, ScaleComponent, SendTo, WorldChannel,
+    send_component, Changed, Construct, EventTargetComponent, EventTransformComponent, Indirect,
+    Lift, MessageContext, MessageResult, NamedEntityComponent, PositionComponent, Resources,
+    RotationComponent, ScaleComponent, Schedule, ScheduleError, SendTo, System, Time, WorldChannel,
 };
 
 use antigen_wgpu::{
@@ -240,22 +240,38 @@ fn circle_strip(subdiv: usize, z_ofs: f32) -> Vec<LineVertexData> {
         .collect()
 }
 
-fn load_shader_message<P: Copy + Into<PathBuf>>(
+fn load_shader_message<U: 'static, P: Copy + Into<PathBuf>>(
     shader_path: P,
     entity: Entity,
 ) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
     move |ctx| {
         ctx.lift()
             .and_then(load_file_string(shader_path))
-            .and_then(spawn_shader_from_file_string(shader_path))
+            .and_then(send_shader_message(shader_path.into(), entity))
+            .and_then(subscribe_file_reload::<U, _, _, _>(
+                shader_path,
+                move |path| send_shader_message(path, entity),
+            ))
+    }
+}
+
+/// Create a shader from a loaded file and send it to the render thread,
+/// replacing any previous version when the file is reloaded
+fn send_shader_message(
+    shader_path: PathBuf,
+    entity: Entity,
+) -> impl for<'a, 'b> FnOnce(MessageContext<'a, 'b>) -> MessageResult<'a, 'b> {
+    move |ctx| {
+        ctx.lift()
+            .and_then(spawn_shader_from_file_string(shader_path.clone()))
             .and_then(
                 send_component::<ShaderModuleDescriptorComponent, Render, _>(
-                    FilePathComponent::construct(shader_path.into()),
+                    FilePathComponent::construct(shader_path.clone()),
                     entity,
                 ),
             )
             .and_then(send_component::<ShaderModuleComponent, Render, _>(
-                FilePathComponent::construct(shader_path.into()),
+                FilePathComponent::construct(shader_path),
                 entity,
             ))
     }
@@ -267,7 +283,7 @@ fn load_shader<T: Send + Sync + 'static, P: Copy + Into<PathBuf> + Send + Sync +
     shader_path: P,
 ) {
     channel
-        .send_to::<T>(load_shader_message(shader_path, entity))
+        .send_to::<T>(load_shader_message::<T, _>(shader_path, entity))
         .unwrap();
 }
 
@@ -371,7 +387,7 @@ fn insert_tagged_entity_by_query_message<Q: hecs::Query + Send + Sync + 'static,
 ) -> impl for<'a, 'b> Fn(MessageContext<'a, 'b>) -> Result<MessageContext<'a, 'b>, Box<dyn Error>> {
     move |mut ctx: MessageContext| {
         let (world, _) = &mut ctx;
-        insert_tagged_entity_by_query::<Q, T>(world);
+        insert_tagged_entity_by_query::<Q, T>(world)?;
         Ok(ctx)
     }
 }
@@ -522,28 +538,24 @@ fn line_vertex_buffer_bundle(entity: Entity, vertices: Vec<LineVertexData>) -> E
 
 fn total_time_builder(uniform_entity: Entity) -> EntityBuilder {
     let mut builder = EntityBuilder::new();
-    builder
-        .add(StartTimeComponent::construct(Instant::now()))
-        .add_bundle(antigen_wgpu::BufferDataBundle::new(
-            TotalTimeComponent::construct(0.0),
-            buffer_size_of::<[nalgebra::Matrix4<f32>; 2]>()
-                + buffer_size_of::<nalgebra::Vector4<f32>>() * 2,
-            uniform_entity,
-        ));
+    builder.add_bundle(antigen_wgpu::BufferDataBundle::new(
+        TotalTimeComponent::construct(0.0),
+        buffer_size_of::<[nalgebra::Matrix4<f32>; 2]>()
+            + buffer_size_of::<nalgebra::Vector4<f32>>() * 2,
+        uniform_entity,
+    ));
     builder
 }
 
 fn delta_time_bundle(uniform_entity: Entity) -> EntityBuilder {
     let mut builder = EntityBuilder::new();
-    builder
-        .add(TimestampComponent::construct(Instant::now()))
-        .add_bundle(antigen_wgpu::BufferDataBundle::new(
-            DeltaTimeComponent::construct(1.0 / 60.0),
-            buffer_size_of::<[nalgebra::Matrix4<f32>; 2]>()
-                + buffer_size_of::<nalgebra::Vector4<f32>>() * 2
-                + buffer_size_of::<f32>(),
-            uniform_entity,
-        ));
+    builder.add_bundle(antigen_wgpu::BufferDataBundle::new(
+        DeltaTimeComponent::construct(1.0 / 60.0),
+        buffer_size_of::<[nalgebra::Matrix4<f32>; 2]>()
+            + buffer_size_of::<nalgebra::Vector4<f32>>() * 2
+            + buffer_size_of::<f32>(),
+        uniform_entity,
+    ));
     builder
 }
 
@@ -799,6 +811,7 @@ pub fn assemble(world: &mut World, channel: &WorldChannel) {
     .unwrap();
 
     // Time entities
+    world.insert_resource(Time::default());
     world.spawn(total_time_builder(uniform_entity).build());
     world.spawn(delta_time_bundle(uniform_entity).build());
 
@@ -1079,22 +1092,22 @@ pub fn assemble(world: &mut World, channel: &WorldChannel) {
     world.insert(renderer_entity, bundle).unwrap();
 
     // Insert tagged entities
-    insert_tagged_entity::<Uniform>(world, uniform_entity);
-    insert_tagged_entity::<BeamBuffer>(world, beam_buffer_entity);
-    insert_tagged_entity::<BeamDepthBuffer>(world, beam_depth_buffer_entity);
-    insert_tagged_entity::<BeamMultisample>(world, beam_multisample_entity);
-    insert_tagged_entity::<StorageBuffers>(world, storage_bind_group_entity);
-    insert_tagged_entity::<BeamTriangles>(world, beam_mesh_pass_entity);
-    insert_tagged_entity::<PhosphorRenderer>(world, renderer_entity);
-
-    insert_tagged_entity::<Vertices>(world, vertex_entity);
-    insert_tagged_entity::<TriangleIndices>(world, triangle_index_entity);
-    insert_tagged_entity::<TriangleMeshes>(world, triangle_mesh_entity);
-    insert_tagged_entity::<TriangleMeshInstances>(world, triangle_mesh_instance_entity);
-    insert_tagged_entity::<LineIndices>(world, line_index_entity);
-    insert_tagged_entity::<LineMeshes>(world, line_mesh_entity);
-    insert_tagged_entity::<LineMeshInstances>(world, line_mesh_instance_entity);
-    insert_tagged_entity::<LineInstances>(world, line_instance_entity);
+    insert_tagged_entity::<Uniform>(world, uniform_entity).unwrap();
+    insert_tagged_entity::<BeamBuffer>(world, beam_buffer_entity).unwrap();
+    insert_tagged_entity::<BeamDepthBuffer>(world, beam_depth_buffer_entity).unwrap();
+    insert_tagged_entity::<BeamMultisample>(world, beam_multisample_entity).unwrap();
+    insert_tagged_entity::<StorageBuffers>(world, storage_bind_group_entity).unwrap();
+    insert_tagged_entity::<BeamTriangles>(world, beam_mesh_pass_entity).unwrap();
+    insert_tagged_entity::<PhosphorRenderer>(world, renderer_entity).unwrap();
+
+    insert_tagged_entity::<Vertices>(world, vertex_entity).unwrap();
+    insert_tagged_entity::<TriangleIndices>(world, triangle_index_entity).unwrap();
+    insert_tagged_entity::<TriangleMeshes>(world, triangle_mesh_entity).unwrap();
+    insert_tagged_entity::<TriangleMeshInstances>(world, triangle_mesh_instance_entity).unwrap();
+    insert_tagged_entity::<LineIndices>(world, line_index_entity).unwrap();
+    insert_tagged_entity::<LineMeshes>(world, line_mesh_entity).unwrap();
+    insert_tagged_entity::<LineMeshInstances>(world, line_mesh_instance_entity).unwrap();
+    insert_tagged_entity::<LineInstances>(world, line_instance_entity).unwrap();
 
     // Insert tagged entities on game thread
     channel
@@ -2512,60 +2525,87 @@ impl MapData {
     }
 }
 
-pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
-    fn prepare_schedule(world: &mut World) {
-        assemble_triangle_mesh_instances_system(world);
-        assemble_line_mesh_instances_system(world);
-
-        // parallel
-        {
-            antigen_wgpu::create_shader_modules_system(world);
-            antigen_wgpu::create_buffers_system(world);
-            antigen_wgpu::create_textures_system(world);
-            antigen_wgpu::create_texture_views_system(world);
-            antigen_wgpu::create_samplers_system(world);
-        }
+fn phosphor_prepare_schedule() -> Result<Schedule, ScheduleError> {
+    use antigen_wgpu::schedule::*;
+
+    Schedule::builder()
+        .add(System::exclusive(assemble_triangle_mesh_instances_system))
+        .add(System::exclusive(assemble_line_mesh_instances_system))
+        .add(create_shader_modules())
+        .add(create_buffers())
+        .add(create_textures())
+        .add(create_texture_views().after(CREATE_TEXTURES))
+        .add(create_samplers())
+        .add(buffer_write::<TotalTimeComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write::<DeltaTimeComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write::<PerspectiveMatrixComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write::<OrthographicMatrixComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<VertexDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<TriangleIndexDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<TriangleMeshDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<TriangleMeshInstanceDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<LineVertexDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<LineIndexDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<LineMeshDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<LineMeshInstanceDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write_slice::<LineInstanceDataComponent, _>().after(CREATE_BUFFERS))
+        .add(buffer_write::<PositionComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write::<RotationComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write::<ScaleComponent>().after(CREATE_BUFFERS))
+        .add(buffer_write::<LineMeshIdComponent>().after(CREATE_BUFFERS))
+        .add(System::exclusive(
+            phosphor_update_beam_mesh_draw_count_system,
+        ))
+        .add(System::exclusive(
+            phosphor_update_beam_line_draw_count_system,
+        ))
+        .add(System::exclusive(phosphor_prepare_system))
+        .build()
+}
 
-        //parallel
-        {
-            antigen_wgpu::buffer_write_system::<TotalTimeComponent>(world);
-            antigen_wgpu::buffer_write_system::<DeltaTimeComponent>(world);
-            antigen_wgpu::buffer_write_system::<PerspectiveMatrixComponent>(world);
-            antigen_wgpu::buffer_write_system::<OrthographicMatrixComponent>(world);
-            antigen_wgpu::buffer_write_slice_system::<VertexDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<TriangleIndexDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<TriangleMeshDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<TriangleMeshInstanceDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<LineVertexDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<LineIndexDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<LineMeshDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<LineMeshInstanceDataComponent, _>(world);
-            antigen_wgpu::buffer_write_slice_system::<LineInstanceDataComponent, _>(world);
-            antigen_wgpu::buffer_write_system::<PositionComponent>(world);
-            antigen_wgpu::buffer_write_system::<RotationComponent>(world);
-            antigen_wgpu::buffer_write_system::<ScaleComponent>(world);
-            antigen_wgpu::buffer_write_system::<LineMeshIdComponent>(world);
-        }
-        phosphor_update_beam_mesh_draw_count_system(world);
-        phosphor_update_beam_line_draw_count_system(world);
-        phosphor_prepare_system(world);
-    }
+fn phosphor_render_schedule() -> Result<Schedule, ScheduleError> {
+    Schedule::builder()
+        .add(antigen_core::update_time())
+        .add(
+            System::shared(phosphor_update_total_time_system)
+                .after(antigen_core::UPDATE_TIME)
+                .reads::<Time>()
+                .writes::<Changed<TotalTimeComponent>>(),
+        )
+        .add(
+            System::shared(phosphor_update_delta_time_system)
+                .after(antigen_core::UPDATE_TIME)
+                .reads::<Time>()
+                .writes::<Changed<DeltaTimeComponent>>(),
+        )
+        .add(System::exclusive(phosphor_update_oscilloscopes_system))
+        .add(antigen_wgpu::schedule::create_command_encoders())
+        .add(System::exclusive(|world: &mut World| {
+            antigen_wgpu::validate_render_passes_system(world);
+            antigen_wgpu::validate_compute_passes_system(world);
+        }))
+        .add(System::exclusive(|world: &mut World| {
+            antigen_wgpu::draw_render_passes_system(world);
+        }))
+        .add(System::exclusive(
+            antigen_core::swap_with_system::<TextureViewComponent>,
+        ))
+        .add(System::exclusive(
+            antigen_core::swap_with_system::<BindGroupComponent>,
+        ))
+        .add(System::exclusive(
+            antigen_wgpu::flush_command_encoders_system,
+        ))
+        .add(System::exclusive(antigen_wgpu::device_poll_system(
+            &Maintain::Wait,
+        )))
+        .build()
+}
 
-    fn render_schedule(world: &mut World) {
-        //parallel
-        {
-            phosphor_update_total_time_system(world);
-            phosphor_update_delta_time_system(world);
-        }
-        phosphor_update_oscilloscopes_system(world);
-        antigen_wgpu::create_command_encoders_system(world);
-        antigen_wgpu::draw_render_passes_system(world);
-        antigen_core::swap_with_system::<TextureViewComponent>(world);
-        antigen_core::swap_with_system::<BindGroupComponent>(world);
-        antigen_wgpu::flush_command_encoders_system(world);
-        phosphor_update_timestamp_system(world);
-        antigen_wgpu::device_poll_system(&Maintain::Wait)(world);
-    }
+pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
+    let mut prepare_schedule =
+        phosphor_prepare_schedule().expect("Invalid phosphor prepare schedule");
+    let mut render_schedule = phosphor_render_schedule().expect("Invalid phosphor render schedule");
 
     move |world: &mut World,
           channel: &WorldChannel,
@@ -2575,7 +2615,7 @@ pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoop
         match &event {
             Event::MainEventsCleared => {
                 phosphor_resize_system(world);
-                prepare_schedule(world);
+                prepare_schedule.run(world);
                 phosphor_camera_position_system(world);
             }
             Event::WindowEvent { event, .. } => match event {
@@ -2591,7 +2631,7 @@ pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoop
                 _ => (),
             },
             Event::RedrawEventsCleared => {
-                render_schedule(world);
+                render_schedule.run(world);
             }
             _ => (),
         }
@@ -2605,3 +2645,14 @@ pub fn winit_event_handler<T>(mut f: impl EventLoopHandler<T>) -> impl EventLoop
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phosphor_schedules() {
+        phosphor_prepare_schedule().expect("Invalid phosphor prepare schedule");
+        phosphor_render_schedule().expect("Invalid phosphor render schedule");
+    }
+}
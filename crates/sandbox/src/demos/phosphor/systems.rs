@@ -2,8 +2,8 @@ use std::{sync::atomic::Ordering, time::Instant};
 
 use super::*;
 use antigen_core::{
-    get_named_entities_component, Changed, ChangedTrait, CopyToComponent, Indirect, LazyComponent,
-    NamedEntitiesComponent,
+    observe_changes_or_warn, Changed, ChangedTrait, CopyToComponent, Indirect, LazyComponent,
+    Resources, Time,
 };
 
 use antigen_wgpu::{
@@ -23,12 +23,11 @@ use winit::event::{ElementState, KeyboardInput};
 // Initialize the hello triangle render pipeline
 pub fn phosphor_prepare_system(world: &mut World) {
     // Fetch resources
-    let mut query = world.query::<&DeviceComponent>();
-    let (_, device) = query.into_iter().next().unwrap();
+    let device = world.get_resource::<DeviceComponent>().unwrap();
 
     let mut query = world.query::<&PhosphorRenderer>();
     for (entity, _) in query.into_iter() {
-        phosphor_prepare(world, entity, device);
+        phosphor_prepare(world, entity, &device);
     }
 }
 
@@ -239,7 +238,7 @@ pub fn phosphor_prepare(world: &World, entity: Entity, device: &DeviceComponent)
     let mut query = world
         .query_one::<&Indirect<&SurfaceConfigurationComponent>>(entity)
         .unwrap();
-    let mut query = query.get().unwrap().get(world);
+    let mut query = query.get().unwrap().get(world).ok()?;
     let surface_config = query.get().unwrap();
 
     let mut query = world
@@ -410,30 +409,37 @@ pub fn phosphor_prepare(world: &World, entity: Entity, device: &DeviceComponent)
 }
 
 // Game tick update
-pub fn phosphor_update_total_time_system(world: &mut World) {
-    for (_, (start_time, total_time)) in
-        world.query_mut::<(&StartTimeComponent, &mut Changed<TotalTimeComponent>)>()
+pub fn phosphor_update_total_time_system(world: &World) {
+    let time = if let Ok(time) = world.get_resource::<Time>() {
+        time
+    } else {
+        return;
+    };
+
+    for (_, total_time) in world
+        .query::<&mut Changed<TotalTimeComponent>>()
+        .into_iter()
     {
-        ***total_time = Instant::now().duration_since(**start_time).as_secs_f32();
+        ***total_time = time.elapsed_seconds();
         println!("Total time: {:#?}", ***total_time);
-        total_time.set_changed(true);
+        total_time.mark_changed();
     }
 }
 
-pub fn phosphor_update_delta_time_system(world: &mut World) {
-    for (_, (timestamp, delta_time)) in
-        world.query_mut::<(&TimestampComponent, &mut Changed<DeltaTimeComponent>)>()
+pub fn phosphor_update_delta_time_system(world: &World) {
+    let time = if let Ok(time) = world.get_resource::<Time>() {
+        time
+    } else {
+        return;
+    };
+
+    for (_, delta_time) in world
+        .query::<&mut Changed<DeltaTimeComponent>>()
+        .into_iter()
     {
-        let timestamp = **timestamp;
-        ***delta_time = Instant::now().duration_since(timestamp).as_secs_f32();
+        ***delta_time = time.delta_seconds();
         println!("Delta time: {:#?}", ***delta_time);
-        delta_time.set_changed(true);
-    }
-}
-
-pub fn phosphor_update_timestamp_system(world: &mut World) {
-    for (_, timestamp) in world.query_mut::<&mut TimestampComponent>() {
-        **timestamp = Instant::now();
+        delta_time.mark_changed();
     }
 }
 
@@ -442,7 +448,7 @@ pub fn phosphor_update_timers_system(world: &mut World) {
         let now = Instant::now();
         if now.duration_since(timer.timestamp) > timer.duration {
             timer.timestamp = now;
-            timer.set_changed(true);
+            timer.mark_changed();
         }
     }
 }
@@ -474,20 +480,30 @@ pub fn phosphor_update_oscilloscopes_system(world: &mut World) {
         last.position[1] = fy;
         last.position[2] = fz;
 
-        vertex_data.set_changed(true);
+        vertex_data.mark_changed();
     }
 }
 
+enum PhosphorResize {}
+
 pub fn phosphor_resize_system(world: &mut World) {
+    let since = match observe_changes_or_warn::<PhosphorResize>(world, "phosphor resize") {
+        Some(since) => since,
+        None => return,
+    };
+
     let mut query = world
         .query::<&Indirect<&SurfaceConfigurationComponent>>()
         .with::<PhosphorRenderer>();
     let (_, indirect) = query.into_iter().next().unwrap();
 
-    let mut query = indirect.get(world);
+    let mut query = match indirect.get(world) {
+        Ok(query) => query,
+        Err(_) => return,
+    };
     let surface_config = query.get().unwrap();
 
-    if !surface_config.get_changed() {
+    if !surface_config.is_changed_since(since) {
         return;
     }
 
@@ -564,17 +580,17 @@ pub fn phosphor_resize_system(world: &mut World) {
     phosphor_front_desc.size = extent;
     phosphor_back_desc.size = extent;
 
-    beam_buffer_desc.set_changed(true);
-    beam_depth_desc.set_changed(true);
-    beam_multisample_desc.set_changed(true);
-    phosphor_front_desc.set_changed(true);
-    phosphor_back_desc.set_changed(true);
+    beam_buffer_desc.mark_changed();
+    beam_depth_desc.mark_changed();
+    beam_multisample_desc.mark_changed();
+    phosphor_front_desc.mark_changed();
+    phosphor_back_desc.mark_changed();
 
-    beam_buffer_view_desc.set_changed(true);
-    beam_depth_view_desc.set_changed(true);
-    beam_multisample_view_desc.set_changed(true);
-    phosphor_front_view_desc.set_changed(true);
-    phosphor_back_view_desc.set_changed(true);
+    beam_buffer_view_desc.mark_changed();
+    beam_depth_view_desc.mark_changed();
+    beam_multisample_view_desc.mark_changed();
+    phosphor_front_view_desc.mark_changed();
+    phosphor_back_view_desc.mark_changed();
 
     front_bind_group.set_pending();
     back_bind_group.set_pending();
@@ -582,10 +598,10 @@ pub fn phosphor_resize_system(world: &mut World) {
     let aspect = surface_config.width as f32 / surface_config.height as f32;
 
     ***perspective_matrix = super::perspective_matrix(aspect, NEAR_PLANE);
-    perspective_matrix.set_changed(true);
+    perspective_matrix.mark_changed();
 
     ***orthographic_matrix = super::orthographic_matrix(aspect, 200.0);
-    orthographic_matrix.set_changed(true);
+    orthographic_matrix.mark_changed();
 }
 
 pub fn phosphor_mouse_moved_system(world: &mut World, (delta_x, delta_y): (f64, f64)) {
@@ -602,7 +618,7 @@ pub fn phosphor_mouse_moved_system(world: &mut World, (delta_x, delta_y): (f64,
     let quat = pitch * yaw;
 
     ***rotation = quat.into();
-    rotation.set_changed(true);
+    rotation.mark_changed();
 }
 
 pub fn phosphor_key_event_system(world: &mut World, key_event: KeyboardInput) {
@@ -656,7 +672,7 @@ pub fn phosphor_camera_position_system(world: &mut World) {
 
     ***position += rotation.conjugate() * delta;
 
-    position.set_changed(true);
+    position.mark_changed();
 }
 
 pub fn phosphor_update_beam_mesh_draw_count_system(world: &mut World) {
@@ -671,7 +687,7 @@ pub fn phosphor_update_beam_mesh_draw_count_system(world: &mut World) {
 
     for (i, (_, triangle_mesh_data)) in query.into_iter().enumerate() {
         triangle_mesh_data[0].instance_count = mesh_instance_counts.read()[i] as u32;
-        triangle_mesh_data.set_changed(true);
+        triangle_mesh_data.mark_changed();
     }
 }
 
@@ -888,8 +904,7 @@ pub fn movers_rotation_system(world: &mut World) {
 }
 
 pub fn intersection_event_output_system(world: &mut World) {
-    let mut query = world.query::<&antigen_rapier3d::EventCollector>();
-    for (_, event_collector) in query.into_iter() {
+    if let Ok(event_collector) = world.get_resource::<antigen_rapier3d::EventCollector>() {
         for intersection in event_collector.intersection_events().iter() {
             // Find the entity corresponding to this collider
             let mut query =
@@ -921,7 +936,7 @@ pub fn movers_event_input_system(world: &mut World) {
         .query_mut::<(&mut MoverEventInputComponent, &mut MoverOpenComponent)>()
         .into_iter()
     {
-        for event in events.drain(..) {
+        for event in events.drain() {
             match event {
                 MoverEvent::Open => **mover_open = true,
                 MoverEvent::Close => **mover_open = false,
@@ -929,64 +944,3 @@ pub fn movers_event_input_system(world: &mut World) {
         }
     }
 }
-
-pub fn event_dispatch_system<T>(world: &mut World)
-where
-    T: std::fmt::Debug + Clone + Send + Sync + 'static,
-{
-    let named_entities = get_named_entities_component(world).unwrap();
-
-    let mut query = world.query::<(&EventTargetComponent<T>, &mut EventOutputComponent<T>)>();
-    for (_, (event_target, event_output)) in query.into_iter() {
-        let targets = named_entities
-            .get(&**event_target)
-            .unwrap_or_else(|| panic!("No event target with name {}", **event_target));
-
-        for target in targets {
-            let mut query = world
-                .query_one::<&mut EventInputComponent<T>>(*target)
-                .unwrap();
-            let event_input = query.get().unwrap();
-
-            event_input.extend(event_output.iter().cloned());
-        }
-    }
-}
-
-pub fn event_transform_system<I, O, F>(world: &mut World, mut f: F)
-where
-    I: Send + Sync + 'static,
-    O: Send + Sync + 'static,
-    F: FnMut(I) -> O,
-{
-    for (_, (input, output)) in world
-        .query_mut::<(&mut EventInputComponent<I>, &mut EventOutputComponent<O>)>()
-        .with::<EventTransformComponent<I, O>>()
-        .into_iter()
-    {
-        for event in input.drain(..) {
-            output.push(f(event))
-        }
-    }
-}
-
-pub fn clear_event_input_system<T>(world: &mut World)
-where
-    T: Send + Sync + 'static,
-{
-    for (_, input) in world.query_mut::<&mut EventInputComponent<T>>().into_iter() {
-        input.clear()
-    }
-}
-
-pub fn clear_event_output_system<T>(world: &mut World)
-where
-    T: Send + Sync + 'static,
-{
-    for (_, output) in world
-        .query_mut::<&mut EventOutputComponent<T>>()
-        .into_iter()
-    {
-        output.clear()
-    }
-}
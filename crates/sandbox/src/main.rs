@@ -252,9 +252,9 @@
 mod demos;
 
 use antigen_core::{
-    receive_messages, send_clone_query, try_receive_messages, NamedEntitiesComponent,
-    PositionComponent, RotationComponent, ScaleComponent, TaggedEntitiesComponent, WorldChannel,
-    WorldExchange,
+    receive_messages_timeout, run_until_shutdown, send_clone_query, send_clone_resources,
+    try_receive_messages, NamedEntitiesComponent, PositionComponent, Resources, RotationComponent,
+    ScaleComponent, TaggedEntitiesComponent, WorldChannel, WorldExchange,
 };
 use antigen_wgpu::{
     wgpu::DeviceDescriptor, AdapterComponent, DeviceComponent, InstanceComponent, QueueComponent,
@@ -279,7 +279,7 @@ enum Render {}
 enum Filesystem {}
 
 fn main() {
-    //tracing_subscriber::fmt::fmt().pretty().init();
+    tracing_subscriber::fmt::fmt().with_thread_names(true).init();
 
     // Create world exchange
     let mut exchange = WorldExchange::default();
@@ -290,7 +290,7 @@ fn main() {
     let render_channel = exchange.create_channel::<Render>();
 
     // Spawn exchange into its own thread
-    exchange.spawn();
+    let exchange = exchange.spawn();
 
     // Create worlds
     let fs_world = World::new();
@@ -298,8 +298,8 @@ fn main() {
     let mut render_world = World::new();
 
     // Setup game world
-    game_world.spawn((TaggedEntitiesComponent::default(),));
-    game_world.spawn((NamedEntitiesComponent::default(),));
+    game_world.insert_resource(TaggedEntitiesComponent::default());
+    game_world.insert_resource(NamedEntitiesComponent::default());
 
     let mut builder = EntityBuilder::new();
     builder.add(demos::phosphor::SharedShapes);
@@ -307,10 +307,10 @@ fn main() {
     game_world.spawn(builder.build());
 
     // Setup render world
-    render_world.spawn((TaggedEntitiesComponent::default(),));
-    render_world.spawn(antigen_winit::BackendBundle::default());
+    render_world.insert_resource(TaggedEntitiesComponent::default());
+    render_world.insert_resources(antigen_winit::BackendBundle::default());
 
-    let wgpu_backend_entity = render_world.spawn(antigen_wgpu::BackendBundle::from_env(
+    render_world.insert_resources(antigen_wgpu::BackendBundle::from_env(
         &DeviceDescriptor {
             label: Some("Device"),
             features: Default::default(),
@@ -349,8 +349,8 @@ fn main() {
     >(line_mesh_ids_entity)((&mut render_world, &render_channel))
     .unwrap();
 
-    // Clone WGPU backend components to game thread
-    send_clone_query::<
+    // Clone WGPU backend resources to game thread
+    send_clone_resources::<
         (
             &InstanceComponent,
             &AdapterComponent,
@@ -358,12 +358,12 @@ fn main() {
             &QueueComponent,
         ),
         Game,
-    >(wgpu_backend_entity)((&mut render_world, &render_channel))
+    >()((&mut render_world, &render_channel))
     .unwrap();
 
     // Spawn filesystem and game threads
-    spawn_world::<Filesystem, _, _>(fs_thread(fs_world, fs_channel));
-    spawn_world::<Game, _, _>(game_thread(game_world, game_channel));
+    let fs_thread = spawn_world::<Filesystem, _, _>(fs_thread(fs_world, fs_channel));
+    let game_thread = spawn_world::<Game, _, _>(game_thread(game_world, game_channel));
 
     // Assemble phosphor renderer
     demos::phosphor::assemble(&mut render_world, &render_channel);
@@ -374,6 +374,12 @@ fn main() {
         render_channel,
         antigen_winit::winit_event_handler(antigen_wgpu::winit_event_handler(
             demos::phosphor::winit_event_handler(render_thread(
+                move || {
+                    // Stop routing, notify other worlds, and wait for them to finish
+                    exchange.shutdown().expect("Exchange thread panicked");
+                    fs_thread.join().expect("Filesystem thread panicked");
+                    game_thread.join().expect("Game thread panicked");
+                },
                 antigen_winit::winit_event_terminator(),
             )),
         )),
@@ -392,62 +398,76 @@ where
         .unwrap()
 }
 
-/// Runs `f` at  `duration` intervals using a spin-lock for timing
-fn spin_loop<F: FnMut()>(duration: Duration, mut f: F) -> ! {
-    let mut ts = Instant::now();
-    loop {
-        f();
-        while Instant::now().duration_since(ts) < duration {
-            std::hint::spin_loop();
-        }
-        ts = Instant::now();
+/// Blocks until `duration` has passed since `ts` using a spin-lock for timing, then resets `ts`
+fn spin_wait(duration: Duration, ts: &mut Instant) {
+    while Instant::now().duration_since(*ts) < duration {
+        std::hint::spin_loop();
     }
+    *ts = Instant::now();
 }
 
 /// Filesystem thread
 fn fs_thread(mut world: World, channel: WorldChannel) -> impl FnMut() {
-    move || loop {
-        receive_messages(&mut world, &channel).expect("Error receiving message");
+    move || {
+        // Wake periodically to poll loaded files for changes
+        run_until_shutdown(&mut world, &channel, |world, channel| {
+            receive_messages_timeout(world, channel, Duration::from_millis(100))?;
+            antigen_fs::file_watch_system(world, channel);
+            Ok(())
+        })
+        .expect("Error receiving message");
     }
 }
 
 /// Game thread
 fn game_thread(mut world: World, channel: WorldChannel) -> impl FnMut() {
     // Create the physics backend
-    world.spawn(physics_backend_builder(nalgebra::Vector3::new(0.0, -98.1, 0.0)).build());
+    world.insert_resources(
+        physics_backend_builder(nalgebra::Vector3::new(0.0, -98.1, 0.0)).build(),
+    );
+    antigen_core::init_observed_ticks(&mut world);
+
+    // Run physics at a fixed rate, independent of the game thread tick
+    world.insert_resources((
+        antigen_core::Time::default(),
+        antigen_core::FixedTime::from_hz(60.0),
+    ));
 
     move || {
-        spin_loop(GAME_THREAD_TICK, || {
-            try_receive_messages(&mut world, &channel).expect("Error handling message");
+        let mut ts = Instant::now();
+        run_until_shutdown(&mut world, &channel, |world, channel| {
+            try_receive_messages(world, channel)?;
+
+            antigen_core::update_time_system(world);
 
             // Preparation systems
-            demos::phosphor::assemble_triangle_mesh_instances_system(&mut world);
-            demos::phosphor::assemble_line_mesh_instances_system(&mut world);
+            demos::phosphor::assemble_triangle_mesh_instances_system(world);
+            demos::phosphor::assemble_line_mesh_instances_system(world);
 
-            antigen_rapier3d::insert_colliders_system(&mut world);
-            antigen_rapier3d::insert_rigid_bodies_system(&mut world);
+            antigen_rapier3d::insert_colliders_system(world);
+            antigen_rapier3d::insert_rigid_bodies_system(world);
 
-            antigen_core::insert_named_entities_system(&mut world);
+            antigen_core::named_entities_maintenance_system(world);
 
             // Entity transform systems
-            demos::phosphor::movers_position_system(&mut world);
-            demos::phosphor::movers_rotation_system(&mut world);
+            demos::phosphor::movers_position_system(world);
+            demos::phosphor::movers_rotation_system(world);
 
-            // Write component transforms to physics system
-            antigen_rapier3d::write_rigid_body_isometries_system(&mut world);
-
-            // Step physics
-            antigen_rapier3d::step_physics_system(&mut world);
+            // Write component transforms to physics system and step physics
+            antigen_core::run_fixed_steps(world, |world| {
+                antigen_rapier3d::write_rigid_body_isometries_system(world);
+                antigen_rapier3d::step_physics_system(world);
+            });
 
             // Event output
-            demos::phosphor::intersection_event_output_system(&mut world);
+            demos::phosphor::intersection_event_output_system(world);
 
             // Intersection event dispatch
-            demos::phosphor::event_dispatch_system::<IntersectionEvent>(&mut world);
+            antigen_core::event_dispatch_system::<IntersectionEvent>(world);
 
             // Event transformation
-            demos::phosphor::event_transform_system::<IntersectionEvent, MoverEvent, _>(
-                &mut world,
+            antigen_core::event_transform_system::<IntersectionEvent, MoverEvent, _>(
+                world,
                 |intersection| {
                     if intersection.intersecting {
                         MoverEvent::Close
@@ -458,55 +478,61 @@ fn game_thread(mut world: World, channel: WorldChannel) -> impl FnMut() {
             );
 
             // Mover event dispatch
-            demos::phosphor::event_dispatch_system::<MoverEvent>(&mut world);
+            antigen_core::event_dispatch_system::<MoverEvent>(world);
 
             // Event input
-            demos::phosphor::movers_event_input_system(&mut world);
-
-            // Event clear
-            demos::phosphor::clear_event_input_system::<IntersectionEvent>(&mut world);
-            demos::phosphor::clear_event_input_system::<MoverEvent>(&mut world);
+            demos::phosphor::movers_event_input_system(world);
 
-            demos::phosphor::clear_event_output_system::<IntersectionEvent>(&mut world);
-            demos::phosphor::clear_event_output_system::<MoverEvent>(&mut world);
+            // Event update
+            antigen_core::update_events_system::<IntersectionEvent>(world);
+            antigen_core::update_events_system::<MoverEvent>(world);
 
-            antigen_rapier3d::clear_physics_event_collector_system(&mut world);
+            antigen_rapier3d::clear_physics_event_collector_system(world);
 
             // Read physics transforms back into components
-            antigen_rapier3d::read_back_rigid_body_isometries_system(&mut world);
+            antigen_rapier3d::read_back_rigid_body_isometries_system(world);
 
             // Copy transform components to triangle mesh instances
-            antigen_core::copy_to_system::<TriangleMeshInstance, PositionComponent>(&mut world);
-            antigen_core::copy_to_system::<TriangleMeshInstance, RotationComponent>(&mut world);
-            antigen_core::copy_to_system::<TriangleMeshInstance, ScaleComponent>(&mut world);
+            antigen_core::copy_to_system::<TriangleMeshInstance, PositionComponent>(world);
+            antigen_core::copy_to_system::<TriangleMeshInstance, RotationComponent>(world);
+            antigen_core::copy_to_system::<TriangleMeshInstance, ScaleComponent>(world);
 
             // Copy transform components to line mesh instances
-            antigen_core::copy_to_system::<LineMeshInstance, PositionComponent>(&mut world);
-            antigen_core::copy_to_system::<LineMeshInstance, RotationComponent>(&mut world);
-            antigen_core::copy_to_system::<LineMeshInstance, ScaleComponent>(&mut world);
+            antigen_core::copy_to_system::<LineMeshInstance, PositionComponent>(world);
+            antigen_core::copy_to_system::<LineMeshInstance, RotationComponent>(world);
+            antigen_core::copy_to_system::<LineMeshInstance, ScaleComponent>(world);
 
             // Write buffers to GPU
             antigen_wgpu::buffer_write_slice_system::<
                 demos::phosphor::TriangleMeshInstanceDataComponent,
                 _,
-            >(&mut world);
+            >(world);
             antigen_wgpu::buffer_write_slice_system::<
                 demos::phosphor::LineMeshInstanceDataComponent,
                 _,
-            >(&mut world);
+            >(world);
             antigen_wgpu::buffer_write_slice_system::<demos::phosphor::LineInstanceDataComponent, _>(
-                &mut world,
+                world,
             );
-            antigen_wgpu::buffer_write_system::<antigen_core::PositionComponent>(&mut world);
-            antigen_wgpu::buffer_write_system::<antigen_core::RotationComponent>(&mut world);
-            antigen_wgpu::buffer_write_system::<antigen_core::ScaleComponent>(&mut world);
-            antigen_wgpu::buffer_write_system::<demos::phosphor::LineMeshIdComponent>(&mut world);
+            antigen_wgpu::buffer_write_system::<antigen_core::PositionComponent>(world);
+            antigen_wgpu::buffer_write_system::<antigen_core::RotationComponent>(world);
+            antigen_wgpu::buffer_write_system::<antigen_core::ScaleComponent>(world);
+            antigen_wgpu::buffer_write_system::<demos::phosphor::LineMeshIdComponent>(world);
+
+            spin_wait(GAME_THREAD_TICK, &mut ts);
+            Ok(())
         })
+        .expect("Error handling message");
     }
 }
 
 /// Render thread
-pub fn render_thread<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl EventLoopHandler<T> {
+pub fn render_thread<T: Clone>(
+    on_exit: impl FnOnce() + 'static,
+    mut f: impl EventLoopHandler<T>,
+) -> impl EventLoopHandler<T> {
+    let mut on_exit = Some(on_exit);
+
     move |world: &mut World,
           channel: &WorldChannel,
           event: Event<'static, T>,
@@ -518,6 +544,17 @@ pub fn render_thread<T: Clone>(mut f: impl EventLoopHandler<T>) -> impl EventLoo
             winit::event::Event::MainEventsCleared => {
                 println!("Main events cleared");
             }
+            winit::event::Event::WindowEvent {
+                event: winit::event::WindowEvent::CloseRequested,
+                ..
+            } => {
+                *control_flow = ControlFlow::Exit;
+            }
+            winit::event::Event::LoopDestroyed => {
+                if let Some(on_exit) = on_exit.take() {
+                    on_exit();
+                }
+            }
             _ => (),
         }
 
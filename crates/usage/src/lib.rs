@@ -76,7 +76,7 @@
 //! For cases where implementing over `Usage` is unavoidable,
 //! such as compatibility with certain `std` traits or those from commonly-used crates,
 //! feel free to send a pull request with the new functionality gated behind a feature flag
-//! as per the existing `rayon` and `bytemuck` implementations.
+//! as per the existing `rayon`, `bytemuck` and `serde` implementations.
 //!
 
 mod as_usage;
@@ -253,6 +253,30 @@ mod bytemuck_impl {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl<U, T> Serialize for Usage<U, T>
+    where
+        T: Serialize,
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.data.serialize(serializer)
+        }
+    }
+
+    impl<'de, U, T> Deserialize<'de> for Usage<U, T>
+    where
+        T: Deserialize<'de>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            T::deserialize(deserializer).map(Usage::from)
+        }
+    }
+}
+
 // Data access traits
 impl<U, T> Borrow<T> for Usage<U, T> {
     fn borrow(&self) -> &T {